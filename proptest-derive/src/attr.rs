@@ -25,7 +25,7 @@ use crate::util;
 #[derive(Clone)]
 pub struct ParsedAttributes {
     /// If we've been ordered to skip this item.
-    /// This is only valid for enum variants.
+    /// This is only valid for enum variants and fields.
     pub skip: bool,
     /// The potential weight assigned to an enum variant.
     /// This must be `None` for things that are not enum variants.
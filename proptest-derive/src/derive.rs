@@ -211,7 +211,7 @@ fn derive_product_has_params(
     fields
         .into_iter()
         .try_fold(StratAcc::new(len), |acc, field| {
-            let attrs = attr::parse_attributes(ctx, &field.attrs)?;
+            let attrs = parse_field_attributes(ctx, &field, item)?;
 
             // Deny attributes that are only for enum variants:
             error::if_enum_attrs_present(ctx, &attrs, item);
@@ -230,6 +230,29 @@ fn derive_product_has_params(
         .map(|acc| acc.finish(closure))
 }
 
+/// Parse the attributes of a field in a product. A skipped field is always
+/// `Default::default()`, so `#[proptest(skip)]` is turned into the value
+/// mode here and nothing else may be specified alongside it.
+fn parse_field_attributes(
+    ctx: Ctx,
+    field: &Field,
+    item: &str,
+) -> DeriveResult<ParsedAttributes> {
+    let mut attrs = attr::parse_attributes(ctx, &field.attrs)?;
+    if attrs.skip {
+        if attrs.strategy.is_set()
+            || attrs.params.is_set()
+            || !attrs.filter.is_empty()
+        {
+            error::skipped_field_has_attrs(ctx, item);
+        }
+        attrs.skip = false;
+        attrs.strategy =
+            StratMode::Value(parse_quote!(::core::default::Default::default()));
+    }
+    Ok(attrs)
+}
+
 /// Determine strategy using "Default" semantics for a product.
 fn product_handle_default_params(
     ut: &mut UseTracker,
@@ -266,7 +289,7 @@ fn derive_product_no_params(
     // and let the caller of this function determine what to do with it.
     let acc = PartsAcc::new(fields.len());
     fields.into_iter().try_fold(acc, |mut acc, field| {
-        let attrs = attr::parse_attributes(ctx, &field.attrs)?;
+        let attrs = parse_field_attributes(ctx, &field, item)?;
 
         // Deny attributes that are only for enum variants:
         error::if_enum_attrs_present(ctx, &attrs, item);
@@ -335,11 +335,12 @@ error!(
 );
 
 // Happens when `#[proptest(skip)]` is specified on an `item` that does
-// not support skipping. Only enum variants support skipping.
+// not support skipping. Only enum variants and fields support skipping.
 error!(
     illegal_skip(item: &str),
     E0008,
-    "A {} can't be `#[proptest(skip)]`ed, only enum variants can be skipped.",
+    "A {} can't be `#[proptest(skip)]`ed, only enum variants and fields can \
+     be skipped.",
     item
 );
 
@@ -677,3 +678,18 @@ error!(
      since `params` cannot be used in `<string>`.",
     item
 );
+
+// Happens when `#[proptest(skip)]` is set on a field together with
+// modifiers that control how the field is generated, which a skipped field
+// never is since it is always `Default::default()`.
+error!(
+    skipped_field_has_attrs(item: &str),
+    E0036,
+    "A {} has been skipped, so it is always `Default::default()`. \
+     Setting `#[proptest(strategy = \"<expr>\")]`, \
+     `#[proptest(value = \"<expr>\")]`, `#[proptest(regex = \"<string>\")]`, \
+     `#[proptest(params(<type>))]`, `#[proptest(no_params)]` or \
+     `#[proptest(filter = \"<expr>\")]` on it is meaningless and is not \
+     allowed.",
+    item
+);
@@ -0,0 +1,56 @@
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate proptest_derive;
+use proptest_derive::Arbitrary;
+
+fn main() {}
+
+#[derive(Debug, Arbitrary)] //~ ERROR: [proptest_derive, E0036]
+struct T0 {
+    #[proptest(skip, value = "42")]
+    field: u8
+}
+
+#[derive(Debug, Arbitrary)] //~ ERROR: [proptest_derive, E0036]
+struct T1(
+    #[proptest(skip, strategy = "0..10u8")]
+    u8
+);
+
+#[derive(Debug, Arbitrary)] //~ ERROR: [proptest_derive, E0036]
+struct T2 {
+    #[proptest(skip, regex = "a+")]
+    field: String
+}
+
+#[derive(Debug, Arbitrary)] //~ ERROR: [proptest_derive, E0036]
+struct T3 {
+    #[proptest(skip, no_params)]
+    field: u8
+}
+
+#[derive(Debug, Arbitrary)] //~ ERROR: [proptest_derive, E0036]
+struct T4 {
+    #[proptest(skip, filter = "|x| *x > 0")]
+    field: u8
+}
+
+#[derive(Debug, Arbitrary)] //~ ERROR: [proptest_derive, E0036]
+enum T5 {
+    V0 {
+        #[proptest(skip, value = "42")]
+        field: u8
+    }
+}
+
+#[derive(Debug, Arbitrary)] //~ ERROR: [proptest_derive, E0036]
+enum T6 {
+    V0(#[proptest(skip, strategy = "0..10u8")] u8)
+}
@@ -30,6 +30,85 @@ enum Ty2 {
     V4,
 }
 
+/// A cache which is not `Arbitrary`, but is `Default`.
+#[derive(Debug, Default, PartialEq)]
+struct Cache(Vec<u32>);
+
+#[derive(Debug, Arbitrary)]
+struct Ty3 {
+    key: u32,
+    #[proptest(skip)]
+    cache: Cache,
+}
+
+#[derive(Debug, Arbitrary)]
+struct Ty4(
+    #[proptest(skip)] Cache,
+    #[proptest(strategy = "1..10u8")] u8,
+);
+
+#[derive(Debug, Arbitrary)]
+#[proptest(no_params)]
+struct Ty5 {
+    #[proptest(skip)]
+    cache: Cache,
+    key: u32,
+}
+
+#[derive(Debug, Arbitrary)]
+enum Ty6 {
+    V1 {
+        key: u32,
+        #[proptest(skip)]
+        cache: Cache,
+    },
+    V2(#[proptest(skip)] Cache),
+}
+
+proptest! {
+    #[test]
+    fn ty3_cache_always_default(v: Ty3) {
+        prop_assert_eq!(v.cache, Cache::default());
+    }
+
+    #[test]
+    fn ty4_cache_always_default(v: Ty4) {
+        prop_assert_eq!(v.0, Cache::default());
+        prop_assert!(v.1 >= 1 && v.1 < 10);
+    }
+
+    #[test]
+    fn ty5_cache_always_default(v: Ty5) {
+        prop_assert_eq!(v.cache, Cache::default());
+    }
+
+    #[test]
+    fn ty6_cache_always_default(v: Ty6) {
+        match v {
+            Ty6::V1 { cache, .. } | Ty6::V2(cache) => {
+                prop_assert_eq!(cache, Cache::default())
+            }
+        }
+    }
+}
+
+#[test]
+fn skipped_field_is_not_shrunk() {
+    use proptest::strategy::{Strategy, ValueTree};
+    use proptest::test_runner::TestRunner;
+
+    let mut runner = TestRunner::deterministic();
+    for _ in 0..64 {
+        let mut tree = Ty3::arbitrary().new_tree(&mut runner).unwrap();
+        while tree.simplify() {
+            assert_eq!(Cache::default(), tree.current().cache);
+        }
+        let minimal = tree.current();
+        assert_eq!(0, minimal.key);
+        assert_eq!(Cache::default(), minimal.cache);
+    }
+}
+
 proptest! {
     #[test]
     fn ty1_always_v1(v: Ty1) {
@@ -48,4 +127,8 @@ fn asserting_arbitrary() {
 
     assert_arbitrary::<Ty1>();
     assert_arbitrary::<Ty2>();
+    assert_arbitrary::<Ty3>();
+    assert_arbitrary::<Ty4>();
+    assert_arbitrary::<Ty5>();
+    assert_arbitrary::<Ty6>();
 }
@@ -0,0 +1,274 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Strategies for generating sequences of commands, such as for testing
+//! transactional stores.
+
+use proptest::bool::Weighted;
+use proptest::num::sample_uniform_incl;
+use proptest::std_facade::fmt::Debug;
+use proptest::std_facade::Vec;
+use proptest::strategy::{NewTree, Strategy, ValueTree};
+use proptest::test_runner::TestRunner;
+
+/// A step in a sequence generated by [`transactional`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TransactionStep<C> {
+    /// Run a command from the base sequence.
+    Command(C),
+    /// Create a savepoint, which the matching `Rollback` returns to.
+    Savepoint,
+    /// Roll back to the most recent savepoint which has not been rolled
+    /// back yet.
+    Rollback,
+}
+
+/// Strategy to create command sequences with balanced savepoints and
+/// rollbacks.
+///
+/// Created by the [`transactional`] function in the same module.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct Transactional<S> {
+    base_commands: S,
+    savepoint: Weighted,
+}
+
+/// Create a strategy to generate the command sequences from `base_commands`
+/// interspersed with savepoints and rollbacks, e.g., for testing a
+/// transactional store against a model.
+///
+/// A savepoint is inserted before each command, and at the end of the
+/// sequence, with probability `savepoint_prob`, and the matching rollback is
+/// placed at a random later point. The markers are balanced like brackets:
+/// every `Rollback` is preceded by a `Savepoint` it returns to, and every
+/// `Savepoint` is eventually rolled back.
+///
+/// Shrinking first removes savepoints together with their rollbacks, then
+/// shrinks `base_commands`, so the sequence stays balanced throughout.
+///
+/// ## Panics
+///
+/// Panics if `savepoint_prob` is not between 0.0 and 1.0 inclusive.
+pub fn transactional<C, S: Strategy<Value = Vec<C>>>(
+    base_commands: S,
+    savepoint_prob: f64,
+) -> Transactional<S> {
+    assert!(
+        (0.0..=1.0).contains(&savepoint_prob),
+        "savepoint_prob must be between 0.0 and 1.0, got {}",
+        savepoint_prob
+    );
+    Transactional {
+        base_commands,
+        savepoint: proptest::bool::weighted(savepoint_prob),
+    }
+}
+
+impl<C: Debug, S: Strategy<Value = Vec<C>>> Strategy for Transactional<S> {
+    type Tree = TransactionalValueTree<S::Tree>;
+    type Value = Vec<TransactionStep<C>>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let base_commands = self.base_commands.new_tree(runner)?;
+        let len = base_commands.current().len();
+
+        // Positions are the gaps before each command and at the end.
+        let mut pairs = Vec::new();
+        for start in 0..=len {
+            if self.savepoint.new_tree(runner)?.current() {
+                pairs.push((start, sample_uniform_incl(runner, start, len)));
+            }
+        }
+
+        Ok(TransactionalValueTree {
+            base_commands,
+            included_pairs: vec![true; pairs.len()],
+            pairs,
+            shrink: Shrink::DeletePair(0),
+            prev_shrink: None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Shrink {
+    DeletePair(usize),
+    BaseCommands,
+}
+
+/// `ValueTree` corresponding to [`Transactional`].
+#[derive(Clone, Debug)]
+pub struct TransactionalValueTree<T> {
+    base_commands: T,
+    /// The positions of the savepoint and rollback of each pair.
+    pairs: Vec<(usize, usize)>,
+    included_pairs: Vec<bool>,
+    shrink: Shrink,
+    prev_shrink: Option<Shrink>,
+}
+
+impl<C: Debug, T: ValueTree<Value = Vec<C>>> ValueTree
+    for TransactionalValueTree<T>
+{
+    type Value = Vec<TransactionStep<C>>;
+
+    fn current(&self) -> Vec<TransactionStep<C>> {
+        let commands = self.base_commands.current();
+        let len = commands.len();
+
+        // Shrinking `base_commands` may have removed commands, so positions
+        // past the end are moved to the end, where every pair still has its
+        // savepoint before (or at the same position as) its rollback.
+        let (mut rollbacks, mut empty, mut savepoints) =
+            (vec![0; len + 1], vec![0; len + 1], vec![0; len + 1]);
+        for (&(start, end), _) in self
+            .pairs
+            .iter()
+            .zip(&self.included_pairs)
+            .filter(|&(_, &included)| included)
+        {
+            let (start, end) = (start.min(len), end.min(len));
+            if start == end {
+                empty[start] += 1;
+            } else {
+                savepoints[start] += 1;
+                rollbacks[end] += 1;
+            }
+        }
+
+        // At each position, first close the savepoints opened earlier, then
+        // open and immediately close the empty ones, then open the rest, so
+        // every rollback matches an open savepoint.
+        let mut steps = Vec::new();
+        let mut commands = commands.into_iter();
+        for pos in 0..=len {
+            for _ in 0..rollbacks[pos] {
+                steps.push(TransactionStep::Rollback);
+            }
+            for _ in 0..empty[pos] {
+                steps.push(TransactionStep::Savepoint);
+                steps.push(TransactionStep::Rollback);
+            }
+            for _ in 0..savepoints[pos] {
+                steps.push(TransactionStep::Savepoint);
+            }
+            steps.extend(commands.next().map(TransactionStep::Command));
+        }
+        steps
+    }
+
+    fn simplify(&mut self) -> bool {
+        if let Shrink::DeletePair(ix) = self.shrink {
+            if ix < self.pairs.len() {
+                self.included_pairs[ix] = false;
+                self.prev_shrink = Some(self.shrink);
+                self.shrink = Shrink::DeletePair(ix + 1);
+                return true;
+            }
+            self.shrink = Shrink::BaseCommands;
+        }
+
+        if self.base_commands.simplify() {
+            self.prev_shrink = Some(Shrink::BaseCommands);
+            true
+        } else {
+            self.prev_shrink = None;
+            false
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.prev_shrink {
+            None => false,
+            Some(Shrink::DeletePair(ix)) => {
+                // Keep this pair and move on to the next one.
+                self.included_pairs[ix] = true;
+                self.prev_shrink = None;
+                true
+            }
+            Some(Shrink::BaseCommands) => {
+                if self.base_commands.complicate() {
+                    true
+                } else {
+                    self.prev_shrink = None;
+                    false
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use proptest::collection::vec;
+    use proptest::strategy::check_strategy_sanity;
+
+    /// Checks that every rollback matches an earlier savepoint and that no
+    /// savepoint is left open, returning the number of pairs.
+    fn check_balanced(steps: &[TransactionStep<u32>]) -> usize {
+        let (mut open, mut pairs) = (0usize, 0);
+        for step in steps {
+            match step {
+                TransactionStep::Savepoint => open += 1,
+                TransactionStep::Rollback => {
+                    assert!(open > 0, "Unmatched rollback in {:?}", steps);
+                    open -= 1;
+                    pairs += 1;
+                }
+                TransactionStep::Command(_) => {}
+            }
+        }
+        assert_eq!(0, open, "Unmatched savepoint in {:?}", steps);
+        pairs
+    }
+
+    #[test]
+    fn rollbacks_have_savepoints_after_shrinking() {
+        let input = transactional(vec(0..100u32, 0..10), 0.3);
+        let mut runner = TestRunner::deterministic();
+        let mut max_pairs = 0;
+        for _ in 0..256 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            max_pairs = max_pairs.max(check_balanced(&tree.current()));
+
+            // Shrink as the test runner would if rollbacks made a test fail.
+            let has_rollback = |steps: &[TransactionStep<u32>]| {
+                steps.contains(&TransactionStep::Rollback)
+            };
+            if !has_rollback(&tree.current()) {
+                continue;
+            }
+            let mut minimal = tree.current();
+            while tree.simplify() {
+                let mut steps = tree.current();
+                check_balanced(&steps);
+                while !has_rollback(&steps) && tree.complicate() {
+                    steps = tree.current();
+                    check_balanced(&steps);
+                }
+                if has_rollback(&steps) {
+                    minimal = steps;
+                }
+            }
+            assert_eq!(
+                vec![TransactionStep::Savepoint, TransactionStep::Rollback],
+                minimal
+            );
+        }
+        assert!(max_pairs > 1, "Never generated more than one savepoint");
+    }
+
+    #[test]
+    fn transactional_sanity() {
+        check_strategy_sanity(transactional(vec(0..100u32, 0..10), 0.3), None);
+    }
+}
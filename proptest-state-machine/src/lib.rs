@@ -12,6 +12,7 @@
 //! Please refer to the Proptest Book chapter "State Machine testing" to learn
 //! when and how to use this and how it's made.
 
+pub mod command;
 pub mod strategy;
 pub mod test_runner;
 
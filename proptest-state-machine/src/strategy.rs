@@ -850,6 +850,8 @@ mod test {
                 if let Err(TestError::Fail(
                     _,
                     (FailIfLessThan(limit), transitions),
+                    _,
+                    _,
                 )) = result
                 {
                     assert_eq!(transitions.len(), 1, "The minimal failing case should be ");
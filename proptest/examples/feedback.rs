@@ -0,0 +1,124 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Demonstrates using `Config::feedback` to steer generation towards a branch
+//! that pure random generation is unlikely to reach.
+//!
+//! The code under test only fails when four independent values all take a
+//! particular value, which random generation hits about once every 65536
+//! cases. Reporting each newly reached branch as novel lets the runner build
+//! on the inputs which got part of the way there.
+
+use std::any::Any;
+use std::sync::{Arc, Mutex};
+
+use proptest::test_runner::{
+    Config, Feedback, RngAlgorithm, TestCaseError, TestRng, TestRunner,
+};
+
+#[derive(Debug, Default)]
+struct State {
+    /// How many of the nested conditions the last case satisfied.
+    depth: usize,
+    /// Which depths have been reached by any case so far.
+    seen: [bool; 5],
+}
+
+#[derive(Clone, Debug, Default)]
+struct BranchCoverage(Arc<Mutex<State>>);
+
+impl Feedback for BranchCoverage {
+    fn novelty(&mut self) -> u32 {
+        let mut state = self.0.lock().unwrap();
+        let depth = state.depth;
+        if state.seen[depth] {
+            0
+        } else {
+            state.seen[depth] = true;
+            1
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn Feedback> {
+        Box::new(self.clone())
+    }
+
+    fn eq(&self, other: &dyn Feedback) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<Self>()
+            .map_or(false, |x| Arc::ptr_eq(&x.0, &self.0))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// The code under test, which has a bug behind four nested conditions.
+fn code_under_test(input: (u8, u8, u8, u8)) -> usize {
+    let (a, b, c, d) = input;
+    if a == 3 {
+        if b == 5 {
+            if c == 9 {
+                if d == 14 {
+                    return 4;
+                }
+                return 3;
+            }
+            return 2;
+        }
+        return 1;
+    }
+    0
+}
+
+/// Runs the search and returns the number of cases it took to reach the
+/// deepest branch, if it was reached at all.
+fn cases_to_find_bug(use_feedback: bool) -> Option<u32> {
+    let coverage = BranchCoverage::default();
+    let config = Config {
+        cases: 1_000_000,
+        failure_persistence: None,
+        feedback: if use_feedback {
+            Some(Box::new(coverage.clone()))
+        } else {
+            None
+        },
+        ..Config::default()
+    };
+    let mut runner = TestRunner::new_with_rng(
+        config,
+        TestRng::deterministic_rng(RngAlgorithm::ChaCha),
+    );
+
+    let cases = Arc::new(Mutex::new((0u32, None)));
+    let cases2 = Arc::clone(&cases);
+    let _ = runner.run(&(0u8..16, 0u8..16, 0u8..16, 0u8..16), move |input| {
+        let mut cases = cases2.lock().unwrap();
+        cases.0 += 1;
+        let depth = code_under_test(input);
+        coverage.0.lock().unwrap().depth = depth;
+        if depth == 4 {
+            if cases.1.is_none() {
+                cases.1 = Some(cases.0);
+            }
+            return Err(TestCaseError::fail("found the bug"));
+        }
+        Ok(())
+    });
+
+    let found_at = cases.lock().unwrap().1;
+    found_at
+}
+
+fn main() {
+    println!("Without feedback: {:?} cases", cases_to_find_bug(false));
+    println!("With feedback:    {:?} cases", cases_to_find_bug(true));
+}
@@ -97,6 +97,39 @@ mod test {
         range_to_inclusive => RangeToInclusive<usize>
     );
 
+    #[test]
+    fn ranges_are_ordered_while_shrinking() {
+        use crate::arbitrary::any;
+        use crate::strategy::{Strategy, ValueTree};
+        use crate::test_runner::TestRunner;
+        use core::ops::{Range, RangeInclusive};
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut range = any::<Range<i32>>().new_tree(&mut runner).unwrap();
+            loop {
+                let r = range.current();
+                assert!(r.start <= r.end, "{:?}", r);
+                if !range.simplify() {
+                    break;
+                }
+            }
+            assert!(range.current().is_empty());
+
+            let mut range =
+                any::<RangeInclusive<i32>>().new_tree(&mut runner).unwrap();
+            loop {
+                let r = range.current();
+                assert!(r.start() <= r.end(), "{:?}", r);
+                if !range.simplify() {
+                    break;
+                }
+            }
+            let r = range.current();
+            assert_eq!(r.start(), r.end());
+        }
+    }
+
     #[cfg(feature = "unstable")]
     no_panic_test!(
         generator_state => CoroutineState<u32, u64>
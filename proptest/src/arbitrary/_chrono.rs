@@ -0,0 +1,184 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Arbitrary implementations for the `chrono` crate.
+//!
+//! Each implementation takes an optional inclusive range as its parameters,
+//! defaulting to the start of 1970 up to the end of 2100 (UTC). Values are
+//! built by offsetting the start of the range, so every generated value is a
+//! valid date or time, including February 29 in leap years. Leap seconds are
+//! never generated. Shrinking moves towards the start of the range, which by
+//! default is the Unix epoch.
+
+use core::ops::RangeInclusive;
+
+use chrono::{
+    DateTime, Days, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc,
+};
+
+use crate::arbitrary::*;
+use crate::strategy::*;
+
+fn default_dates() -> RangeInclusive<NaiveDate> {
+    NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()
+        ..=NaiveDate::from_ymd_opt(2100, 12, 31).unwrap()
+}
+
+fn default_date_times() -> RangeInclusive<NaiveDateTime> {
+    let (start, end) = default_dates().into_inner();
+    start.and_time(NaiveTime::MIN)
+        ..=end.and_hms_nano_opt(23, 59, 59, 999_999_999).unwrap()
+}
+
+fn date_times_in(
+    range: RangeInclusive<NaiveDateTime>,
+) -> impl Strategy<Value = NaiveDateTime> {
+    let (start, end) = range.into_inner();
+    assert!(start <= end, "Empty date-time range {}..={}", start, end);
+    let span = (end - start).num_seconds() as u64;
+    (0..=span, 0..1_000_000_000u32).prop_map(move |(secs, nanos)| {
+        let value = start
+            + Duration::seconds(secs as i64)
+            + Duration::nanoseconds(nanos.into());
+        value.min(end)
+    })
+}
+
+/// Generates dates in the given range, or 1970-01-01 to 2100-12-31 if
+/// `None`, shrinking towards its start.
+impl Arbitrary for NaiveDate {
+    type Parameters = Option<RangeInclusive<NaiveDate>>;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(range: Self::Parameters) -> Self::Strategy {
+        let (start, end) = range.unwrap_or_else(default_dates).into_inner();
+        assert!(start <= end, "Empty date range {}..={}", start, end);
+        let span = (end - start).num_days() as u64;
+        (0..=span)
+            .prop_map(move |days| start + Days::new(days))
+            .boxed()
+    }
+}
+
+/// Generates date-times in the given range, or from the start of 1970 to the
+/// end of 2100 if `None`, shrinking towards its start.
+impl Arbitrary for NaiveDateTime {
+    type Parameters = Option<RangeInclusive<NaiveDateTime>>;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(range: Self::Parameters) -> Self::Strategy {
+        date_times_in(range.unwrap_or_else(default_date_times)).boxed()
+    }
+}
+
+/// Generates UTC date-times in the given range, or from the Unix epoch to
+/// the end of 2100 if `None`, shrinking towards its start.
+impl Arbitrary for DateTime<Utc> {
+    type Parameters = Option<RangeInclusive<DateTime<Utc>>>;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(range: Self::Parameters) -> Self::Strategy {
+        let range = match range {
+            Some(range) => {
+                let (start, end) = range.into_inner();
+                start.naive_utc()..=end.naive_utc()
+            }
+            None => default_date_times(),
+        };
+        date_times_in(range)
+            .prop_map(|naive| DateTime::from_naive_utc_and_offset(naive, Utc))
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_runner::TestRunner;
+    use chrono::{Datelike, Timelike};
+
+    no_panic_test!(
+        naive_date => NaiveDate,
+        naive_date_time => NaiveDateTime,
+        date_time_utc => DateTime<Utc>
+    );
+
+    fn assert_valid(value: NaiveDateTime) {
+        let date =
+            NaiveDate::from_ymd_opt(value.year(), value.month(), value.day());
+        let time = NaiveTime::from_hms_nano_opt(
+            value.hour(),
+            value.minute(),
+            value.second(),
+            value.nanosecond(),
+        );
+        assert_eq!(Some(value.date()), date, "Invalid date in {}", value);
+        assert_eq!(Some(value.time()), time, "Invalid time in {}", value);
+        assert!(value.nanosecond() < 1_000_000_000, "Leap second {}", value);
+    }
+
+    #[test]
+    fn generated_values_are_valid_and_in_range() {
+        let mut runner = TestRunner::deterministic();
+        let range = default_date_times();
+        let mut leap_days = 0;
+        for _ in 0..4096 {
+            let date = any::<NaiveDate>().new_tree(&mut runner).unwrap();
+            let date = date.current();
+            assert!(default_dates().contains(&date), "{}", date);
+            assert_valid(date.and_time(NaiveTime::MIN));
+            if (date.month(), date.day()) == (2, 29) {
+                assert!(date.leap_year(), "{}", date);
+                leap_days += 1;
+            }
+
+            let naive = any::<NaiveDateTime>().new_tree(&mut runner).unwrap();
+            assert!(range.contains(&naive.current()));
+            assert_valid(naive.current());
+
+            let utc = any::<DateTime<Utc>>().new_tree(&mut runner).unwrap();
+            assert!(range.contains(&utc.current().naive_utc()));
+            assert_valid(utc.current().naive_utc());
+        }
+        assert!(leap_days > 0);
+    }
+
+    #[test]
+    fn shrinks_to_range_start() {
+        let mut runner = TestRunner::deterministic();
+        let epoch = DateTime::<Utc>::UNIX_EPOCH;
+        let start = NaiveDate::from_ymd_opt(2024, 2, 28).unwrap();
+        let end = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let dates = any_with::<NaiveDate>(Some(start..=end));
+        let date_times = any_with::<NaiveDateTime>(Some(
+            start.and_hms_opt(12, 0, 0).unwrap()
+                ..=end.and_hms_opt(12, 0, 0).unwrap(),
+        ));
+        for _ in 0..64 {
+            let mut tree = any::<NaiveDate>().new_tree(&mut runner).unwrap();
+            while tree.simplify() {}
+            assert_eq!(epoch.date_naive(), tree.current());
+
+            let mut tree =
+                any::<DateTime<Utc>>().new_tree(&mut runner).unwrap();
+            while tree.simplify() {}
+            assert_eq!(epoch, tree.current());
+
+            let mut tree = dates.new_tree(&mut runner).unwrap();
+            assert!((start..=end).contains(&tree.current()));
+            while tree.simplify() {}
+            assert_eq!(start, tree.current());
+
+            let mut tree = date_times.new_tree(&mut runner).unwrap();
+            assert_valid(tree.current());
+            while tree.simplify() {}
+            assert_eq!(start.and_hms_opt(12, 0, 0).unwrap(), tree.current());
+        }
+    }
+}
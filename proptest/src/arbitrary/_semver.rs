@@ -0,0 +1,194 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Arbitrary implementations for the `semver` crate.
+
+use semver::{BuildMetadata, Comparator, Op, Prerelease, Version, VersionReq};
+
+use crate::arbitrary::*;
+use crate::collection::vec;
+use crate::option;
+use crate::sample::select;
+use crate::strategy::*;
+
+/// A version number component, usually small but occasionally anywhere in
+/// the range of `u64`. Shrinks towards `0`.
+fn component() -> impl Strategy<Value = u64> {
+    prop_oneof![3 => 0u64..20, 1 => any::<u64>()]
+}
+
+/// A dot-separated list of pre-release identifiers, each either a number
+/// without leading zeroes or an alphanumeric identifier containing at least
+/// one non-digit.
+fn prerelease() -> impl Strategy<Value = Prerelease> {
+    let identifier = prop_oneof![
+        "[a-z][0-9a-z]{0,7}",
+        "0|[1-9][0-9]{0,8}",
+        "[0-9A-Za-z-]{0,4}[A-Za-z-][0-9A-Za-z-]{0,4}",
+    ];
+    vec(identifier, 1..4).prop_map(|identifiers| {
+        Prerelease::new(&identifiers.join("."))
+            .expect("generated invalid pre-release")
+    })
+}
+
+/// A dot-separated list of build metadata identifiers. Unlike pre-release
+/// identifiers, numeric ones may have leading zeroes.
+fn build_metadata() -> impl Strategy<Value = BuildMetadata> {
+    vec("[0-9A-Za-z-]{1,8}", 1..4).prop_map(|identifiers| {
+        BuildMetadata::new(&identifiers.join("."))
+            .expect("generated invalid build metadata")
+    })
+}
+
+/// Generates structurally valid versions, with optional pre-release and build
+/// metadata. Shrinks towards `0.0.0`.
+impl Arbitrary for Version {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            component(),
+            component(),
+            component(),
+            option::of(prerelease()),
+            option::of(build_metadata()),
+        )
+            .prop_map(|(major, minor, patch, pre, build)| Version {
+                major,
+                minor,
+                patch,
+                pre: pre.unwrap_or(Prerelease::EMPTY),
+                build: build.unwrap_or(BuildMetadata::EMPTY),
+            })
+            .boxed()
+    }
+}
+
+const COMPARISON_OPS: &[Op] = &[
+    Op::Caret,
+    Op::Exact,
+    Op::Greater,
+    Op::GreaterEq,
+    Op::Less,
+    Op::LessEq,
+    Op::Tilde,
+];
+
+/// A comparator with any operator other than `Op::Wildcard`. The minor and
+/// patch versions may be omitted, but a pre-release is only included along
+/// with a patch version.
+fn comparator() -> impl Strategy<Value = Comparator> {
+    (
+        select(COMPARISON_OPS),
+        component(),
+        option::of((
+            component(),
+            option::of((component(), option::of(prerelease()))),
+        )),
+    )
+        .prop_map(|(op, major, rest)| {
+            let (minor, patch, pre) = match rest {
+                None => (None, None, None),
+                Some((minor, None)) => (Some(minor), None, None),
+                Some((minor, Some((patch, pre)))) => {
+                    (Some(minor), Some(patch), pre)
+                }
+            };
+            Comparator {
+                op,
+                major,
+                minor,
+                patch,
+                pre: pre.unwrap_or(Prerelease::EMPTY),
+            }
+        })
+}
+
+/// A wildcard comparator such as `1.*` or `1.2.*`.
+fn wildcard() -> impl Strategy<Value = Comparator> {
+    (component(), option::of(component())).prop_map(|(major, minor)| {
+        Comparator {
+            op: Op::Wildcard,
+            major,
+            minor,
+            patch: None,
+            pre: Prerelease::EMPTY,
+        }
+    })
+}
+
+/// Generates structurally valid version requirements of up to three
+/// comparators. A wildcard comparator is only ever generated on its own, as
+/// `semver` requires. Shrinks towards `*`, i.e., no comparators.
+impl Arbitrary for VersionReq {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            4 => vec(comparator(), 0..4),
+            1 => wildcard().prop_map(|comparator| vec![comparator]),
+        ]
+        .prop_map(|comparators| VersionReq { comparators })
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::std_facade::string::ToString;
+    use crate::test_runner::TestRunner;
+
+    no_panic_test!(
+        version => Version,
+        version_req => VersionReq
+    );
+
+    #[test]
+    fn versions_parse_back() {
+        let mut runner = TestRunner::deterministic();
+        let mut with_pre = 0;
+        let mut with_build = 0;
+        for _ in 0..1024 {
+            let version = any::<Version>().new_tree(&mut runner).unwrap();
+            let version = version.current();
+            assert_eq!(version, Version::parse(&version.to_string()).unwrap());
+            with_pre += !version.pre.is_empty() as u32;
+            with_build += !version.build.is_empty() as u32;
+        }
+        assert!(with_pre > 0 && with_build > 0);
+    }
+
+    #[test]
+    fn version_reqs_parse_back() {
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..1024 {
+            let req = any::<VersionReq>().new_tree(&mut runner).unwrap();
+            let req = req.current();
+            assert_eq!(req, VersionReq::parse(&req.to_string()).unwrap());
+        }
+    }
+
+    #[test]
+    fn shrinks_to_zero() {
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut version = any::<Version>().new_tree(&mut runner).unwrap();
+            while version.simplify() {}
+            assert_eq!(Version::new(0, 0, 0), version.current());
+
+            let mut req = any::<VersionReq>().new_tree(&mut runner).unwrap();
+            while req.simplify() {}
+            assert_eq!(VersionReq::STAR, req.current());
+        }
+    }
+}
@@ -24,11 +24,36 @@ impl<A: Arbitrary, const N: usize> Arbitrary for [A; N] {
 
 #[cfg(test)]
 mod test {
+    use crate::arbitrary::any;
+    use crate::strategy::{Strategy, ValueTree};
+    use crate::test_runner::TestRunner;
+
     no_panic_test!(
         array_16 => [u8; 16]
     );
 
+    no_panic_test!(
+        array_37 => [u8; 37]
+    );
+
     no_panic_test!(
         array_1024 => [u8; 1024]
     );
+
+    #[test]
+    fn arrays_of_any_size_shrink_element_wise() {
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut tree = any::<[u8; 37]>().new_tree(&mut runner).unwrap();
+            let initial = tree.current();
+            assert!(tree.simplify());
+            let shrunk = tree.current();
+            // Only the first element which is not already 0 is shrunk.
+            let first = initial.iter().position(|&v| v != 0).unwrap();
+            assert!(shrunk[first] < initial[first]);
+            assert_eq!(initial[first + 1..], shrunk[first + 1..]);
+            while tree.simplify() {}
+            assert_eq!([0u8; 37], tree.current());
+        }
+    }
 }
@@ -42,6 +42,12 @@ mod _alloc;
 #[cfg(feature = "std")]
 mod _std;
 
+#[cfg(feature = "semver")]
+mod _semver;
+
+#[cfg(feature = "chrono")]
+mod _chrono;
+
 pub use self::traits::*;
 
 //==============================================================================
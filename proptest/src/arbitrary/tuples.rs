@@ -36,10 +36,45 @@ impl_tuple!(T0, T1, T2, T3, T4, T5, T6);
 impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7);
 impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8);
 impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
 
 #[cfg(test)]
 mod test {
     no_panic_test!(
-        tuple_n10 => ((), bool, u8, u16, u32, u64, i8, i16, i32, i64)
+        tuple_n10 => ((), bool, u8, u16, u32, u64, i8, i16, i32, i64),
+        tuple_n12 => ((), bool, u8, u16, u32, u64, i8, i16, i32, i64, char, f64)
     );
+
+    #[test]
+    fn tuple_n12_shrinks_left_to_right() {
+        use crate::arbitrary::any;
+        use crate::std_facade::Vec;
+        use crate::strategy::{Strategy, ValueTree};
+        use crate::test_runner::TestRunner;
+
+        type T12 = (u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8);
+        fn fields(t: T12) -> [u8; 12] {
+            [t.0, t.1, t.2, t.3, t.4, t.5, t.6, t.7, t.8, t.9, t.10, t.11]
+        }
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut tree = any::<T12>().new_tree(&mut runner).unwrap();
+            let mut prev = fields(tree.current());
+            let mut last_changed = 0;
+            while tree.simplify() {
+                let curr = fields(tree.current());
+                let changed: Vec<usize> =
+                    (0..12).filter(|&ix| curr[ix] != prev[ix]).collect();
+                assert!(changed.len() <= 1, "{:?} -> {:?}", prev, curr);
+                if let Some(&ix) = changed.first() {
+                    assert!(ix >= last_changed, "{:?} -> {:?}", prev, curr);
+                    last_changed = ix;
+                }
+                prev = curr;
+            }
+            assert_eq!([0; 12], prev);
+        }
+    }
 }
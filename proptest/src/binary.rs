@@ -0,0 +1,350 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Strategies for assembling byte buffers in binary formats.
+//!
+//! The combinators here build `Vec<u8>` values out of other byte strategies,
+//! writing length fields computed from the generated payload. Since the
+//! length is recomputed whenever a value is produced, shrinking the payload
+//! shrinks its length field along with it, so buffers stay structurally
+//! valid throughout shrinking.
+//!
+//! The combinators can be nested, e.g., to produce a sequence of records:
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use proptest::binary;
+//!
+//! proptest! {
+//!   # /*
+//!   #[test]
+//!   # */
+//!   fn test_parse_records(buf in binary::concat(vec![
+//!       binary::tlv(Just(1u8), prop::collection::vec(any::<u8>(), 0..4), 1)
+//!           .boxed(),
+//!       binary::tlv(2..=3u8, prop::collection::vec(any::<u8>(), 0..64), 2)
+//!           .boxed(),
+//!   ])) {
+//!       prop_assert_eq!(1, buf[0]);
+//!       let second = 2 + buf[1] as usize;
+//!       prop_assert!(buf[second] == 2 || buf[second] == 3);
+//!   }
+//! }
+//! #
+//! # fn main() { test_parse_records(); }
+//! ```
+
+use crate::std_facade::Vec;
+
+use crate::collection::VecValueTree;
+use crate::strategy::*;
+use crate::test_runner::*;
+use crate::tuple::TupleValueTree;
+
+fn assert_len_bytes(len_bytes: usize) {
+    assert!(
+        (1..=8).contains(&len_bytes),
+        "length prefix must be 1 to 8 bytes long, got {}",
+        len_bytes
+    );
+}
+
+/// Write the big-endian, `len_bytes`-byte length of `payload` into `buf`
+/// followed by `payload` itself.
+fn write_length_prefixed(buf: &mut Vec<u8>, len_bytes: usize, payload: &[u8]) {
+    let len = payload.len() as u64;
+    assert!(
+        len_bytes == 8 || len >> (8 * len_bytes) == 0,
+        "payload of {} bytes is too long for a {}-byte length prefix",
+        len,
+        len_bytes
+    );
+    buf.extend_from_slice(&len.to_be_bytes()[8 - len_bytes..]);
+    buf.extend_from_slice(payload);
+}
+
+//==============================================================================
+// Length-prefixed
+//==============================================================================
+
+/// Strategy to create byte buffers consisting of a length prefix followed by
+/// a payload.
+///
+/// Created by the `length_prefixed()` function in the same module.
+#[must_use = "strategies do nothing unless used"]
+#[derive(Clone, Copy, Debug)]
+pub struct LengthPrefixed<S> {
+    payload: S,
+    len_bytes: usize,
+}
+
+/// `ValueTree` corresponding to `LengthPrefixed`.
+#[derive(Clone, Copy, Debug)]
+pub struct LengthPrefixedValueTree<T> {
+    payload: T,
+    len_bytes: usize,
+}
+
+/// Create a strategy to generate a payload from `payload`, preceded by its
+/// length in bytes as a big-endian unsigned integer `len_bytes` bytes long.
+///
+/// The length prefix always matches the payload, including while shrinking.
+///
+/// ## Panics
+///
+/// Panics if `len_bytes` is not between 1 and 8, inclusive. Value generation
+/// panics if a payload is too long for its length to fit in `len_bytes`
+/// bytes, so `payload` should be limited accordingly.
+pub fn length_prefixed<S: Strategy<Value = Vec<u8>>>(
+    payload: S,
+    len_bytes: usize,
+) -> LengthPrefixed<S> {
+    assert_len_bytes(len_bytes);
+    LengthPrefixed { payload, len_bytes }
+}
+
+impl<S: Strategy<Value = Vec<u8>>> Strategy for LengthPrefixed<S> {
+    type Tree = LengthPrefixedValueTree<S::Tree>;
+    type Value = Vec<u8>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        Ok(LengthPrefixedValueTree {
+            payload: self.payload.new_tree(runner)?,
+            len_bytes: self.len_bytes,
+        })
+    }
+}
+
+impl<T: ValueTree<Value = Vec<u8>>> ValueTree for LengthPrefixedValueTree<T> {
+    type Value = Vec<u8>;
+
+    fn current(&self) -> Vec<u8> {
+        let payload = self.payload.current();
+        let mut buf = Vec::with_capacity(self.len_bytes + payload.len());
+        write_length_prefixed(&mut buf, self.len_bytes, &payload);
+        buf
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.payload.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.payload.complicate()
+    }
+}
+
+//==============================================================================
+// Tag-length-value
+//==============================================================================
+
+/// Strategy to create tag-length-value records.
+///
+/// Created by the `tlv()` function in the same module.
+#[must_use = "strategies do nothing unless used"]
+#[derive(Clone, Copy, Debug)]
+pub struct Tlv<T, V> {
+    source: (T, LengthPrefixed<V>),
+}
+
+/// `ValueTree` corresponding to `Tlv`.
+#[derive(Clone, Copy, Debug)]
+pub struct TlvValueTree<T, V> {
+    source: TupleValueTree<(T, LengthPrefixedValueTree<V>)>,
+}
+
+/// Create a strategy to generate tag-length-value records: a one-byte tag
+/// from `tag`, followed by a value from `value` with its length in bytes as
+/// a big-endian unsigned integer `len_bytes` bytes long.
+///
+/// The length field always matches the value, including while shrinking.
+/// The tag shrinks before the value.
+///
+/// ## Panics
+///
+/// Panics under the same conditions as `length_prefixed()`.
+pub fn tlv<T: Strategy<Value = u8>, V: Strategy<Value = Vec<u8>>>(
+    tag: T,
+    value: V,
+    len_bytes: usize,
+) -> Tlv<T, V> {
+    Tlv {
+        source: (tag, length_prefixed(value, len_bytes)),
+    }
+}
+
+impl<T: Strategy<Value = u8>, V: Strategy<Value = Vec<u8>>> Strategy
+    for Tlv<T, V>
+{
+    type Tree = TlvValueTree<T::Tree, V::Tree>;
+    type Value = Vec<u8>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        Ok(TlvValueTree {
+            source: self.source.new_tree(runner)?,
+        })
+    }
+}
+
+impl<T: ValueTree<Value = u8>, V: ValueTree<Value = Vec<u8>>> ValueTree
+    for TlvValueTree<T, V>
+{
+    type Value = Vec<u8>;
+
+    fn current(&self) -> Vec<u8> {
+        let (tag, mut length_value) = self.source.current();
+        length_value.insert(0, tag);
+        length_value
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.source.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.source.complicate()
+    }
+}
+
+//==============================================================================
+// Concatenation
+//==============================================================================
+
+/// Strategy to create byte buffers by concatenating several parts.
+///
+/// Created by the `concat()` function in the same module.
+#[must_use = "strategies do nothing unless used"]
+#[derive(Clone, Debug)]
+pub struct Concat<S> {
+    parts: Vec<S>,
+}
+
+/// `ValueTree` corresponding to `Concat`.
+#[derive(Clone, Debug)]
+pub struct ConcatValueTree<T: ValueTree> {
+    parts: VecValueTree<T>,
+}
+
+/// Create a strategy to generate the concatenation of one value from each
+/// strategy in `parts`, in order.
+///
+/// Every part is always present; shrinking only shrinks the parts
+/// themselves. To mix different kinds of strategy, box them first, e.g.,
+/// with `Strategy::boxed()`.
+pub fn concat<S: Strategy<Value = Vec<u8>>>(parts: Vec<S>) -> Concat<S> {
+    Concat { parts }
+}
+
+impl<S: Strategy<Value = Vec<u8>>> Strategy for Concat<S> {
+    type Tree = ConcatValueTree<S::Tree>;
+    type Value = Vec<u8>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        Ok(ConcatValueTree {
+            parts: self.parts.new_tree(runner)?,
+        })
+    }
+}
+
+impl<T: ValueTree<Value = Vec<u8>>> ValueTree for ConcatValueTree<T> {
+    type Value = Vec<u8>;
+
+    fn current(&self) -> Vec<u8> {
+        self.parts.current().concat()
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.parts.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.parts.complicate()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::arbitrary::any;
+    use crate::collection;
+    use crate::strategy::Just;
+
+    fn payload_len(buf: &[u8]) -> usize {
+        u16::from_be_bytes([buf[0], buf[1]]) as usize
+    }
+
+    #[test]
+    fn length_prefix_matches_payload_through_shrinking() {
+        let input = length_prefixed(collection::vec(any::<u8>(), 0..300), 2);
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            // Shrink as though payloads of at least 3 bytes fail.
+            loop {
+                let buf = tree.current();
+                assert_eq!(buf.len() - 2, payload_len(&buf), "{:?}", buf);
+                let more = if payload_len(&buf) >= 3 {
+                    tree.simplify()
+                } else {
+                    tree.complicate()
+                };
+                if !more {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn concat_of_tlvs_is_well_formed() {
+        let input = concat(vec![
+            tlv(Just(7u8), collection::vec(any::<u8>(), 0..4), 1).boxed(),
+            tlv(0..3u8, collection::vec(any::<u8>(), 0..600), 2).boxed(),
+            length_prefixed(collection::vec(Just(9u8), 1..10), 1).boxed(),
+        ]);
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            loop {
+                let buf = tree.current();
+                assert_eq!(7, buf[0]);
+                let second = 2 + buf[1] as usize;
+                assert!(buf[second] < 3);
+                let third = second + 3 + payload_len(&buf[second + 1..]);
+                assert_eq!(buf.len(), third + 1 + buf[third] as usize);
+                assert!(buf[third + 1..].iter().all(|&b| 9 == b));
+                if !tree.simplify() {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "too long for a 1-byte length prefix")]
+    fn payload_too_long_for_prefix_panics() {
+        let input = length_prefixed(Just(vec![0u8; 256]), 1);
+        let _ = input
+            .new_tree(&mut TestRunner::deterministic())
+            .unwrap()
+            .current();
+    }
+
+    #[test]
+    fn test_sanity() {
+        check_strategy_sanity(
+            tlv(any::<u8>(), collection::vec(any::<u8>(), 0..32), 4),
+            None,
+        );
+        check_strategy_sanity(
+            concat(vec![collection::vec(any::<u8>(), 0..8); 3]),
+            None,
+        );
+    }
+}
@@ -27,7 +27,7 @@ use bit_vec::BitVec;
 use rand::{self, seq::IteratorRandom, Rng};
 
 use crate::collection::SizeRange;
-use crate::num::sample_uniform_incl;
+use crate::option::Probability;
 use crate::strategy::*;
 use crate::test_runner::*;
 
@@ -96,6 +96,27 @@ int_bitset!(i32);
 int_bitset!(i64);
 int_bitset!(isize);
 
+impl<const N: usize> BitSetLike for [u64; N] {
+    fn new_bitset(_: usize) -> Self {
+        [0; N]
+    }
+    fn len(&self) -> usize {
+        N * 64
+    }
+    fn test(&self, ix: usize) -> bool {
+        0 != (self[ix / 64] & (1 << (ix % 64)))
+    }
+    fn set(&mut self, ix: usize) {
+        self[ix / 64] |= 1 << (ix % 64);
+    }
+    fn clear(&mut self, ix: usize) {
+        self[ix / 64] &= !(1 << (ix % 64));
+    }
+    fn count(&self) -> usize {
+        self.iter().map(|word| word.count_ones() as usize).sum()
+    }
+}
+
 #[cfg(feature = "bit-set")]
 #[cfg_attr(docsrs, doc(cfg(feature = "bit-set")))]
 impl BitSetLike for BitSet {
@@ -275,11 +296,7 @@ impl<T: BitSetLike> Strategy for SampledBitSetStrategy<T> {
 
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
         let mut bits = T::new_bitset(self.bits.end_excl());
-        let count = sample_uniform_incl(
-            runner,
-            self.size.start(),
-            self.size.end_incl(),
-        );
+        let count = self.size.sample(runner)?;
         if bits.len() < count {
             panic!("not enough bits to sample");
         }
@@ -297,6 +314,67 @@ impl<T: BitSetLike> Strategy for SampledBitSetStrategy<T> {
     }
 }
 
+/// Generates `[u64; N]` bit masks, where each of the `64 * N` bits is set
+/// independently with a fixed probability.
+///
+/// Created by the `array()` function in the same module.
+///
+/// Shrinking happens as with [`BitSetStrategy`](struct.BitSetStrategy.html).
+#[derive(Clone, Copy, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct BitArrayStrategy<const N: usize> {
+    density: Probability,
+}
+
+/// Create a strategy to generate bit masks wider than the primitive integer
+/// types, as arrays of `N` words, where bit `i` is bit `i % 64` of word
+/// `i / 64`. Each bit is set with probability `density`.
+///
+/// ```
+/// use proptest::bits;
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn feature_masks(mask in bits::array::<4>(0.1)) {
+///     prop_assert!(mask.iter().map(|w| w.count_ones()).sum::<u32>() <= 256);
+///   }
+/// }
+/// #
+/// # fn main() { feature_masks(); }
+/// ```
+pub fn array<const N: usize>(
+    density: impl Into<Probability>,
+) -> BitArrayStrategy<N> {
+    BitArrayStrategy {
+        density: density.into(),
+    }
+}
+
+impl<const N: usize> Strategy for BitArrayStrategy<N> {
+    type Tree = BitSetValueTree<[u64; N]>;
+    type Value = [u64; N];
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let mut inner = [0; N];
+        let density = self.density.into();
+        for bit in 0..inner.len() {
+            if runner.rng().gen_bool(density) {
+                inner.set(bit);
+            }
+        }
+
+        Ok(BitSetValueTree {
+            inner,
+            shrink: 0,
+            prev_shrink: None,
+            min_count: 0,
+        })
+    }
+}
+
 /// Value tree produced by `BitSetStrategy` and `SampledBitSetStrategy`.
 #[derive(Clone, Copy, Debug)]
 pub struct BitSetValueTree<T: BitSetLike> {
@@ -685,8 +763,47 @@ mod test {
         }
     }
 
+    #[test]
+    fn array_density_controls_bits_set() {
+        let mut runner = TestRunner::deterministic();
+        for &(density, min, max) in
+            &[(0.1, 15, 40), (0.5, 100, 156), (0.9, 215, 240)]
+        {
+            let input = array::<4>(density);
+            let mut total = 0;
+            for _ in 0..64 {
+                total += input.new_tree(&mut runner).unwrap().current().count();
+            }
+            let mean = total / 64;
+            assert!(mean >= min && mean <= max, "{}: {}", density, mean);
+        }
+
+        let input = array::<3>(1.0);
+        assert_eq!([!0; 3], input.new_tree(&mut runner).unwrap().current());
+    }
+
+    #[test]
+    fn array_shrinks_to_zero_one_bit_at_a_time() {
+        let input = array::<4>(0.3);
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut value = input.new_tree(&mut runner).unwrap();
+            let mut prev = value.current();
+            while value.simplify() {
+                let v = value.current();
+                assert_eq!(prev.count() - 1, v.count());
+                assert!(prev.iter().zip(&v).all(|(&p, &v)| 0 == v & !p));
+                prev = v;
+            }
+
+            assert_eq!([0; 4], value.current());
+        }
+    }
+
     #[test]
     fn test_sanity() {
         check_strategy_sanity(u32::masked(0xdeadbeef), None);
+        check_strategy_sanity(array::<2>(0.25), None);
     }
 }
@@ -9,8 +9,11 @@
 
 //! Strategies for generating `std::collections` of values.
 
+#[cfg(feature = "unstable")]
+use core::alloc::Allocator;
 use core::cmp::Ord;
-use core::hash::Hash;
+use core::hash::{Hash, Hasher};
+use core::iter;
 use core::ops::{Add, Range, RangeInclusive, RangeTo, RangeToInclusive};
 use core::usize;
 
@@ -20,6 +23,11 @@ use crate::std_facade::{
 
 #[cfg(feature = "std")]
 use crate::std_facade::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use core::hash::BuildHasher;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
 
 use crate::bits::{BitSetLike, VarBitSet};
 use crate::num::sample_uniform_incl;
@@ -39,8 +47,11 @@ use crate::tuple::TupleValueTree;
 ///
 /// The `Default` is `0..PROPTEST_MAX_DEFAULT_SIZE_RANGE`. The max can be set with
 /// the `PROPTEST_MAX_DEFAULT_SIZE_RANGE` env var, which defaults to `100`.
-#[derive(Clone, PartialEq, Eq, Hash, Debug)]
-pub struct SizeRange(Range<usize>);
+///
+/// Sizes are normally chosen uniformly from the range. A custom length
+/// distribution can be attached with `SizeRange::with_distribution()`.
+#[derive(Clone, Debug)]
+pub struct SizeRange(Range<usize>, Option<SBoxedStrategy<usize>>);
 
 /// Creates a `SizeRange` from some value that is convertible into it.
 pub fn size_range(from: impl Into<SizeRange>) -> SizeRange {
@@ -61,6 +72,35 @@ impl SizeRange {
         range.into()
     }
 
+    /// Creates a `SizeRange` from `range` whose sizes are chosen by
+    /// `distribution` rather than uniformly.
+    ///
+    /// Each value produced by `distribution` is used as an offset above the
+    /// lower bound of `range`, saturating at the upper bound. For example,
+    /// the following biases towards short collections while still
+    /// occasionally producing long ones:
+    ///
+    /// ```
+    /// use proptest::collection::{vec, SizeRange};
+    ///
+    /// let size = SizeRange::with_distribution(
+    ///     1..100, proptest::num::usize::geometric(0.2));
+    /// let strategy = vec(0u8..10, size);
+    /// # let _ = strategy;
+    /// ```
+    ///
+    /// The distribution is only used to pick the initial size; shrinking
+    /// still removes elements down to the lower bound of `range` as usual.
+    pub fn with_distribution<S>(
+        range: impl Into<SizeRange>,
+        distribution: S,
+    ) -> Self
+    where
+        S: Strategy<Value = usize> + Send + Sync + 'static,
+    {
+        SizeRange(range.into().0, Some(distribution.sboxed()))
+    }
+
     // Don't rely on these existing internally:
 
     /// Merges self together with some other argument producing a product
@@ -99,6 +139,22 @@ impl SizeRange {
         self.0.end
     }
 
+    /// Picks a size within this range, using the attached distribution if
+    /// there is one and sampling uniformly otherwise.
+    pub(crate) fn sample(
+        &self,
+        runner: &mut TestRunner,
+    ) -> Result<usize, Reason> {
+        let (start, end) = self.start_end_incl();
+        match self.1 {
+            Some(ref distribution) => {
+                let offset = distribution.new_tree(runner)?.current();
+                Ok(start.saturating_add(offset).min(end))
+            }
+            None => Ok(sample_uniform_incl(runner, start, end)),
+        }
+    }
+
     pub(crate) fn iter(&self) -> impl Iterator<Item = usize> {
         self.0.clone().into_iter()
     }
@@ -122,6 +178,27 @@ impl SizeRange {
     }
 }
 
+/// Two `SizeRange`s are equal if they have the same bounds and either both
+/// sample uniformly or share the same distribution.
+impl PartialEq for SizeRange {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+            && match (&self.1, &other.1) {
+                (None, None) => true,
+                (Some(a), Some(b)) => a.ptr_eq(b),
+                _ => false,
+            }
+    }
+}
+
+impl Eq for SizeRange {}
+
+impl Hash for SizeRange {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
 /// Given `(low: usize, high: usize)`,
 /// then a size range of `[low..high)` is the result.
 impl From<(usize, usize)> for SizeRange {
@@ -147,7 +224,7 @@ impl From<RangeTo<usize>> for SizeRange {
 /// Given `low .. high`, then a size range `[low, high)` is the result.
 impl From<Range<usize>> for SizeRange {
     fn from(r: Range<usize>) -> Self {
-        SizeRange(r)
+        SizeRange(r, None)
     }
 }
 
@@ -182,13 +259,15 @@ impl Generic for SizeRange {
 
 /// Adds `usize` to both start and end of the bounds.
 ///
+/// Any attached distribution is kept.
+///
 /// Panics if adding to either end overflows `usize`.
 impl Add<usize> for SizeRange {
     type Output = SizeRange;
 
     fn add(self, rhs: usize) -> Self::Output {
         let (start, end) = self.start_end_incl();
-        size_range((start + rhs)..=(end + rhs))
+        SizeRange(size_range((start + rhs)..=(end + rhs)).0, self.1)
     }
 }
 
@@ -196,6 +275,29 @@ impl Add<usize> for SizeRange {
 // Strategies
 //==============================================================================
 
+/// The order in which a `Vec` generated by `vec()` tries to delete elements
+/// while shrinking.
+///
+/// Each deletion that no longer reproduces the failure is undone and not
+/// tried again, so the order determines which parts of the value survive
+/// shrinking. See `VecStrategy::with_shrink_order()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ShrinkOrder {
+    /// Try deleting each element once, starting with the first. This keeps
+    /// the end of the value intact for as long as possible, which tends to
+    /// find smaller cases when the failure depends on a suffix.
+    #[default]
+    FrontFirst,
+    /// Try deleting each element once, starting with the last. This keeps
+    /// the start of the value intact for as long as possible, which tends to
+    /// find smaller cases when the failure depends on a prefix.
+    BackFirst,
+    /// Try deleting the first and second halves, then each quarter, and so
+    /// on down to single elements. This removes large irrelevant runs of
+    /// elements in few steps.
+    Bisect,
+}
+
 /// Strategy to create `Vec`s with a length in a certain range.
 ///
 /// Created by the `vec()` function in the same module.
@@ -204,6 +306,8 @@ impl Add<usize> for SizeRange {
 pub struct VecStrategy<T: Strategy> {
     element: T,
     size: SizeRange,
+    capacity_hint: usize,
+    shrink_order: ShrinkOrder,
 }
 
 /// Create a strategy to generate `Vec`s containing elements drawn from
@@ -217,7 +321,88 @@ pub fn vec<T: Strategy>(
 ) -> VecStrategy<T> {
     let size = size.into();
     size.assert_nonempty();
-    VecStrategy { element, size }
+    VecStrategy {
+        element,
+        size,
+        capacity_hint: 0,
+        shrink_order: ShrinkOrder::FrontFirst,
+    }
+}
+
+impl<T: Strategy> VecStrategy<T> {
+    /// Allocate every generated `Vec` with room for at least `capacity`
+    /// elements.
+    ///
+    /// Generated values otherwise grow as elements are added, which may
+    /// reallocate several times. If the upper bound of the size range is
+    /// known and small, passing it here makes each value take exactly one
+    /// allocation, including the values produced while shrinking.
+    pub fn with_capacity_hint(mut self, capacity: usize) -> Self {
+        self.capacity_hint = capacity;
+        self
+    }
+
+    /// Delete elements in the given order while shrinking, instead of
+    /// starting with the first element.
+    pub fn with_shrink_order(mut self, order: ShrinkOrder) -> Self {
+        self.shrink_order = order;
+        self
+    }
+}
+
+/// Strategy to create `Vec`s with a length in a certain range, allocated
+/// with a custom allocator.
+///
+/// Created by the `vec_in()` function in the same module.
+#[cfg(feature = "unstable")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+#[must_use = "strategies do nothing unless used"]
+#[derive(Clone, Debug)]
+pub struct VecInStrategy<T: Strategy, A> {
+    vec: VecStrategy<T>,
+    alloc: A,
+}
+
+/// Create a strategy to generate `Vec`s containing elements drawn from
+/// `element` and with a size range given by `size`, like `vec()`, but
+/// allocated with a clone of `alloc` rather than the global allocator.
+///
+/// This lets `no_std` users with a bounded heap, such as an arena, control
+/// where generated values live. Only the `Vec`s themselves are allocated with
+/// `alloc`; the value trees, and any allocations made by the elements, use
+/// the global allocator as usual. Use `VecInStrategy::with_capacity_hint()`
+/// to make every value take a single allocation of known size.
+///
+/// This requires the `unstable` feature, as allocators are not yet stable.
+#[cfg(feature = "unstable")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+pub fn vec_in<T: Strategy, A: Allocator + Clone + fmt::Debug>(
+    element: T,
+    size: impl Into<SizeRange>,
+    alloc: A,
+) -> VecInStrategy<T, A> {
+    VecInStrategy {
+        vec: vec(element, size),
+        alloc,
+    }
+}
+
+#[cfg(feature = "unstable")]
+impl<T: Strategy, A> VecInStrategy<T, A> {
+    /// Allocate every generated `Vec` with room for at least `capacity`
+    /// elements.
+    ///
+    /// See `VecStrategy::with_capacity_hint()`.
+    pub fn with_capacity_hint(mut self, capacity: usize) -> Self {
+        self.vec = self.vec.with_capacity_hint(capacity);
+        self
+    }
+
+    /// See `VecStrategy::with_shrink_order()`.
+    pub fn with_shrink_order(mut self, order: ShrinkOrder) -> Self {
+        self.vec = self.vec.with_shrink_order(order);
+        self
+    }
 }
 
 mapfn! {
@@ -371,6 +556,106 @@ where
     ))
 }
 
+/// The number of duplicate elements in a row `hash_set_exact()` tolerates
+/// before concluding that the element space is too small.
+#[cfg(feature = "std")]
+const MAX_CONSECUTIVE_DUPLICATES: u32 = 256;
+
+/// Strategy to create `HashSet`s whose length is exactly the size drawn from
+/// a certain range.
+///
+/// Created by the `hash_set_exact()` function in the same module.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[must_use = "strategies do nothing unless used"]
+#[derive(Clone, Debug)]
+pub struct HashSetExactStrategy<T: Strategy> {
+    element: T,
+    size: SizeRange,
+}
+
+#[cfg(feature = "std")]
+impl<T: Strategy> Strategy for HashSetExactStrategy<T>
+where
+    T::Value: Hash + Eq,
+{
+    type Tree = HashSetValueTree<T::Tree>;
+    type Value = HashSet<T::Value>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let size = self.size.sample(runner)?;
+        let mut seen = HashSet::with_capacity(size);
+        let mut elements = Vec::with_capacity(size);
+        let mut duplicates = 0;
+        while elements.len() < size {
+            let element = self.element.new_tree(runner)?;
+            if seen.insert(element.current()) {
+                elements.push(element);
+                duplicates = 0;
+            } else {
+                duplicates += 1;
+                if duplicates >= MAX_CONSECUTIVE_DUPLICATES {
+                    return Err(format!(
+                        "HashSet exact size: only found {} of {} distinct \
+                         elements after {} consecutive duplicates",
+                        elements.len(),
+                        size,
+                        duplicates
+                    )
+                    .into());
+                }
+            }
+        }
+
+        // Never delete elements while shrinking, and reject shrinks of
+        // individual elements which would collide with another one.
+        Ok(HashSetValueTree(statics::Filter::new(
+            statics::Map::new(
+                VecValueTree {
+                    elements,
+                    included_elements: VarBitSet::saturated(size),
+                    min_size: size,
+                    capacity_hint: 0,
+                    shrink_order: ShrinkOrder::FrontFirst,
+                    shrink: Shrink::ShrinkElement(0),
+                    prev_shrink: None,
+                },
+                VecToHashSet,
+            ),
+            "HashSet exact size".into(),
+            MinSize(size),
+        )))
+    }
+}
+
+/// Create a strategy to generate `HashSet`s containing elements drawn from
+/// `element` and with exactly as many elements as a size drawn from `size`.
+///
+/// This differs from `hash_set()`, which sizes its sets on a best-effort
+/// basis: it generates a `Vec` with a size from `size` and collects it into a
+/// set, so collisions between elements can yield any size between the
+/// minimum of `size` and the chosen one. Here, elements are instead
+/// regenerated until the chosen number of distinct values is found, and
+/// shrinking never changes the number of elements.
+///
+/// If `element` produces too many duplicates in a row (e.g., because it
+/// cannot produce that many distinct values at all), the strategy fails to
+/// generate a value rather than silently producing a smaller set.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn hash_set_exact<T: Strategy>(
+    element: T,
+    size: impl Into<SizeRange>,
+) -> HashSetExactStrategy<T>
+where
+    T::Value: Hash + Eq,
+{
+    HashSetExactStrategy {
+        element,
+        size: size.into(),
+    }
+}
+
 mapfn! {
     [] fn VecToBTreeSet[<T : fmt::Debug + Ord>](vec: Vec<T>)
                                                 -> BTreeSet<T> {
@@ -482,6 +767,182 @@ where
     ))
 }
 
+/// The number of buckets `hash_map_colliding()` sorts keys into by the low
+/// bits of their hashes; all keys of a map share one of them.
+#[cfg(feature = "std")]
+const COLLIDING_BUCKETS: u64 = 64;
+
+/// The number of keys in a row `hash_map_colliding()` tries before
+/// concluding that it can't find another key in the chosen bucket.
+#[cfg(feature = "std")]
+const MAX_CONSECUTIVE_MISSES: u32 = 64 * COLLIDING_BUCKETS as u32;
+
+/// Strategy to create `HashMap`s whose keys share a hash bucket.
+///
+/// Created by the `hash_map_colliding()` function in the same module.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[must_use = "strategies do nothing unless used"]
+#[derive(Clone, Debug)]
+pub struct HashMapCollidingStrategy<K: Strategy, V: Strategy, S> {
+    key: NoShrink<K>,
+    value: V,
+    size: SizeRange,
+    hasher: S,
+}
+
+/// Create a strategy to generate `HashMap`s using `hasher`, containing keys
+/// and values drawn from `key_base` and `value` respectively, and with a
+/// size within the given range, whose keys all collide under `hasher`.
+///
+/// This is meant for stressing the collision handling of hash tables, which
+/// random keys rarely exercise. Each map picks one of 64 buckets, and only
+/// keeps keys from `key_base` whose hashes under `hasher` fall into that
+/// bucket by their low bits, which many hash tables use to index their
+/// buckets. With a good hasher, about one in 64 keys is kept, so `key_base`
+/// should produce a large number of distinct values.
+///
+/// Shrinking removes entries and shrinks the values, but leaves the keys
+/// alone, since smaller keys would generally no longer collide.
+///
+/// If the strategy fails to find a colliding key too many times in a row,
+/// e.g., because `key_base` produces few distinct values, it fails to
+/// generate a value rather than producing a map with fewer collisions.
+///
+/// ```
+/// use std::collections::hash_map::RandomState;
+/// use proptest::prelude::*;
+/// use proptest::collection::hash_map_colliding;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn colliding_keys_round_trip(map in hash_map_colliding(
+///       any::<u64>(), any::<u8>(), 0..32, RandomState::new(),
+///   )) {
+///     for (key, value) in &map {
+///       prop_assert_eq!(Some(value), map.get(key));
+///     }
+///   }
+/// }
+/// #
+/// # fn main() { colliding_keys_round_trip(); }
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn hash_map_colliding<K: Strategy, V: Strategy, S: BuildHasher + Clone>(
+    key_base: K,
+    value: V,
+    size: impl Into<SizeRange>,
+    hasher: S,
+) -> HashMapCollidingStrategy<K, V, S>
+where
+    K::Value: Hash + Eq,
+{
+    HashMapCollidingStrategy {
+        key: key_base.no_shrink(),
+        value,
+        size: size.into(),
+        hasher,
+    }
+}
+
+#[cfg(feature = "std")]
+fn hash_bucket<T: Hash, S: BuildHasher>(hasher: &S, value: &T) -> u64 {
+    let mut state = hasher.build_hasher();
+    value.hash(&mut state);
+    state.finish() % COLLIDING_BUCKETS
+}
+
+#[cfg(feature = "std")]
+impl<K: Strategy, V: Strategy, S: BuildHasher + Clone + fmt::Debug> Strategy
+    for HashMapCollidingStrategy<K, V, S>
+where
+    K::Value: Hash + Eq,
+{
+    type Tree = HashMapCollidingValueTree<K::Tree, V::Tree, S>;
+    type Value = HashMap<K::Value, V::Value, S>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let size = self.size.sample(runner)?;
+        let bucket = runner.rng().gen_range(0..COLLIDING_BUCKETS);
+        let mut seen = HashMap::with_hasher(self.hasher.clone());
+        let mut entries = Vec::with_capacity(size);
+        let mut misses = 0;
+        while entries.len() < size {
+            let key = self.key.new_tree(runner)?;
+            let current = key.current();
+            if hash_bucket(&self.hasher, &current) == bucket
+                && seen.insert(current, ()).is_none()
+            {
+                entries.push(TupleValueTree::new((
+                    key,
+                    self.value.new_tree(runner)?,
+                )));
+                misses = 0;
+            } else {
+                misses += 1;
+                if misses >= MAX_CONSECUTIVE_MISSES {
+                    return Err(format!(
+                        "HashMap colliding: only found {} of {} distinct \
+                         keys in the same bucket after {} consecutive misses",
+                        entries.len(),
+                        size,
+                        misses
+                    )
+                    .into());
+                }
+            }
+        }
+
+        Ok(HashMapCollidingValueTree {
+            entries: VecValueTree {
+                elements: entries,
+                included_elements: VarBitSet::saturated(size),
+                min_size: self.size.start(),
+                capacity_hint: 0,
+                shrink_order: ShrinkOrder::FrontFirst,
+                shrink: Shrink::DeleteElement(0),
+                prev_shrink: None,
+            },
+            hasher: self.hasher.clone(),
+        })
+    }
+}
+
+/// `ValueTree` corresponding to `HashMapCollidingStrategy`.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug)]
+pub struct HashMapCollidingValueTree<K: ValueTree, V: ValueTree, S> {
+    entries: VecValueTree<TupleValueTree<(NoShrink<K>, V)>>,
+    hasher: S,
+}
+
+#[cfg(feature = "std")]
+impl<K: ValueTree, V: ValueTree, S: BuildHasher + Clone + fmt::Debug> ValueTree
+    for HashMapCollidingValueTree<K, V, S>
+where
+    K::Value: Hash + Eq,
+{
+    type Value = HashMap<K::Value, V::Value, S>;
+
+    fn current(&self) -> HashMap<K::Value, V::Value, S> {
+        let mut map = HashMap::with_hasher(self.hasher.clone());
+        map.extend(self.entries.included());
+        map
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.entries.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.entries.complicate()
+    }
+}
+
 mapfn! {
     [] fn VecToBTreeMap[<K : fmt::Debug + Ord, V : fmt::Debug>]
         (vec: Vec<(K, V)>) -> BTreeMap<K, V>
@@ -538,75 +999,483 @@ where
     ))
 }
 
-#[derive(Clone, Copy, Debug)]
-enum Shrink {
-    DeleteElement(usize),
-    ShrinkElement(usize),
+/// Rearranges entries sorted by key into the insertion order selected by
+/// `order`, using `seed` for the parts of the order which are random.
+fn order_entries<T>(mut entries: Vec<T>, order: u8, seed: u64) -> Vec<T> {
+    let mut seed_bytes = [0u8; 16];
+    seed_bytes[..8].copy_from_slice(&seed.to_le_bytes());
+    seed_bytes[8..].copy_from_slice(&(!seed).to_le_bytes());
+    let mut rng = TestRng::from_seed(RngAlgorithm::XorShift, &seed_bytes);
+
+    match order {
+        // Ascending
+        0 => entries,
+        // Descending
+        1 => {
+            entries.reverse();
+            entries
+        }
+        // Clustered: runs of adjacent keys, with the runs in random order
+        2 | 3 => {
+            let run_len = 1 + entries.len() / rng.gen_range(2..5);
+            let mut runs = Vec::new();
+            while !entries.is_empty() {
+                let rest = entries.split_off(run_len.min(entries.len()));
+                runs.push(entries);
+                entries = rest;
+            }
+            runs.shuffle(&mut rng);
+            runs.into_iter().flatten().collect()
+        }
+        // Random
+        _ => {
+            entries.shuffle(&mut rng);
+            entries
+        }
+    }
 }
 
-/// `ValueTree` corresponding to `VecStrategy`.
-#[derive(Clone, Debug)]
-pub struct VecValueTree<T: ValueTree> {
-    elements: Vec<T>,
-    included_elements: VarBitSet,
-    min_size: usize,
-    shrink: Shrink,
-    prev_shrink: Option<Shrink>,
+mapfn! {
+    [] fn OrderBTreeMapEntries[<K : fmt::Debug + Ord, V : fmt::Debug>]
+        (input: (BTreeMap<K, V>, u8, u64)) -> Vec<(K, V)>
+    {
+        let (map, order, seed) = input;
+        order_entries(map.into_iter().collect(), order, seed)
+    }
 }
 
-impl<T: Strategy> Strategy for VecStrategy<T> {
-    type Tree = VecValueTree<T::Tree>;
-    type Value = Vec<T::Value>;
+opaque_strategy_wrapper! {
+    /// Strategy to create the entries of `BTreeMap`s in adversarial insertion
+    /// orders.
+    ///
+    /// Created by the `btree_map_adversarial()` function in the same module.
+    #[derive(Clone, Debug)]
+    pub struct BTreeMapAdversarialStrategy[<K, V>]
+        [where K : Strategy, V : Strategy, K::Value : Ord](
+            statics::Map<(BTreeMapStrategy<K, V>, Range<u8>,
+                          crate::num::u64::Any), OrderBTreeMapEntries>)
+        -> BTreeMapAdversarialValueTree<K::Tree, V::Tree>;
+    /// `ValueTree` corresponding to `BTreeMapAdversarialStrategy`.
+    #[derive(Clone, Debug)]
+    pub struct BTreeMapAdversarialValueTree[<K, V>]
+        [where K : ValueTree, V : ValueTree, K::Value : Ord](
+            statics::Map<TupleValueTree<(BTreeMapValueTree<K, V>,
+                                         crate::num::u8::BinarySearch,
+                                         crate::num::u64::BinarySearch)>,
+                         OrderBTreeMapEntries>)
+        -> Vec<(K::Value, V::Value)>;
+}
 
-    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
-        let (start, end) = self.size.start_end_incl();
-        let max_size = sample_uniform_incl(runner, start, end);
-        let mut elements = Vec::with_capacity(max_size);
-        while elements.len() < max_size {
-            elements.push(self.element.new_tree(runner)?);
+/// Create a strategy to generate the entries of a `BTreeMap`, with keys and
+/// values drawn from `key` and `value` respectively and a size within the
+/// given range, in an order chosen to cover edge cases of insertion order.
+///
+/// The keys are distinct, so collecting the entries produces a `BTreeMap` of
+/// the same size; inserting them one at a time exercises how the code under
+/// test copes with that order. Orders are chosen as follows:
+///
+/// - About half of the time, a random order.
+///
+/// - About a quarter of the time, clustered: runs of adjacent keys in
+///   ascending order, with the runs themselves in random order.
+///
+/// - Otherwise, strictly ascending or strictly descending by key.
+///
+/// Shrinking removes entries and moves the order towards ascending.
+///
+/// This strategy will implicitly do local rejects to ensure that the map has
+/// at least the minimum number of elements, in case `key` should produce
+/// duplicate values.
+pub fn btree_map_adversarial<K: Strategy, V: Strategy>(
+    key: K,
+    value: V,
+    size: impl Into<SizeRange>,
+) -> BTreeMapAdversarialStrategy<K, V>
+where
+    K::Value: Ord,
+{
+    BTreeMapAdversarialStrategy(statics::Map::new(
+        (btree_map(key, value, size), 0..8, crate::num::u64::ANY),
+        OrderBTreeMapEntries,
+    ))
+}
+
+/// A rectangular grid of values, stored in row-major order.
+///
+/// Unlike a `Vec<Vec<T>>`, every row is guaranteed to have the same length.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Returns the number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns, i.e., the length of every row.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the value at `row` and `col`, or `None` if either is out of
+    /// bounds.
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.rows && col < self.cols {
+            self.cells.get(row * self.cols + col)
+        } else {
+            None
         }
+    }
 
-        Ok(VecValueTree {
-            elements,
-            included_elements: VarBitSet::saturated(max_size),
-            min_size: start,
-            shrink: Shrink::DeleteElement(0),
-            prev_shrink: None,
-        })
+    /// Returns the given row.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `row` is out of bounds.
+    pub fn row(&self, row: usize) -> &[T] {
+        assert!(row < self.rows, "row {} out of bounds", row);
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// Returns all values in row-major order.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Converts the grid into a `Vec` of rows, for code expecting nested
+    /// vectors.
+    pub fn into_rows(self) -> Vec<Vec<T>> {
+        let cols = self.cols;
+        let mut cells = self.cells.into_iter();
+        (0..self.rows)
+            .map(|_| cells.by_ref().take(cols).collect())
+            .collect()
     }
 }
 
-impl<T: Strategy> Strategy for Vec<T> {
-    type Tree = VecValueTree<T::Tree>;
-    type Value = Vec<T::Value>;
+/// Strategy to create rectangular `Grid`s with dimensions in certain ranges.
+///
+/// Created by the `grid()` function in the same module.
+#[must_use = "strategies do nothing unless used"]
+#[derive(Clone, Debug)]
+pub struct GridStrategy<T: Strategy> {
+    element: T,
+    rows: SizeRange,
+    cols: SizeRange,
+}
+
+/// Create a strategy to generate rectangular `Grid`s containing elements
+/// drawn from `element`, with a number of rows given by `rows` and a number
+/// of columns given by `cols`.
+///
+/// Nesting `vec()` instead produces rows of independent lengths; here, all
+/// rows have the same length, both when generated and while shrinking.
+///
+/// Shrinking first deletes whole rows, then whole columns, and finally
+/// shrinks the individual elements.
+pub fn grid<T: Strategy>(
+    element: T,
+    rows: impl Into<SizeRange>,
+    cols: impl Into<SizeRange>,
+) -> GridStrategy<T> {
+    let rows = rows.into();
+    let cols = cols.into();
+    rows.assert_nonempty();
+    cols.assert_nonempty();
+    GridStrategy {
+        element,
+        rows,
+        cols,
+    }
+}
+
+impl<T: Strategy> Strategy for GridStrategy<T> {
+    type Tree = GridValueTree<T::Tree>;
+    type Value = Grid<T::Value>;
 
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
-        let len = self.len();
-        let elements = self
-            .iter()
-            .map(|t| t.new_tree(runner))
-            .collect::<Result<Vec<_>, Reason>>()?;
+        let rows = self.rows.sample(runner)?;
+        let cols = self.cols.sample(runner)?;
+        let mut elements = Vec::with_capacity(rows * cols);
+        while elements.len() < rows * cols {
+            elements.push(self.element.new_tree(runner)?);
+        }
 
-        Ok(VecValueTree {
+        Ok(GridValueTree {
             elements,
-            included_elements: VarBitSet::saturated(len),
-            min_size: len,
-            shrink: Shrink::ShrinkElement(0),
+            rows,
+            cols,
+            included_rows: VarBitSet::saturated(rows),
+            included_cols: VarBitSet::saturated(cols),
+            min_rows: self.rows.start(),
+            min_cols: self.cols.start(),
+            shrink: GridShrink::DeleteRow(0),
             prev_shrink: None,
         })
     }
 }
 
-impl<T: ValueTree> ValueTree for VecValueTree<T> {
+#[derive(Clone, Copy, Debug)]
+enum GridShrink {
+    DeleteRow(usize),
+    DeleteCol(usize),
+    ShrinkElement(usize),
+}
+
+/// `ValueTree` corresponding to `GridStrategy`.
+#[derive(Clone, Debug)]
+pub struct GridValueTree<T: ValueTree> {
+    // All elements of the initial grid, in row-major order.
+    elements: Vec<T>,
+    rows: usize,
+    cols: usize,
+    included_rows: VarBitSet,
+    included_cols: VarBitSet,
+    min_rows: usize,
+    min_cols: usize,
+    shrink: GridShrink,
+    prev_shrink: Option<GridShrink>,
+}
+
+impl<T: ValueTree> GridValueTree<T> {
+    fn is_included(&self, ix: usize) -> bool {
+        self.included_rows.test(ix / self.cols)
+            && self.included_cols.test(ix % self.cols)
+    }
+}
+
+impl<T: ValueTree> ValueTree for GridValueTree<T> {
+    type Value = Grid<T::Value>;
+
+    fn current(&self) -> Grid<T::Value> {
+        Grid {
+            rows: self.included_rows.count(),
+            cols: self.included_cols.count(),
+            cells: self
+                .elements
+                .iter()
+                .enumerate()
+                .filter(|&(ix, _)| self.is_included(ix))
+                .map(|(_, element)| element.current())
+                .collect(),
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        // As for `VecValueTree`, but deleting whole rows and then whole
+        // columns so that the grid stays rectangular, before shrinking the
+        // remaining elements in sequence.
+        if let GridShrink::DeleteRow(ix) = self.shrink {
+            if ix >= self.rows || self.included_rows.count() == self.min_rows {
+                self.shrink = GridShrink::DeleteCol(0);
+            } else {
+                self.included_rows.clear(ix);
+                self.prev_shrink = Some(self.shrink);
+                self.shrink = GridShrink::DeleteRow(ix + 1);
+                return true;
+            }
+        }
+
+        if let GridShrink::DeleteCol(ix) = self.shrink {
+            if ix >= self.cols || self.included_cols.count() == self.min_cols {
+                self.shrink = GridShrink::ShrinkElement(0);
+            } else {
+                self.included_cols.clear(ix);
+                self.prev_shrink = Some(self.shrink);
+                self.shrink = GridShrink::DeleteCol(ix + 1);
+                return true;
+            }
+        }
+
+        while let GridShrink::ShrinkElement(ix) = self.shrink {
+            if ix >= self.elements.len() {
+                return false;
+            }
+
+            if !self.is_included(ix) || !self.elements[ix].simplify() {
+                self.shrink = GridShrink::ShrinkElement(ix + 1);
+            } else {
+                self.prev_shrink = Some(self.shrink);
+                return true;
+            }
+        }
+
+        panic!("Unexpected shrink state");
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.prev_shrink {
+            None => false,
+            Some(GridShrink::DeleteRow(ix)) => {
+                self.included_rows.set(ix);
+                self.prev_shrink = None;
+                true
+            }
+            Some(GridShrink::DeleteCol(ix)) => {
+                self.included_cols.set(ix);
+                self.prev_shrink = None;
+                true
+            }
+            Some(GridShrink::ShrinkElement(ix)) => {
+                if self.elements[ix].complicate() {
+                    true
+                } else {
+                    self.prev_shrink = None;
+                    false
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Shrink {
+    /// Delete the element at this position in the `ShrinkOrder`, which is
+    /// the index itself for `ShrinkOrder::FrontFirst`. `prev_shrink` holds
+    /// the index instead.
+    DeleteElement(usize),
+    /// Delete the `len` elements from `start` for `ShrinkOrder::Bisect`.
+    DeleteRange {
+        start: usize,
+        len: usize,
+    },
+    ShrinkElement(usize),
+}
+
+/// `ValueTree` corresponding to `VecStrategy`.
+#[derive(Clone, Debug)]
+pub struct VecValueTree<T: ValueTree> {
+    elements: Vec<T>,
+    included_elements: VarBitSet,
+    min_size: usize,
+    capacity_hint: usize,
+    shrink_order: ShrinkOrder,
+    shrink: Shrink,
+    prev_shrink: Option<Shrink>,
+}
+
+impl<T: Strategy> Strategy for VecStrategy<T> {
+    type Tree = VecValueTree<T::Tree>;
     type Value = Vec<T::Value>;
 
-    fn current(&self) -> Vec<T::Value> {
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let start = self.size.start();
+        let max_size = self.size.sample(runner)?;
+        let mut elements = Vec::with_capacity(max_size);
+        while elements.len() < max_size {
+            elements.push(self.element.new_tree(runner)?);
+        }
+
+        Ok(VecValueTree {
+            elements,
+            included_elements: VarBitSet::saturated(max_size),
+            min_size: start,
+            capacity_hint: self.capacity_hint,
+            shrink_order: self.shrink_order,
+            shrink: match self.shrink_order {
+                ShrinkOrder::FrontFirst | ShrinkOrder::BackFirst => {
+                    Shrink::DeleteElement(0)
+                }
+                ShrinkOrder::Bisect => Shrink::DeleteRange {
+                    start: 0,
+                    len: (max_size.next_power_of_two() / 2).max(1),
+                },
+            },
+            prev_shrink: None,
+        })
+    }
+}
+
+/// `ValueTree` corresponding to `VecInStrategy`.
+#[cfg(feature = "unstable")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unstable")))]
+#[derive(Clone, Debug)]
+pub struct VecInValueTree<T: ValueTree, A> {
+    vec: VecValueTree<T>,
+    alloc: A,
+}
+
+#[cfg(feature = "unstable")]
+impl<T: Strategy, A: Allocator + Clone + fmt::Debug> Strategy
+    for VecInStrategy<T, A>
+{
+    type Tree = VecInValueTree<T::Tree, A>;
+    type Value = Vec<T::Value, A>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        Ok(VecInValueTree {
+            vec: self.vec.new_tree(runner)?,
+            alloc: self.alloc.clone(),
+        })
+    }
+}
+
+#[cfg(feature = "unstable")]
+impl<T: ValueTree, A: Allocator + Clone + fmt::Debug> ValueTree
+    for VecInValueTree<T, A>
+{
+    type Value = Vec<T::Value, A>;
+
+    fn current(&self) -> Vec<T::Value, A> {
+        let mut values =
+            Vec::with_capacity_in(self.vec.capacity_hint, self.alloc.clone());
+        values.extend(self.vec.included());
+        values
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.vec.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.vec.complicate()
+    }
+}
+
+impl<T: Strategy> Strategy for Vec<T> {
+    type Tree = VecValueTree<T::Tree>;
+    type Value = Vec<T::Value>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let len = self.len();
+        let elements = self
+            .iter()
+            .map(|t| t.new_tree(runner))
+            .collect::<Result<Vec<_>, Reason>>()?;
+
+        Ok(VecValueTree {
+            elements,
+            included_elements: VarBitSet::saturated(len),
+            min_size: len,
+            capacity_hint: 0,
+            shrink_order: ShrinkOrder::FrontFirst,
+            shrink: Shrink::ShrinkElement(0),
+            prev_shrink: None,
+        })
+    }
+}
+
+impl<T: ValueTree> VecValueTree<T> {
+    fn included(&self) -> impl Iterator<Item = T::Value> + '_ {
         self.elements
             .iter()
             .enumerate()
-            .filter(|&(ix, _)| self.included_elements.test(ix))
+            .filter(move |&(ix, _)| self.included_elements.test(ix))
             .map(|(_, element)| element.current())
-            .collect()
+    }
+}
+
+impl<T: ValueTree> ValueTree for VecValueTree<T> {
+    type Value = Vec<T::Value>;
+
+    fn current(&self) -> Vec<T::Value> {
+        let mut values = Vec::with_capacity(self.capacity_hint);
+        values.extend(self.included());
+        values
     }
 
     fn simplify(&mut self) -> bool {
@@ -616,17 +1485,54 @@ impl<T: ValueTree> ValueTree for VecValueTree<T> {
         //
         // For `complicate()`, we simply undo the last shrink operation, if
         // there was any.
-        if let Shrink::DeleteElement(ix) = self.shrink {
+        while let Shrink::DeleteRange { start, len } = self.shrink {
+            // Ranges are aligned to their length, which is a power of two,
+            // so every range is either entirely inside or entirely outside
+            // of the larger ranges deleted before it.
+            if len == 0 {
+                self.shrink = Shrink::ShrinkElement(0);
+            } else if start >= self.elements.len() {
+                self.shrink = Shrink::DeleteRange {
+                    start: 0,
+                    len: len / 2,
+                };
+            } else {
+                let end = (start + len).min(self.elements.len());
+                self.shrink = Shrink::DeleteRange {
+                    start: start + len,
+                    len,
+                };
+                if self.included_elements.test(start)
+                    && self.included_elements.count() - (end - start)
+                        >= self.min_size
+                {
+                    for ix in start..end {
+                        self.included_elements.clear(ix);
+                    }
+                    self.prev_shrink = Some(Shrink::DeleteRange {
+                        start,
+                        len: end - start,
+                    });
+                    return true;
+                }
+            }
+        }
+
+        if let Shrink::DeleteElement(step) = self.shrink {
             // Can't delete an element if beyond the end of the vec or if it
             // would put us under the minimum length.
-            if ix >= self.elements.len()
+            if step >= self.elements.len()
                 || self.included_elements.count() == self.min_size
             {
                 self.shrink = Shrink::ShrinkElement(0);
             } else {
+                let ix = match self.shrink_order {
+                    ShrinkOrder::BackFirst => self.elements.len() - 1 - step,
+                    _ => step,
+                };
                 self.included_elements.clear(ix);
-                self.prev_shrink = Some(self.shrink);
-                self.shrink = Shrink::DeleteElement(ix + 1);
+                self.prev_shrink = Some(Shrink::DeleteElement(ix));
+                self.shrink = Shrink::DeleteElement(step + 1);
                 return true;
             }
         }
@@ -665,6 +1571,13 @@ impl<T: ValueTree> ValueTree for VecValueTree<T> {
                 self.prev_shrink = None;
                 true
             }
+            Some(Shrink::DeleteRange { start, len }) => {
+                for ix in start..start + len {
+                    self.included_elements.set(ix);
+                }
+                self.prev_shrink = None;
+                true
+            }
             Some(Shrink::ShrinkElement(ix)) => {
                 if self.elements[ix].complicate() {
                     // Don't unset prev_shrink; we may be able to complicate
@@ -680,6 +1593,303 @@ impl<T: ValueTree> ValueTree for VecValueTree<T> {
     }
 }
 
+/// Strategy to create `Vec<u32>`s of a fixed length whose elements sum to a
+/// fixed total.
+///
+/// Created by the `vec_summing_to()` function in the same module.
+#[must_use = "strategies do nothing unless used"]
+#[derive(Clone, Copy, Debug)]
+pub struct VecSummingToStrategy {
+    len: usize,
+    total: u32,
+}
+
+/// Create a strategy to generate `Vec<u32>`s of exactly `len` elements which
+/// sum to exactly `total`, i.e., compositions of `total` into `len` parts
+/// (some of which may be zero).
+///
+/// This is much more efficient than filtering `vec()` on the sum, which
+/// rejects almost every input.
+///
+/// Shrinking preserves the sum by moving value from the later elements into
+/// the first one, towards `[total, 0, 0, ...]`.
+///
+/// ## Panics
+///
+/// Panics if `len` is 0 but `total` is not.
+pub fn vec_summing_to(len: usize, total: u32) -> VecSummingToStrategy {
+    assert!(
+        len > 0 || total == 0,
+        "vec_summing_to() cannot make an empty Vec sum to {}",
+        total
+    );
+    VecSummingToStrategy { len, total }
+}
+
+impl Strategy for VecSummingToStrategy {
+    type Tree = VecSummingToValueTree;
+    type Value = Vec<u32>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        // Cut `0..=total` at `len - 1` random points; the parts are the gaps
+        // between consecutive cuts.
+        let mut cuts = (1..self.len)
+            .map(|_| sample_uniform_incl(runner, 0, self.total))
+            .collect::<Vec<_>>();
+        cuts.sort_unstable();
+        cuts.push(self.total);
+
+        Ok(VecSummingToValueTree {
+            len: self.len,
+            total: self.total,
+            rest: cuts
+                .windows(2)
+                .map(|w| crate::num::u32::BinarySearch::new(w[1] - w[0]))
+                .collect(),
+            shrink: 0,
+            prev_shrink: None,
+        })
+    }
+}
+
+/// `ValueTree` corresponding to `VecSummingToStrategy`.
+#[derive(Clone, Debug)]
+pub struct VecSummingToValueTree {
+    len: usize,
+    total: u32,
+    // All elements but the first, each of which only shrinks towards 0. The
+    // first element makes up the difference to `total`.
+    rest: Vec<crate::num::u32::BinarySearch>,
+    shrink: usize,
+    prev_shrink: Option<usize>,
+}
+
+impl ValueTree for VecSummingToValueTree {
+    type Value = Vec<u32>;
+
+    fn current(&self) -> Vec<u32> {
+        if 0 == self.len {
+            return Vec::new();
+        }
+
+        let rest = self.rest.iter().map(|element| element.current());
+        let first = self.total - rest.clone().sum::<u32>();
+        iter::once(first).chain(rest).collect()
+    }
+
+    fn simplify(&mut self) -> bool {
+        while self.shrink < self.rest.len() {
+            if self.rest[self.shrink].simplify() {
+                self.prev_shrink = Some(self.shrink);
+                return true;
+            }
+            self.shrink += 1;
+        }
+        false
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.prev_shrink {
+            None => false,
+            Some(ix) => {
+                if self.rest[ix].complicate() {
+                    true
+                } else {
+                    self.prev_shrink = None;
+                    false
+                }
+            }
+        }
+    }
+}
+
+/// Strategy to create increasing `Vec<f64>`s.
+///
+/// Created by the `monotonic_vec()` function in the same module.
+#[must_use = "strategies do nothing unless used"]
+#[derive(Clone, Debug)]
+pub struct MonotonicVecStrategy<T: Strategy<Value = f64>> {
+    vec: VecStrategy<T>,
+    strict: bool,
+}
+
+/// Create a strategy to generate increasing `Vec<f64>`s with a size range
+/// given by `size`; if `strict` is true, each element is strictly greater
+/// than the one before it, and otherwise greater than or equal to it.
+///
+/// The first element is drawn from `element`, and each later element adds
+/// the magnitude of another value drawn from `element` to the one before it.
+/// NaN values are treated as 0. In strict mode, a sum which fails to exceed
+/// the previous element (e.g., because the gap is 0 or lost to rounding) is
+/// replaced by the next representable `f64`. `element` should produce finite
+/// values small enough that the sum cannot overflow, as no element can exceed
+/// infinity.
+///
+/// Since the order holds by construction, shrinking, which removes elements
+/// and shrinks the first element and the gaps towards 0, preserves it too.
+pub fn monotonic_vec<T: Strategy<Value = f64>>(
+    element: T,
+    size: impl Into<SizeRange>,
+    strict: bool,
+) -> MonotonicVecStrategy<T> {
+    MonotonicVecStrategy {
+        vec: vec(element, size),
+        strict,
+    }
+}
+
+impl<T: Strategy<Value = f64>> Strategy for MonotonicVecStrategy<T> {
+    type Tree = MonotonicVecValueTree<T::Tree>;
+    type Value = Vec<f64>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        Ok(MonotonicVecValueTree {
+            vec: self.vec.new_tree(runner)?,
+            strict: self.strict,
+        })
+    }
+}
+
+/// `ValueTree` corresponding to `MonotonicVecStrategy`.
+#[derive(Clone, Debug)]
+pub struct MonotonicVecValueTree<T: ValueTree<Value = f64>> {
+    // The first element followed by the gaps.
+    vec: VecValueTree<T>,
+    strict: bool,
+}
+
+/// The smallest `f64` greater than `x`, or infinity if there is none.
+fn next_up(x: f64) -> f64 {
+    if x == f64::INFINITY {
+        x
+    } else if x == 0.0 {
+        f64::from_bits(1)
+    } else if x > 0.0 {
+        f64::from_bits(x.to_bits() + 1)
+    } else {
+        f64::from_bits(x.to_bits() - 1)
+    }
+}
+
+impl<T: ValueTree<Value = f64>> ValueTree for MonotonicVecValueTree<T> {
+    type Value = Vec<f64>;
+
+    fn current(&self) -> Vec<f64> {
+        let mut values = Vec::<f64>::new();
+        for x in self.vec.included() {
+            let x = if x.is_nan() { 0.0 } else { x };
+            let value = match values.last() {
+                None => x,
+                Some(&prev) => {
+                    let next = prev + if x < 0.0 { -x } else { x };
+                    if self.strict && next <= prev {
+                        next_up(prev)
+                    } else {
+                        next
+                    }
+                }
+            };
+            values.push(value);
+        }
+        values
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.vec.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.vec.complicate()
+    }
+}
+
+//==============================================================================
+// Disjoint intervals
+//==============================================================================
+
+/// Strategy to create sorted `Vec`s of non-overlapping intervals.
+///
+/// Created by the `disjoint_intervals()` function in the same module.
+#[must_use = "strategies do nothing unless used"]
+#[derive(Clone, Debug)]
+pub struct DisjointIntervalsStrategy<T: Strategy> {
+    breakpoints: VecStrategy<(T, T)>,
+}
+
+/// Create a strategy to generate `Vec`s of half-open intervals
+/// `start..end`, sorted and pairwise disjoint, with a number of intervals
+/// within `count`, e.g., for testing interval trees or range maps.
+///
+/// The intervals are built from twice as many breakpoints drawn from
+/// `point`, which are sorted and paired up in order, so the intervals never
+/// overlap by construction. An interval is empty if both of its breakpoints
+/// are equal, and adjacent intervals touch if they share a breakpoint.
+///
+/// Shrinking removes intervals and shrinks the breakpoints, which narrows or
+/// moves the intervals but keeps them sorted and disjoint.
+pub fn disjoint_intervals<T: Strategy + Clone>(
+    point: T,
+    count: impl Into<SizeRange>,
+) -> DisjointIntervalsStrategy<T>
+where
+    T::Value: Ord,
+{
+    DisjointIntervalsStrategy {
+        breakpoints: vec((point.clone(), point), count),
+    }
+}
+
+impl<T: Strategy> Strategy for DisjointIntervalsStrategy<T>
+where
+    T::Value: Ord,
+{
+    type Tree = DisjointIntervalsValueTree<T::Tree>;
+    type Value = Vec<Range<T::Value>>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        Ok(DisjointIntervalsValueTree {
+            breakpoints: self.breakpoints.new_tree(runner)?,
+        })
+    }
+}
+
+/// `ValueTree` corresponding to `DisjointIntervalsStrategy`.
+#[derive(Clone, Debug)]
+pub struct DisjointIntervalsValueTree<T: ValueTree> {
+    breakpoints: VecValueTree<TupleValueTree<(T, T)>>,
+}
+
+impl<T: ValueTree> ValueTree for DisjointIntervalsValueTree<T>
+where
+    T::Value: Ord,
+{
+    type Value = Vec<Range<T::Value>>;
+
+    fn current(&self) -> Vec<Range<T::Value>> {
+        let mut points = Vec::new();
+        for (a, b) in self.breakpoints.included() {
+            points.push(a);
+            points.push(b);
+        }
+        points.sort();
+
+        let mut points = points.into_iter();
+        let mut intervals = Vec::with_capacity(points.len() / 2);
+        while let (Some(start), Some(end)) = (points.next(), points.next()) {
+            intervals.push(start..end);
+        }
+        intervals
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.breakpoints.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.breakpoints.complicate()
+    }
+}
+
 //==============================================================================
 // Tests
 //==============================================================================
@@ -714,7 +1924,7 @@ mod test {
 
             match result {
                 Ok(true) => num_successes += 1,
-                Err(TestError::Fail(_, value)) => {
+                Err(TestError::Fail(_, value, _, _)) => {
                     // The minimal case always has between 5 (due to min
                     // length) and 9 (min element value = 1) elements, and
                     // always sums to exactly 9.
@@ -733,6 +1943,351 @@ mod test {
         assert!(num_successes < 256);
     }
 
+    /// Find the minimal failing `Vec` for `fails` with each of 32 seeds and
+    /// return their total length.
+    fn total_minimal_len(
+        order: ShrinkOrder,
+        fails: fn(&[u8]) -> bool,
+    ) -> usize {
+        let input = vec(0..10u8, 0..40).with_shrink_order(order);
+        let mut rng = TestRng::deterministic_rng(RngAlgorithm::ChaCha);
+        let mut total = 0;
+        for _ in 0..32 {
+            let config = Config {
+                failure_persistence: None,
+                ..Config::default()
+            };
+            let mut runner = TestRunner::new_with_rng(config, rng.gen_rng());
+            let result = runner.run(&input, |v| {
+                if fails(&v) {
+                    Err(TestCaseError::fail("fails"))
+                } else {
+                    Ok(())
+                }
+            });
+            if let Err(TestError::Fail(_, v, ..)) = result {
+                total += v.len();
+            }
+        }
+        total
+    }
+
+    #[test]
+    fn test_vec_shrink_order_prefix_and_suffix() {
+        // Whether the first element exceeds the sum of the next two, so
+        // deleting from the front changes which elements are compared.
+        fn prefix(v: &[u8]) -> bool {
+            v.len() >= 2 && v[0] > v[1].saturating_add(*v.get(2).unwrap_or(&0))
+        }
+        fn suffix(v: &[u8]) -> bool {
+            let v = v.iter().rev().cloned().collect::<Vec<_>>();
+            prefix(&v)
+        }
+
+        let front = total_minimal_len(ShrinkOrder::FrontFirst, prefix);
+        let back = total_minimal_len(ShrinkOrder::BackFirst, prefix);
+        assert!(back < front, "back {} >= front {}", back, front);
+
+        let front = total_minimal_len(ShrinkOrder::FrontFirst, suffix);
+        let back = total_minimal_len(ShrinkOrder::BackFirst, suffix);
+        assert!(front < back, "front {} >= back {}", front, back);
+    }
+
+    #[test]
+    fn test_vec_shrink_order_respects_min_size() {
+        for &order in &[
+            ShrinkOrder::FrontFirst,
+            ShrinkOrder::BackFirst,
+            ShrinkOrder::Bisect,
+        ] {
+            let input = vec(0u8..10, 3..=17).with_shrink_order(order);
+            let mut runner = TestRunner::deterministic();
+            for _ in 0..64 {
+                let mut tree = input.new_tree(&mut runner).unwrap();
+                while tree.simplify() {
+                    assert!(tree.current().len() >= 3);
+                }
+                assert_eq!(vec![0, 0, 0], tree.current());
+            }
+        }
+    }
+
+    #[test]
+    fn test_vec_bisect_sanity() {
+        check_strategy_sanity(
+            vec(0u8..10, 0..=17).with_shrink_order(ShrinkOrder::Bisect),
+            None,
+        );
+    }
+
+    #[test]
+    fn test_disjoint_intervals() {
+        fn assert_disjoint(intervals: &[Range<u32>]) {
+            for interval in intervals {
+                assert!(interval.start <= interval.end, "{:?}", intervals);
+            }
+            for pair in intervals.windows(2) {
+                assert!(pair[0].end <= pair[1].start, "{:?}", intervals);
+            }
+        }
+
+        let input = disjoint_intervals(0..1000u32, 1..10);
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            let intervals = case.current();
+            assert!((1..10).contains(&intervals.len()));
+            assert_disjoint(&intervals);
+            while case.simplify() {
+                assert_disjoint(&case.current());
+            }
+            assert_eq!(vec![0..0], case.current());
+        }
+    }
+
+    #[test]
+    fn test_disjoint_intervals_sanity() {
+        check_strategy_sanity(disjoint_intervals(0..1000u32, 0..10), None);
+    }
+
+    #[test]
+    fn test_vec_with_capacity_hint() {
+        let input = vec(0u8..10, 0..=16).with_capacity_hint(16);
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            assert!(case.current().capacity() >= 16);
+            while case.simplify() {
+                assert!(case.current().capacity() >= 16);
+            }
+            assert!(case.current().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_vec_summing_to() {
+        let mut runner = TestRunner::deterministic();
+        for &(len, total) in &[(0, 0), (1, 0), (1, 7), (5, 0), (5, 100)] {
+            let input = vec_summing_to(len, total);
+            let mut nonzero_rest = false;
+            for _ in 0..256 {
+                let mut case = input.new_tree(&mut runner).unwrap();
+                let value = case.current();
+                assert_eq!(len, value.len());
+                assert_eq!(total, value.iter().sum::<u32>());
+                nonzero_rest |= value.iter().skip(1).any(|&v| v > 0);
+
+                // As if every shrunk value passed the test.
+                let mut passing = case.clone();
+                while passing.simplify() {
+                    assert_eq!(total, passing.current().iter().sum::<u32>());
+                    while passing.complicate() {
+                        let value = passing.current();
+                        assert_eq!(total, value.iter().sum::<u32>());
+                    }
+                }
+                assert_eq!(value, passing.current());
+
+                // As if every shrunk value failed the test.
+                while case.simplify() {
+                    let value = case.current();
+                    assert_eq!(len, value.len());
+                    assert_eq!(total, value.iter().sum::<u32>());
+                }
+                let mut minimal = vec![0; len];
+                if len > 0 {
+                    minimal[0] = total;
+                }
+                assert_eq!(minimal, case.current());
+            }
+            assert_eq!(len > 1 && total > 0, nonzero_rest);
+        }
+    }
+
+    #[test]
+    fn test_monotonic_vec() {
+        fn assert_monotonic(values: &[f64], strict: bool) {
+            for pair in values.windows(2) {
+                if strict {
+                    assert!(pair[0] < pair[1], "{:?}", values);
+                } else {
+                    assert!(pair[0] <= pair[1], "{:?}", values);
+                }
+            }
+        }
+
+        let mut runner = TestRunner::deterministic();
+        for &strict in &[false, true] {
+            let input = monotonic_vec(-100.0..100.0f64, 0..20, strict);
+            for _ in 0..256 {
+                let mut case = input.new_tree(&mut runner).unwrap();
+                let value = case.current();
+                assert!(value.len() < 20);
+                assert_monotonic(&value, strict);
+
+                // As if every shrunk value passed the test.
+                let mut passing = case.clone();
+                while passing.simplify() {
+                    assert_monotonic(&passing.current(), strict);
+                    while passing.complicate() {
+                        assert_monotonic(&passing.current(), strict);
+                    }
+                }
+
+                // As if every shrunk value failed the test.
+                while case.simplify() {
+                    assert_monotonic(&case.current(), strict);
+                }
+                assert!(case.current().is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn monotonic_vec_with_zero_gaps() {
+        let mut runner = TestRunner::deterministic();
+        let value = monotonic_vec(Just(0.0), 3, false)
+            .new_tree(&mut runner)
+            .unwrap()
+            .current();
+        assert_eq!(vec![0.0; 3], value);
+
+        let value = monotonic_vec(Just(0.0), 3, true)
+            .new_tree(&mut runner)
+            .unwrap()
+            .current();
+        assert_eq!(vec![0.0, f64::from_bits(1), f64::from_bits(2)], value);
+    }
+
+    #[test]
+    fn test_monotonic_vec_sanity() {
+        check_strategy_sanity(monotonic_vec(-10.0..10.0f64, 0..8, true), None);
+    }
+
+    #[test]
+    fn test_vec_summing_to_sanity() {
+        check_strategy_sanity(vec_summing_to(8, 1000), None);
+    }
+
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn test_vec_in_bump_allocator() {
+        use crate::std_facade::{Box, Rc};
+        use core::alloc::{AllocError, Layout};
+        use core::cell::Cell;
+        use core::ptr::NonNull;
+
+        // Allocates from a fixed arena and never frees.
+        #[derive(Clone, Debug)]
+        struct Bump(Rc<(Box<[Cell<u8>]>, Cell<usize>)>);
+
+        unsafe impl Allocator for Bump {
+            fn allocate(
+                &self,
+                layout: Layout,
+            ) -> Result<NonNull<[u8]>, AllocError> {
+                let (ref arena, ref used) = *self.0;
+                let base = arena.as_ptr() as usize;
+                let start = (base + used.get() + layout.align() - 1)
+                    & !(layout.align() - 1);
+                let end = start + layout.size();
+                if end > base + arena.len() {
+                    return Err(AllocError);
+                }
+                used.set(end - base);
+                let ptr = arena[start - base..].as_ptr() as *mut u8;
+                Ok(NonNull::slice_from_raw_parts(
+                    NonNull::new(ptr).unwrap(),
+                    layout.size(),
+                ))
+            }
+
+            unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {}
+        }
+
+        let bump = Bump(Rc::new((
+            (0..1 << 20).map(|_| Cell::new(0)).collect(),
+            Cell::new(0),
+        )));
+        let input =
+            vec_in(0u32..100, 2..10, bump.clone()).with_capacity_hint(10);
+
+        let mut runner = TestRunner::deterministic();
+        let mut values = 0;
+        for _ in 0..64 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            loop {
+                let value = case.current();
+                assert!(value.len() >= 2 && value.len() < 10);
+                assert!(value.iter().all(|&v| v < 100));
+                assert_eq!(10, value.capacity());
+                values += 1;
+                if !case.simplify() {
+                    break;
+                }
+            }
+            assert_eq!(&[0, 0], &case.current()[..]);
+            values += 1;
+        }
+
+        // Every value took exactly one allocation of 10 `u32`s from the
+        // arena.
+        assert_eq!(values * 40, (bump.0).1.get());
+    }
+
+    #[test]
+    fn test_vec_with_geometric_size_distribution() {
+        use crate::num;
+
+        let uniform = vec(0u8..10, 0..100);
+        let geometric = vec(
+            0u8..10,
+            SizeRange::with_distribution(0..100, num::usize::geometric(0.2)),
+        );
+
+        let mut runner = TestRunner::deterministic();
+        let mut uniform_short = 0;
+        let mut geometric_short = 0;
+        for _ in 0..1024 {
+            let len = uniform.new_tree(&mut runner).unwrap().current().len();
+            assert!(len < 100);
+            if len < 10 {
+                uniform_short += 1;
+            }
+
+            let len = geometric.new_tree(&mut runner).unwrap().current().len();
+            assert!(len < 100);
+            if len < 10 {
+                geometric_short += 1;
+            }
+        }
+
+        // Roughly 10% of uniform lengths are under 10, versus nearly 90% for
+        // a geometric distribution with p = 0.2.
+        assert!(uniform_short < 200, "uniform_short = {}", uniform_short);
+        assert!(
+            geometric_short > 800,
+            "geometric_short = {}",
+            geometric_short
+        );
+    }
+
+    #[test]
+    fn test_size_distribution_respects_bounds() {
+        let size = SizeRange::with_distribution(5..=8, 0usize..1000);
+        let input = vec(0u8..10, size.clone() + 2);
+        assert_eq!((7, 10), (size.clone() + 2).start_end_incl());
+        assert_eq!(size, size.clone());
+        assert_ne!(size, size_range(5..=8));
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let len = input.new_tree(&mut runner).unwrap().current().len();
+            assert!((7..=10).contains(&len), "len = {}", len);
+        }
+    }
+
     #[test]
     fn test_vec_sanity() {
         check_strategy_sanity(vec(0i32..1000, 5..10), None);
@@ -773,6 +2328,75 @@ mod test {
         }
     }
 
+    /// A weak hasher which just sums the bytes it's given.
+    #[cfg(feature = "std")]
+    #[derive(Default)]
+    struct SumHasher(u64);
+
+    #[cfg(feature = "std")]
+    impl Hasher for SumHasher {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0 += bytes.iter().map(|&b| u64::from(b)).sum::<u64>();
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_hash_map_colliding() {
+        use crate::arbitrary::any;
+        use core::hash::BuildHasherDefault;
+
+        let hasher = BuildHasherDefault::<SumHasher>::default();
+        // The fraction of pairs of keys in the same bucket.
+        let collisions = |keys: Vec<&u32>| {
+            let buckets = keys
+                .into_iter()
+                .map(|k| hash_bucket(&hasher, k))
+                .collect::<Vec<_>>();
+            let mut same = 0;
+            let mut pairs = 0;
+            for (i, a) in buckets.iter().enumerate() {
+                for b in &buckets[i + 1..] {
+                    same += (a == b) as u32;
+                    pairs += 1;
+                }
+            }
+            f64::from(same) / f64::from(pairs)
+        };
+
+        let colliding = hash_map_colliding(
+            any::<u32>(),
+            any::<u8>(),
+            8..16,
+            hasher.clone(),
+        );
+        let mut runner = TestRunner::deterministic();
+        let mut random_collisions = 0.0;
+        for _ in 0..64 {
+            let mut case = colliding.new_tree(&mut runner).unwrap();
+            assert_eq!(1.0, collisions(case.current().keys().collect()));
+            while case.simplify() {
+                assert_eq!(1.0, collisions(case.current().keys().collect()));
+            }
+            assert_eq!(8, case.current().len());
+
+            let random = hash_map(any::<u32>(), any::<u8>(), 8..16)
+                .new_tree(&mut runner)
+                .unwrap()
+                .current();
+            random_collisions += collisions(random.keys().collect()) / 64.0;
+        }
+        assert!(
+            random_collisions < 0.5,
+            "Random keys collided too often: {}",
+            random_collisions
+        );
+    }
+
     #[cfg(feature = "std")]
     #[test]
     fn test_set() {
@@ -785,4 +2409,130 @@ mod test {
             assert_eq!(2, v.len());
         }
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_set_exact_keeps_size_while_shrinking() {
+        let input = hash_set_exact(0u32..40, 10..20);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..256 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            let size = case.current().len();
+            assert!((10..20).contains(&size), "size = {}", size);
+            while case.simplify() {
+                assert_eq!(size, case.current().len());
+            }
+            assert_eq!(size, case.current().len());
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_set_exact_fails_when_element_space_too_small() {
+        // Only 4 possible values
+        let input = hash_set_exact(0u8..4, 5);
+        let mut runner = TestRunner::deterministic();
+
+        let err = input.new_tree(&mut runner).unwrap_err();
+        assert!(err.message().contains("only found 4 of 5"), "{}", err);
+    }
+
+    #[test]
+    fn test_btree_map_adversarial_covers_orderings() {
+        let input = btree_map_adversarial(0u32..1000, 0u8..2, 3..20);
+        let mut runner = TestRunner::deterministic();
+        let (mut ascending, mut descending, mut other) = (0, 0, 0);
+
+        for _ in 0..256 {
+            let entries = input.new_tree(&mut runner).unwrap().current();
+            assert!(entries.len() >= 3 && entries.len() < 20);
+            let keys = entries.iter().map(|&(k, _)| k).collect::<Vec<_>>();
+            let distinct = keys.iter().collect::<BTreeSet<_>>();
+            assert_eq!(keys.len(), distinct.len());
+
+            if keys.windows(2).all(|w| w[0] < w[1]) {
+                ascending += 1;
+            } else if keys.windows(2).all(|w| w[0] > w[1]) {
+                descending += 1;
+            } else {
+                other += 1;
+            }
+        }
+
+        assert!(ascending > 0, "No strictly ascending entries");
+        assert!(descending > 0, "No strictly descending entries");
+        assert!(other > 0, "No unstructured entries");
+    }
+
+    #[test]
+    fn test_btree_map_adversarial_shrinks_to_minimum_size() {
+        let input = btree_map_adversarial(0u32..1000, 0u8..2, 3..20);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..64 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            while case.simplify() {}
+            let entries = case.current();
+            assert_eq!(3, entries.len());
+            assert!(entries.windows(2).all(|w| w[0].0 < w[1].0));
+        }
+    }
+
+    #[test]
+    fn test_grid_stays_rectangular_while_shrinking() {
+        let input = grid(0u32..100, 1..8, 2..6);
+        let mut runner = TestRunner::deterministic();
+
+        fn assert_rectangular(grid: Grid<u32>) {
+            assert_eq!(grid.rows() * grid.cols(), grid.cells().len());
+            let rows = grid.clone().into_rows();
+            assert_eq!(grid.rows(), rows.len());
+            for (ix, row) in rows.iter().enumerate() {
+                assert_eq!(grid.cols(), row.len());
+                assert_eq!(grid.row(ix), &row[..]);
+            }
+        }
+
+        for _ in 0..256 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            let start = case.current();
+            assert!((1..8).contains(&start.rows()));
+            assert!((2..6).contains(&start.cols()));
+            assert_rectangular(start);
+
+            while case.simplify() {
+                assert_rectangular(case.current());
+                if runner.rng().gen() {
+                    case.complicate();
+                    assert_rectangular(case.current());
+                }
+            }
+
+            let end = case.current();
+            assert!(end.rows() >= 1 && end.cols() >= 2);
+            assert_rectangular(end);
+        }
+    }
+
+    #[test]
+    fn test_grid_shrinks_to_minimum_dimensions() {
+        let input = grid(0u32..100, 1..8, 2..6);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..64 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            while case.simplify() {}
+            let end = case.current();
+            assert_eq!((1, 2), (end.rows(), end.cols()));
+            assert_eq!(&[0, 0], end.cells());
+            assert_eq!(None, end.get(1, 0));
+            assert_eq!(Some(&0), end.get(0, 1));
+        }
+    }
+
+    #[test]
+    fn test_grid_sanity() {
+        check_strategy_sanity(grid(0i32..1000, 0..5, 0..5), None);
+    }
 }
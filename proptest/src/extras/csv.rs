@@ -0,0 +1,314 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Strategies for generating CSV tables, e.g., for testing CSV parsers.
+
+use crate::std_facade::{String, Vec};
+
+use crate::collection::SizeRange;
+use crate::strategy::*;
+use crate::test_runner::*;
+
+/// Strategy to create CSV tables together with their rendering.
+///
+/// Created by the `table()` function in the same module.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct TableStrategy<S> {
+    rows: SizeRange,
+    cols: SizeRange,
+    field: S,
+}
+
+/// Create a strategy to generate tables with a number of rows within `rows`
+/// and a number of columns within `cols`, whose fields are generated by
+/// `field`, together with their rendering as CSV text, as `(rows, text)`
+/// pairs.
+///
+/// The text follows RFC 4180: fields are separated by commas and each row,
+/// including the last, is terminated by `\r\n`. Fields containing commas,
+/// double quotes, carriage returns or line feeds are enclosed in double
+/// quotes, with any double quotes doubled. An empty field in a single-column
+/// row is also quoted, so that the row does not render as an empty line.
+///
+/// Shrinking first removes rows, then columns, and then simplifies the
+/// remaining fields, rendering the text from the shrunk table each time, so
+/// every row always has the same number of fields, and the text always
+/// parses back to the table.
+///
+/// ```
+/// use proptest::extras::csv;
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn lines_match_rows((rows, text) in csv::table(0..8, 1..4, "[a-z]{0,4}")) {
+///     // Without quoted line breaks, each row is one line.
+///     prop_assert_eq!(rows.len(), text.lines().count());
+///   }
+/// }
+/// #
+/// # fn main() { lines_match_rows(); }
+/// ```
+///
+/// ## Panics
+///
+/// Panics if `cols` includes 0, since rows without fields cannot be told
+/// apart from empty lines.
+pub fn table<S: Strategy<Value = String>>(
+    rows: impl Into<SizeRange>,
+    cols: impl Into<SizeRange>,
+    field: S,
+) -> TableStrategy<S> {
+    let cols = cols.into();
+    assert!(cols.start() > 0, "CSV tables require at least one column");
+    TableStrategy {
+        rows: rows.into(),
+        cols,
+        field,
+    }
+}
+
+impl<S: Strategy<Value = String>> Strategy for TableStrategy<S> {
+    type Tree = TableValueTree<S::Tree>;
+    type Value = (Vec<Vec<String>>, String);
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let rows = self.rows.sample(runner)?;
+        let cols = self.cols.sample(runner)?;
+        let mut fields = Vec::with_capacity(rows);
+        for _ in 0..rows {
+            let row = (0..cols)
+                .map(|_| self.field.new_tree(runner))
+                .collect::<Result<Vec<_>, _>>()?;
+            fields.push(row);
+        }
+
+        Ok(TableValueTree {
+            fields,
+            included_rows: vec![true; rows],
+            included_cols: vec![true; cols],
+            min_rows: self.rows.start(),
+            min_cols: self.cols.start(),
+            shrink: Shrink::DeleteRow(0),
+            prev_shrink: None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Shrink {
+    DeleteRow(usize),
+    DeleteCol(usize),
+    /// Simplify the field at the given row and column.
+    Field(usize, usize),
+}
+
+/// `ValueTree` corresponding to `TableStrategy`.
+#[derive(Clone, Debug)]
+pub struct TableValueTree<T> {
+    fields: Vec<Vec<T>>,
+    included_rows: Vec<bool>,
+    included_cols: Vec<bool>,
+    min_rows: usize,
+    min_cols: usize,
+    shrink: Shrink,
+    prev_shrink: Option<Shrink>,
+}
+
+/// Appends `field` to `text`, quoting it if necessary.
+fn push_field(text: &mut String, field: &str, only_field: bool) {
+    if field.contains(|c| [',', '"', '\r', '\n'].contains(&c))
+        || (only_field && field.is_empty())
+    {
+        text.push('"');
+        text.push_str(&field.replace('"', "\"\""));
+        text.push('"');
+    } else {
+        text.push_str(field);
+    }
+}
+
+fn render(rows: &[Vec<String>]) -> String {
+    let mut text = String::new();
+    for row in rows {
+        for (ix, field) in row.iter().enumerate() {
+            if ix > 0 {
+                text.push(',');
+            }
+            push_field(&mut text, field, 1 == row.len());
+        }
+        text.push_str("\r\n");
+    }
+    text
+}
+
+impl<T: ValueTree<Value = String>> ValueTree for TableValueTree<T> {
+    type Value = (Vec<Vec<String>>, String);
+
+    fn current(&self) -> (Vec<Vec<String>>, String) {
+        let rows = self
+            .fields
+            .iter()
+            .zip(&self.included_rows)
+            .filter(|&(_, &included)| included)
+            .map(|(row, _)| {
+                row.iter()
+                    .zip(&self.included_cols)
+                    .filter(|&(_, &included)| included)
+                    .map(|(field, _)| field.current())
+                    .collect()
+            })
+            .collect::<Vec<Vec<String>>>();
+        let text = render(&rows);
+        (rows, text)
+    }
+
+    fn simplify(&mut self) -> bool {
+        // Rows and columns are deleted in order, and only restored by
+        // `complicate()` right after being deleted, so the one at `ix` is
+        // always still included.
+        if let Shrink::DeleteRow(ix) = self.shrink {
+            let rows = self.included_rows.iter().filter(|&&i| i).count();
+            if ix < self.included_rows.len() && rows > self.min_rows {
+                self.included_rows[ix] = false;
+                self.prev_shrink = Some(self.shrink);
+                self.shrink = Shrink::DeleteRow(ix + 1);
+                return true;
+            }
+            self.shrink = Shrink::DeleteCol(0);
+        }
+
+        if let Shrink::DeleteCol(ix) = self.shrink {
+            let cols = self.included_cols.iter().filter(|&&i| i).count();
+            if ix < self.included_cols.len() && cols > self.min_cols {
+                self.included_cols[ix] = false;
+                self.prev_shrink = Some(self.shrink);
+                self.shrink = Shrink::DeleteCol(ix + 1);
+                return true;
+            }
+            self.shrink = Shrink::Field(0, 0);
+        }
+
+        while let Shrink::Field(row, col) = self.shrink {
+            if row >= self.fields.len() {
+                // Nothing more we can do
+                return false;
+            }
+
+            if !self.included_rows[row] || col >= self.included_cols.len() {
+                self.shrink = Shrink::Field(row + 1, 0);
+            } else if !self.included_cols[col]
+                || !self.fields[row][col].simplify()
+            {
+                self.shrink = Shrink::Field(row, col + 1);
+            } else {
+                self.prev_shrink = Some(self.shrink);
+                return true;
+            }
+        }
+
+        panic!("Unexpected shrink state");
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.prev_shrink {
+            None => false,
+            Some(Shrink::DeleteRow(ix)) => {
+                // Keep this row and move on to the next one.
+                self.included_rows[ix] = true;
+                self.prev_shrink = None;
+                true
+            }
+            Some(Shrink::DeleteCol(ix)) => {
+                self.included_cols[ix] = true;
+                self.prev_shrink = None;
+                true
+            }
+            Some(Shrink::Field(row, col)) => {
+                if self.fields[row][col].complicate() {
+                    true
+                } else {
+                    self.prev_shrink = None;
+                    false
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::collection::vec;
+    use crate::sample::select;
+
+    fn row(fields: &[&str]) -> Vec<String> {
+        fields.iter().map(|&field| String::from(field)).collect()
+    }
+
+    #[test]
+    fn renders_rfc_4180_examples() {
+        // The examples of section 2 of RFC 4180, quoting only the fields
+        // which need it.
+        let rows =
+            vec![row(&["aaa", "bbb", "ccc"]), row(&["zzz", "yyy", "xxx"])];
+        assert_eq!("aaa,bbb,ccc\r\nzzz,yyy,xxx\r\n", render(&rows));
+        let rows = vec![row(&["aaa", "b\r\nbb", "ccc"])];
+        assert_eq!("aaa,\"b\r\nbb\",ccc\r\n", render(&rows));
+        let rows = vec![row(&["aaa", "b\"bb", "ccc"])];
+        assert_eq!("aaa,\"b\"\"bb\",ccc\r\n", render(&rows));
+        let rows = vec![row(&["a,b", "", "\""]), row(&["\n", "c", ""])];
+        assert_eq!("\"a,b\",,\"\"\"\"\r\n\"\n\",c,\r\n", render(&rows));
+        let rows = vec![row(&[""]), row(&["a"])];
+        assert_eq!("\"\"\r\na\r\n", render(&rows));
+    }
+
+    #[test]
+    fn rows_stay_rectangular_while_shrinking() {
+        let input = table(0..6, 1..5, "[a,\"\r\n ]{0,4}");
+        let mut runner = TestRunner::deterministic();
+        let mut max_cols = 0;
+        for _ in 0..256 {
+            let mut val = input.new_tree(&mut runner).unwrap();
+            loop {
+                let (rows, _) = val.current();
+                let cols = rows.first().map_or(0, Vec::len);
+                assert!(rows.iter().all(|row| cols == row.len()));
+                max_cols = max_cols.max(cols);
+                if !val.simplify() {
+                    break;
+                }
+            }
+            assert_eq!((Vec::new(), String::new()), val.current());
+        }
+        assert_eq!(4, max_cols);
+    }
+
+    #[test]
+    fn shrinks_to_minimum_size() {
+        let input = table(2..6, 2..5, "[a-z]{1,4}");
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut val = input.new_tree(&mut runner).unwrap();
+            while val.simplify() {}
+            let rows = vec![vec![String::from("a"), String::from("a")]; 2];
+            assert_eq!((rows, String::from("a,a\r\na,a\r\n")), val.current());
+        }
+    }
+
+    #[test]
+    fn test_sanity() {
+        let field = vec(select(&['a', ',', '"'][..]), 0..3)
+            .prop_map(|chars| chars.into_iter().collect());
+        check_strategy_sanity(table(0..4, 1..4, field), None);
+    }
+}
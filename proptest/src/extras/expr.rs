@@ -0,0 +1,362 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Strategies for generating well-formed arithmetic expression trees.
+//!
+//! This is mostly useful for testing parsers, interpreters and compilers of
+//! small expression languages, e.g., checking that printing and re-parsing an
+//! expression is the identity, or that an optimiser preserves the value
+//! computed by `Expr::eval()`.
+//!
+//! ```
+//! use proptest::extras::expr::{arith_expr, ArithExprConfig};
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!   # /*
+//!   #[test]
+//!   # */
+//!   fn display_is_fully_parenthesised(e in arith_expr(ArithExprConfig::default())) {
+//!     let printed = e.to_string();
+//!     prop_assert_eq!(
+//!       printed.matches('(').count(), printed.matches(')').count());
+//!   }
+//! }
+//! #
+//! # fn main() { display_is_fully_parenthesised(); }
+//! ```
+
+use crate::std_facade::{fmt, Box, Vec};
+use core::ops::RangeInclusive;
+
+use crate::sample::select;
+use crate::strategy::*;
+
+/// A binary operator of an `Expr`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BinOp {
+    /// Addition, written `+`.
+    Add,
+    /// Subtraction, written `-`.
+    Sub,
+    /// Multiplication, written `*`.
+    Mul,
+    /// Truncating integer division, written `/`.
+    Div,
+    /// Remainder of truncating integer division, written `%`.
+    Rem,
+}
+
+impl BinOp {
+    /// All binary operators.
+    pub const ALL: &'static [BinOp] =
+        &[BinOp::Add, BinOp::Sub, BinOp::Mul, BinOp::Div, BinOp::Rem];
+
+    /// Applies the operator, returning `None` on overflow or division by
+    /// zero.
+    pub fn apply(self, lhs: i64, rhs: i64) -> Option<i64> {
+        match self {
+            BinOp::Add => lhs.checked_add(rhs),
+            BinOp::Sub => lhs.checked_sub(rhs),
+            BinOp::Mul => lhs.checked_mul(rhs),
+            BinOp::Div => lhs.checked_div(rhs),
+            BinOp::Rem => lhs.checked_rem(rhs),
+        }
+    }
+}
+
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::Rem => "%",
+        })
+    }
+}
+
+/// A unary operator of an `Expr`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UnaryOp {
+    /// Negation, written `-`.
+    Neg,
+    /// Absolute value, written `abs`.
+    Abs,
+}
+
+impl UnaryOp {
+    /// All unary operators.
+    pub const ALL: &'static [UnaryOp] = &[UnaryOp::Neg, UnaryOp::Abs];
+
+    /// Applies the operator, returning `None` on overflow.
+    pub fn apply(self, operand: i64) -> Option<i64> {
+        match self {
+            UnaryOp::Neg => operand.checked_neg(),
+            UnaryOp::Abs => operand.checked_abs(),
+        }
+    }
+}
+
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            UnaryOp::Neg => "-",
+            UnaryOp::Abs => "abs",
+        })
+    }
+}
+
+/// An arithmetic expression over `i64`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Expr {
+    /// An integer literal.
+    Lit(i64),
+    /// A unary operator applied to an expression.
+    Unary(UnaryOp, Box<Expr>),
+    /// A binary operator applied to two expressions.
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    /// An expression in explicit parentheses, which evaluates to the same
+    /// value as the inner expression.
+    Paren(Box<Expr>),
+}
+
+impl Expr {
+    /// Evaluates the expression, returning `None` if any operation overflows
+    /// or divides by zero.
+    ///
+    /// This is a reference evaluator, intended as the oracle against which
+    /// an implementation under test is compared.
+    pub fn eval(&self) -> Option<i64> {
+        match *self {
+            Expr::Lit(value) => Some(value),
+            Expr::Unary(op, ref operand) => op.apply(operand.eval()?),
+            Expr::Binary(op, ref lhs, ref rhs) => {
+                op.apply(lhs.eval()?, rhs.eval()?)
+            }
+            Expr::Paren(ref inner) => inner.eval(),
+        }
+    }
+
+    /// Returns the number of levels of the tree, where a literal has depth
+    /// 1.
+    pub fn depth(&self) -> u32 {
+        match *self {
+            Expr::Lit(_) => 1,
+            Expr::Unary(_, ref operand) => 1 + operand.depth(),
+            Expr::Binary(_, ref lhs, ref rhs) => {
+                1 + lhs.depth().max(rhs.depth())
+            }
+            Expr::Paren(ref inner) => 1 + inner.depth(),
+        }
+    }
+}
+
+/// Writes the expression in infix notation. Every unary and binary operation
+/// is enclosed in parentheses, so the output parses back unambiguously
+/// without precedence rules. `Paren` nodes add a further pair.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Expr::Lit(value) if value < 0 => write!(f, "({})", value),
+            Expr::Lit(value) => write!(f, "{}", value),
+            Expr::Unary(UnaryOp::Abs, ref operand) => {
+                write!(f, "(abs {})", operand)
+            }
+            Expr::Unary(op, ref operand) => write!(f, "({}{})", op, operand),
+            Expr::Binary(op, ref lhs, ref rhs) => {
+                write!(f, "({} {} {})", lhs, op, rhs)
+            }
+            Expr::Paren(ref inner) => write!(f, "({})", inner),
+        }
+    }
+}
+
+/// Configuration for `arith_expr()`.
+#[derive(Clone, Debug)]
+pub struct ArithExprConfig {
+    /// The literals to generate. Literals shrink towards `0`, or towards the
+    /// bound of the range closest to it.
+    pub literals: RangeInclusive<i64>,
+    /// The binary operators to generate. If empty, no `Binary` nodes are
+    /// generated.
+    pub binary_ops: Vec<BinOp>,
+    /// The unary operators to generate. If empty, no `Unary` nodes are
+    /// generated.
+    pub unary_ops: Vec<UnaryOp>,
+    /// Whether to generate `Paren` nodes.
+    pub parens: bool,
+    /// The maximum number of levels of operators above each literal, as for
+    /// the `depth` parameter of `Strategy::prop_recursive()`.
+    pub depth: u32,
+    /// The targeted number of nodes, as for the `desired_size` parameter of
+    /// `Strategy::prop_recursive()`.
+    pub desired_size: u32,
+}
+
+impl Default for ArithExprConfig {
+    fn default() -> Self {
+        ArithExprConfig {
+            literals: 0..=100,
+            binary_ops: BinOp::ALL.to_vec(),
+            unary_ops: UnaryOp::ALL.to_vec(),
+            parens: true,
+            depth: 6,
+            desired_size: 32,
+        }
+    }
+}
+
+/// Create a strategy to generate arithmetic expressions as configured by
+/// `config`.
+///
+/// Generated expressions are at most `config.depth + 1` levels deep (see
+/// `Expr::depth()`) and only use the configured operators. They are
+/// well-formed, but may still overflow or divide by zero when evaluated; see
+/// `Expr::eval()`.
+///
+/// Expressions shrink towards a single literal.
+pub fn arith_expr(config: ArithExprConfig) -> BoxedStrategy<Expr> {
+    let leaf = config.literals.clone().prop_map(Expr::Lit).boxed();
+    if config.binary_ops.is_empty()
+        && config.unary_ops.is_empty()
+        && !config.parens
+    {
+        return leaf;
+    }
+
+    leaf.prop_recursive(config.depth, config.desired_size, 2, move |inner| {
+        let mut branches = Vec::new();
+        if !config.binary_ops.is_empty() {
+            branches.push(
+                (
+                    select(config.binary_ops.clone()),
+                    inner.clone(),
+                    inner.clone(),
+                )
+                    .prop_map(|(op, lhs, rhs)| {
+                        Expr::Binary(op, Box::new(lhs), Box::new(rhs))
+                    })
+                    .boxed(),
+            );
+        }
+        if !config.unary_ops.is_empty() {
+            branches.push(
+                (select(config.unary_ops.clone()), inner.clone())
+                    .prop_map(|(op, operand)| {
+                        Expr::Unary(op, Box::new(operand))
+                    })
+                    .boxed(),
+            );
+        }
+        if config.parens {
+            branches.push(
+                inner.prop_map(|inner| Expr::Paren(Box::new(inner))).boxed(),
+            );
+        }
+        Union::new(branches)
+    })
+    .boxed()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::std_facade::string::ToString;
+    use crate::test_runner::TestRunner;
+
+    fn uses_only(expr: &Expr, config: &ArithExprConfig) -> bool {
+        match *expr {
+            Expr::Lit(value) => config.literals.contains(&value),
+            Expr::Unary(op, ref operand) => {
+                config.unary_ops.contains(&op) && uses_only(operand, config)
+            }
+            Expr::Binary(op, ref lhs, ref rhs) => {
+                config.binary_ops.contains(&op)
+                    && uses_only(lhs, config)
+                    && uses_only(rhs, config)
+            }
+            Expr::Paren(ref inner) => config.parens && uses_only(inner, config),
+        }
+    }
+
+    #[test]
+    fn generated_exprs_respect_config_and_evaluate() {
+        let configs = vec![
+            ArithExprConfig::default(),
+            ArithExprConfig {
+                literals: -5..=5,
+                binary_ops: vec![BinOp::Div, BinOp::Rem],
+                unary_ops: vec![],
+                parens: false,
+                depth: 3,
+                ..ArithExprConfig::default()
+            },
+            ArithExprConfig {
+                literals: i64::MIN..=i64::MAX,
+                unary_ops: vec![UnaryOp::Neg],
+                ..ArithExprConfig::default()
+            },
+        ];
+
+        for config in configs {
+            let mut runner = TestRunner::deterministic();
+            let strategy = arith_expr(config.clone());
+            let mut evaluated = 0;
+            for _ in 0..256 {
+                let expr = strategy.new_tree(&mut runner).unwrap().current();
+                assert!(uses_only(&expr, &config), "Unexpected {:?}", expr);
+                assert!(expr.depth() <= config.depth + 1, "Too deep {}", expr);
+                if expr.eval().is_some() {
+                    evaluated += 1;
+                }
+            }
+            assert!(evaluated > 0, "Nothing evaluated for {:?}", config);
+        }
+    }
+
+    #[test]
+    fn shrinks_to_a_single_literal() {
+        let mut runner = TestRunner::deterministic();
+        let strategy = arith_expr(ArithExprConfig::default());
+        for _ in 0..64 {
+            let mut tree = strategy.new_tree(&mut runner).unwrap();
+            while tree.simplify() {}
+            assert_eq!(Expr::Lit(0), tree.current());
+        }
+    }
+
+    #[test]
+    fn display_is_fully_parenthesised() {
+        let expr = Expr::Binary(
+            BinOp::Mul,
+            Box::new(Expr::Unary(UnaryOp::Neg, Box::new(Expr::Lit(-3)))),
+            Box::new(Expr::Paren(Box::new(Expr::Binary(
+                BinOp::Add,
+                Box::new(Expr::Lit(1)),
+                Box::new(Expr::Unary(UnaryOp::Abs, Box::new(Expr::Lit(2)))),
+            )))),
+        );
+        assert_eq!("((-(-3)) * ((1 + (abs 2))))", expr.to_string());
+        assert_eq!(Some(9), expr.eval());
+    }
+
+    #[test]
+    fn eval_reports_overflow_and_division_by_zero() {
+        let div_zero = Expr::Binary(
+            BinOp::Div,
+            Box::new(Expr::Lit(1)),
+            Box::new(Expr::Lit(0)),
+        );
+        assert_eq!(None, div_zero.eval());
+        let overflow = Expr::Unary(UnaryOp::Neg, Box::new(Expr::Lit(i64::MIN)));
+        assert_eq!(None, overflow.eval());
+    }
+}
@@ -0,0 +1,477 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Strategies for generating sequences accepted by a finite-state automaton.
+//!
+//! An `Fsa` is a set of states connected by transitions, each labeled with a
+//! strategy, built with `Fsa::builder()`. `Fsa::strategy()` then generates
+//! the sequences of labels along paths from the start state to an accepting
+//! state, which is mostly useful for testing protocols and state machines
+//! whose valid inputs follow a fixed shape.
+//!
+//! ```
+//! use proptest::extras::fsa::Fsa;
+//! use proptest::prelude::*;
+//!
+//! // A connection is opened, used any number of times, and closed.
+//! fn sessions() -> impl Strategy<Value = Vec<String>> {
+//!     Fsa::builder()
+//!         .transition("idle", Just("open".to_owned()), "open")
+//!         .transition("open", "(read|write) [0-9]", "open")
+//!         .transition("open", Just("close".to_owned()), "closed")
+//!         .accept("closed")
+//!         .build("idle")
+//!         .strategy(10)
+//! }
+//!
+//! proptest! {
+//!   # /*
+//!   #[test]
+//!   # */
+//!   fn sessions_are_closed(session in sessions()) {
+//!     prop_assert_eq!("open", &session[0]);
+//!     prop_assert_eq!("close", session.last().unwrap());
+//!   }
+//! }
+//! #
+//! # fn main() { sessions_are_closed(); }
+//! ```
+
+use crate::std_facade::{fmt, Arc, BTreeMap, Box, Vec};
+
+use rand::Rng;
+
+use crate::strategy::*;
+use crate::test_runner::*;
+
+#[derive(Clone, Debug)]
+struct Transition<T> {
+    from: usize,
+    label: BoxedStrategy<T>,
+    to: usize,
+}
+
+/// Builder for `Fsa`.
+///
+/// Created by `Fsa::builder()`.
+#[derive(Clone, Debug)]
+pub struct FsaBuilder<T> {
+    states: BTreeMap<&'static str, usize>,
+    transitions: Vec<Transition<T>>,
+    accepting: Vec<&'static str>,
+}
+
+impl<T> Default for FsaBuilder<T> {
+    fn default() -> Self {
+        FsaBuilder {
+            states: BTreeMap::new(),
+            transitions: Vec::new(),
+            accepting: Vec::new(),
+        }
+    }
+}
+
+impl<T: fmt::Debug + 'static> FsaBuilder<T> {
+    fn state(&mut self, name: &'static str) -> usize {
+        let next = self.states.len();
+        *self.states.entry(name).or_insert(next)
+    }
+
+    /// Add a transition from state `from` to state `to`, whose labels are
+    /// generated by `label`.
+    ///
+    /// States are created by mentioning them, and there may be any number of
+    /// transitions between the same pair of states.
+    pub fn transition(
+        mut self,
+        from: &'static str,
+        label: impl Strategy<Value = T> + 'static,
+        to: &'static str,
+    ) -> Self {
+        let from = self.state(from);
+        let to = self.state(to);
+        self.transitions.push(Transition {
+            from,
+            label: label.boxed(),
+            to,
+        });
+        self
+    }
+
+    /// Make `state` an accepting state, where sequences may end.
+    pub fn accept(mut self, state: &'static str) -> Self {
+        self.state(state);
+        self.accepting.push(state);
+        self
+    }
+
+    /// Finish building an automaton starting in state `start`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if no accepting state can be reached from `start`.
+    pub fn build(mut self, start: &'static str) -> Fsa<T> {
+        let start = self.state(start);
+        let mut accepting = vec![false; self.states.len()];
+        for state in &self.accepting {
+            accepting[self.states[state]] = true;
+        }
+
+        // Find the length of the shortest path from every state to an
+        // accepting state by iterating to a fixed point, like Bellman-Ford.
+        let mut distances = accepting
+            .iter()
+            .map(|&accepting| if accepting { Some(0) } else { None })
+            .collect::<Vec<_>>();
+        loop {
+            let mut changed = false;
+            for transition in &self.transitions {
+                if let Some(distance) = distances[transition.to] {
+                    let distance = Some(distance + 1);
+                    let current = distances[transition.from];
+                    if current.map_or(true, |current| Some(current) > distance)
+                    {
+                        distances[transition.from] = distance;
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let start_name = self
+            .states
+            .iter()
+            .find(|&(_, &ix)| ix == start)
+            .map(|(&name, _)| name)
+            .unwrap();
+        assert!(
+            distances[start].is_some(),
+            "no accepting state can be reached from start state {}",
+            start_name
+        );
+
+        let mut outgoing = vec![Vec::new(); self.states.len()];
+        for (ix, transition) in self.transitions.iter().enumerate() {
+            outgoing[transition.from].push(ix);
+        }
+
+        Fsa {
+            transitions: self.transitions,
+            outgoing,
+            accepting,
+            distances,
+            start,
+        }
+    }
+}
+
+/// A finite-state automaton whose transitions are labeled with strategies.
+///
+/// Created with `Fsa::builder()`.
+#[derive(Clone, Debug)]
+pub struct Fsa<T> {
+    transitions: Vec<Transition<T>>,
+    // The indices of the transitions leaving each state.
+    outgoing: Vec<Vec<usize>>,
+    accepting: Vec<bool>,
+    // The length of the shortest path from each state to an accepting state,
+    // or `None` if there is no such path.
+    distances: Vec<Option<usize>>,
+    start: usize,
+}
+
+impl<T: fmt::Debug + 'static> Fsa<T> {
+    /// Start building an automaton.
+    pub fn builder() -> FsaBuilder<T> {
+        FsaBuilder::default()
+    }
+}
+
+impl<T: fmt::Debug + Clone + 'static> Fsa<T> {
+    /// Create a strategy to generate the labels along paths of at most
+    /// `max_len` transitions from the start state to an accepting state. If
+    /// every such path is longer than that, the shortest paths are used
+    /// instead.
+    ///
+    /// Paths are generated by walking from the start state, choosing
+    /// uniformly between every transition which can still reach an
+    /// accepting state in time, and stopping if the current state is
+    /// accepting. Shrinking first shortens the path, by cutting out loops
+    /// which return to the same state and by stopping at accepting states
+    /// along the way, and then simplifies the remaining labels, so every
+    /// shrunk sequence is still accepted.
+    pub fn strategy(&self, max_len: usize) -> FsaStrategy<T> {
+        FsaStrategy {
+            max_len: max_len.max(self.distances[self.start].unwrap()),
+            fsa: Arc::new(self.clone()),
+        }
+    }
+}
+
+/// Strategy to create sequences accepted by an `Fsa`.
+///
+/// Created by `Fsa::strategy()`.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct FsaStrategy<T> {
+    fsa: Arc<Fsa<T>>,
+    max_len: usize,
+}
+
+impl<T: fmt::Debug + 'static> Strategy for FsaStrategy<T> {
+    type Tree = FsaValueTree<T>;
+    type Value = Vec<T>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let fsa = &*self.fsa;
+        let mut state = fsa.start;
+        let mut steps = Vec::new();
+        loop {
+            // Only take transitions which leave enough room to reach an
+            // accepting state, which always includes one on a shortest path.
+            let remaining = self.max_len - steps.len();
+            let options = fsa.outgoing[state]
+                .iter()
+                .cloned()
+                .filter(|&ix| {
+                    let to = fsa.transitions[ix].to;
+                    fsa.distances[to].map_or(false, |d| d < remaining)
+                })
+                .collect::<Vec<_>>();
+            let stop = usize::from(fsa.accepting[state]);
+            let choice = runner.rng().gen_range(0..options.len() + stop);
+            if choice == options.len() {
+                break;
+            }
+
+            let transition = &fsa.transitions[options[choice]];
+            steps.push(Step {
+                transition: options[choice],
+                label: transition.label.new_tree(runner)?,
+            });
+            state = transition.to;
+        }
+
+        Ok(FsaValueTree {
+            fsa: Arc::clone(&self.fsa),
+            steps,
+            shrink: Shrink::Cut(0, usize::MAX),
+            prev_shrink: None,
+        })
+    }
+}
+
+struct Step<T> {
+    transition: usize,
+    label: Box<dyn ValueTree<Value = T>>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Shrink {
+    /// Cut out the longest part of the path starting at the first index
+    /// and ending before the second which leaves it accepted.
+    Cut(usize, usize),
+    /// Simplify the label of the given step.
+    Label(usize),
+}
+
+enum PrevShrink<T> {
+    /// The given steps were cut out of the path at the given index.
+    Cut(usize, Vec<Step<T>>),
+    Label(usize),
+}
+
+/// `ValueTree` corresponding to `FsaStrategy`.
+pub struct FsaValueTree<T> {
+    fsa: Arc<Fsa<T>>,
+    steps: Vec<Step<T>>,
+    shrink: Shrink,
+    prev_shrink: Option<PrevShrink<T>>,
+}
+
+impl<T> FsaValueTree<T> {
+    /// The state the path is in before the step at `ix`.
+    fn state_before(&self, ix: usize) -> usize {
+        match ix.checked_sub(1) {
+            None => self.fsa.start,
+            Some(prev) => self.fsa.transitions[self.steps[prev].transition].to,
+        }
+    }
+}
+
+impl<T: fmt::Debug> ValueTree for FsaValueTree<T> {
+    type Value = Vec<T>;
+
+    fn current(&self) -> Vec<T> {
+        self.steps.iter().map(|step| step.label.current()).collect()
+    }
+
+    fn simplify(&mut self) -> bool {
+        while let Shrink::Cut(ix, before) = self.shrink {
+            if ix >= self.steps.len() {
+                self.shrink = Shrink::Label(0);
+                break;
+            }
+
+            // The path stays accepted if it skips ahead to a later step in
+            // the same state, or stops here if this state is accepting.
+            let state = self.state_before(ix);
+            let len = self.steps.len();
+            let end = (ix + 1..before.min(len + 1)).rev().find(|&end| {
+                state == self.state_before(end)
+                    || (end == len && self.fsa.accepting[state])
+            });
+            match end {
+                Some(end) => {
+                    let cut = self.steps.drain(ix..end).collect();
+                    self.prev_shrink = Some(PrevShrink::Cut(ix, cut));
+                    // Stay at `ix`, since a different step is there now.
+                    self.shrink = Shrink::Cut(ix, usize::MAX);
+                    return true;
+                }
+                None => self.shrink = Shrink::Cut(ix + 1, usize::MAX),
+            }
+        }
+
+        while let Shrink::Label(ix) = self.shrink {
+            if ix >= self.steps.len() {
+                // Nothing more we can do
+                self.prev_shrink = None;
+                return false;
+            }
+
+            if self.steps[ix].label.simplify() {
+                self.prev_shrink = Some(PrevShrink::Label(ix));
+                return true;
+            }
+            self.shrink = Shrink::Label(ix + 1);
+        }
+
+        panic!("Unexpected shrink state");
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.prev_shrink.take() {
+            None => false,
+            Some(PrevShrink::Cut(ix, cut)) => {
+                // Keep these steps and try a shorter cut instead.
+                let end = ix + cut.len();
+                let rest = self.steps.split_off(ix);
+                self.steps.extend(cut);
+                self.steps.extend(rest);
+                self.shrink = Shrink::Cut(ix, end);
+                true
+            }
+            Some(PrevShrink::Label(ix))
+                if self.steps[ix].label.complicate() =>
+            {
+                self.prev_shrink = Some(PrevShrink::Label(ix));
+                true
+            }
+            Some(PrevShrink::Label(_)) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Accepts `a (ba | c)*`, i.e., alternating `a`s and `b`s starting and
+    /// ending with `a`, with `c`s after any of the `a`s.
+    fn alternating() -> Fsa<char> {
+        Fsa::builder()
+            .transition("start", Just('a'), "a")
+            .transition("a", Just('b'), "b")
+            .transition("b", Just('a'), "a")
+            .transition("a", Just('c'), "a")
+            .accept("a")
+            .build("start")
+    }
+
+    fn accepted(s: &[char]) -> bool {
+        let mut state = "start";
+        for &c in s {
+            state = match (state, c) {
+                ("start", 'a') | ("b", 'a') | ("a", 'c') => "a",
+                ("a", 'b') => "b",
+                _ => return false,
+            };
+        }
+        "a" == state
+    }
+
+    #[test]
+    fn generates_accepted_sequences() {
+        let strategy = alternating().strategy(12);
+        let mut runner = TestRunner::deterministic();
+        let mut longest = 0;
+        for _ in 0..256 {
+            let mut tree = strategy.new_tree(&mut runner).unwrap();
+            let s = tree.current();
+            assert!(s.len() <= 12, "{:?}", s);
+            longest = longest.max(s.len());
+            loop {
+                let s = tree.current();
+                assert!(accepted(&s), "{:?}", s);
+                if !tree.simplify() {
+                    break;
+                }
+            }
+            assert_eq!(vec!['a'], tree.current());
+        }
+        assert!(longest > 5, "Only got sequences up to {} long", longest);
+    }
+
+    #[test]
+    fn shrinking_keeps_sequences_accepted() {
+        let strategy = alternating().strategy(12);
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&strategy, |s| {
+            prop_assert!(accepted(&s));
+            prop_assert!(s.iter().filter(|&&c| 'b' == c).count() < 2);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, s, _, _)) => {
+                assert_eq!(vec!['a', 'b', 'a', 'b', 'a'], s)
+            }
+            e => panic!("Unexpected result: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn labels_shrink_after_path() {
+        let strategy = Fsa::builder()
+            .transition("start", 0..100u32, "end")
+            .accept("end")
+            .build("start")
+            .strategy(0);
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..16 {
+            let mut tree = strategy.new_tree(&mut runner).unwrap();
+            assert_eq!(1, tree.current().len());
+            while tree.simplify() {}
+            assert_eq!(vec![0], tree.current());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "no accepting state can be reached")]
+    fn rejects_unreachable_accepting_states() {
+        Fsa::<char>::builder()
+            .transition("start", Just('a'), "loop")
+            .transition("loop", Just('a'), "loop")
+            .accept("elsewhere")
+            .build("start");
+    }
+}
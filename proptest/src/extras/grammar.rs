@@ -0,0 +1,376 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Strategies for generating strings from a context-free grammar.
+//!
+//! A `Grammar` is a set of weighted productions for each nonterminal, built
+//! with `Grammar::builder()`. `Grammar::strategy()` then generates the
+//! strings derived from its start symbol, which is mostly useful for testing
+//! parsers, interpreters and compilers.
+//!
+//! ```
+//! use proptest::extras::grammar::{Grammar, Symbol::*};
+//! use proptest::prelude::*;
+//!
+//! fn sums() -> impl Strategy<Value = String> {
+//!     Grammar::builder()
+//!         .production("sum", 1, vec![N("num")])
+//!         .production("sum", 2, vec![N("sum"), T("+"), N("num")])
+//!         .production("num", 1, vec![T("0")])
+//!         .production("num", 1, vec![T("1")])
+//!         .build("sum")
+//!         .strategy(8)
+//! }
+//!
+//! proptest! {
+//!   # /*
+//!   #[test]
+//!   # */
+//!   fn sums_alternate(s in sums()) {
+//!     for (ix, c) in s.chars().enumerate() {
+//!       prop_assert_eq!(ix % 2 == 1, c == '+');
+//!     }
+//!   }
+//! }
+//! #
+//! # fn main() { sums_alternate(); }
+//! ```
+
+use crate::std_facade::{BTreeMap, String, Vec};
+
+use crate::strategy::*;
+
+/// A symbol on the right-hand side of a production.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Symbol {
+    /// A terminal, which appears in the generated string verbatim.
+    T(&'static str),
+    /// A nonterminal, which is replaced by a string derived from one of its
+    /// productions.
+    N(&'static str),
+}
+
+#[derive(Clone, Debug)]
+struct Production {
+    weight: u32,
+    symbols: Vec<Symbol>,
+    // The depth of the shallowest derivation starting with this production,
+    // i.e., one more than the depth of its deepest nonterminal, or 0 if it
+    // only has terminals.
+    depth: u32,
+}
+
+/// Builder for `Grammar`.
+///
+/// Created by `Grammar::builder()`.
+#[derive(Clone, Debug, Default)]
+pub struct GrammarBuilder {
+    productions: BTreeMap<&'static str, Vec<Production>>,
+}
+
+impl GrammarBuilder {
+    /// Add a production deriving `symbols` from `nonterminal`, chosen with a
+    /// relative frequency of `weight` among the productions of
+    /// `nonterminal`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `weight` is 0.
+    pub fn production(
+        mut self,
+        nonterminal: &'static str,
+        weight: u32,
+        symbols: Vec<Symbol>,
+    ) -> Self {
+        assert!(
+            weight > 0,
+            "production of {} has a weight of 0",
+            nonterminal
+        );
+        self.productions
+            .entry(nonterminal)
+            .or_default()
+            .push(Production {
+                weight,
+                symbols,
+                depth: u32::MAX,
+            });
+        self
+    }
+
+    /// Finish building a grammar deriving strings from `start`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `start` or any nonterminal used in a production has no
+    /// productions, or if a nonterminal cannot derive any finite string,
+    /// e.g., because all its productions refer back to it.
+    pub fn build(mut self, start: &'static str) -> Grammar {
+        for production in self.productions.values().flatten() {
+            for &symbol in &production.symbols {
+                if let Symbol::N(nonterminal) = symbol {
+                    assert!(
+                        self.productions.contains_key(nonterminal),
+                        "nonterminal {} has no productions",
+                        nonterminal
+                    );
+                }
+            }
+        }
+        assert!(
+            self.productions.contains_key(start),
+            "start symbol {} has no productions",
+            start
+        );
+
+        // Find the depth of the shallowest derivation of every nonterminal
+        // by iterating to a fixed point. Depths only ever decrease, so this
+        // terminates.
+        let mut depths = BTreeMap::<&'static str, u32>::new();
+        loop {
+            let mut changed = false;
+            for (&nonterminal, productions) in &mut self.productions {
+                for production in productions.iter_mut() {
+                    let depth = production
+                        .symbols
+                        .iter()
+                        .map(|&symbol| match symbol {
+                            Symbol::T(_) => Some(0),
+                            Symbol::N(n) => depths.get(n).map(|&d| d + 1),
+                        })
+                        .try_fold(0, |max, depth| depth.map(|d| max.max(d)));
+                    if let Some(depth) = depth {
+                        production.depth = depth;
+                    }
+                }
+                let depth = productions.iter().map(|p| p.depth).min();
+                if let Some(depth) = depth.filter(|&d| d != u32::MAX) {
+                    if depths.get(nonterminal) != Some(&depth) {
+                        depths.insert(nonterminal, depth);
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        for &nonterminal in self.productions.keys() {
+            assert!(
+                depths.contains_key(nonterminal),
+                "nonterminal {} cannot derive a finite string",
+                nonterminal
+            );
+        }
+
+        // Unions shrink towards earlier options, so put the shallowest and
+        // shortest productions first.
+        for productions in self.productions.values_mut() {
+            productions.sort_by_key(|p| (p.depth, p.symbols.len()));
+        }
+
+        Grammar {
+            productions: self.productions,
+            start,
+        }
+    }
+}
+
+/// A context-free grammar with weighted productions.
+///
+/// Created with `Grammar::builder()`.
+#[derive(Clone, Debug)]
+pub struct Grammar {
+    productions: BTreeMap<&'static str, Vec<Production>>,
+    start: &'static str,
+}
+
+impl Grammar {
+    /// Start building a grammar.
+    pub fn builder() -> GrammarBuilder {
+        GrammarBuilder::default()
+    }
+
+    /// Create a strategy to generate strings derived from the start symbol
+    /// with derivations at most `depth` levels deep, where a production
+    /// consisting only of terminals has depth 0. If the grammar has no
+    /// derivation of the start symbol that shallow, its shallowest
+    /// derivations are used instead.
+    ///
+    /// Like `Strategy::prop_recursive()`, this unrolls the recursion into a
+    /// fixed number of levels, so derivations always terminate. Shrinking
+    /// moves towards the shallowest and shortest productions of every
+    /// nonterminal, and so towards the shortest derivation.
+    pub fn strategy(&self, depth: u32) -> BoxedStrategy<String> {
+        let depth = depth.max(self.productions[self.start][0].depth);
+
+        // `levels[d]` holds the strategies for every nonterminal which can
+        // be derived in at most `d` levels.
+        let mut levels =
+            Vec::<BTreeMap<&'static str, BoxedStrategy<String>>>::new();
+        for level in 0..=depth {
+            let mut strategies = BTreeMap::new();
+            for (&nonterminal, productions) in &self.productions {
+                let options = productions
+                    .iter()
+                    .filter(|p| p.depth <= level)
+                    .map(|p| (p.weight, self.production(p, &levels)))
+                    .collect::<Vec<_>>();
+                if !options.is_empty() {
+                    strategies.insert(
+                        nonterminal,
+                        Union::new_weighted(options).boxed(),
+                    );
+                }
+            }
+            levels.push(strategies);
+        }
+
+        levels.pop().unwrap().remove(self.start).unwrap()
+    }
+
+    fn production(
+        &self,
+        production: &Production,
+        levels: &[BTreeMap<&'static str, BoxedStrategy<String>>],
+    ) -> BoxedStrategy<String> {
+        let parts = production
+            .symbols
+            .iter()
+            .map(|&symbol| match symbol {
+                Symbol::T(terminal) => Just(String::from(terminal)).boxed(),
+                // A production of depth `d` only uses nonterminals of depth
+                // less than `d`, so this is in the previous level.
+                Symbol::N(nonterminal) => {
+                    levels[levels.len() - 1][nonterminal].clone()
+                }
+            })
+            .collect::<Vec<_>>();
+        parts.prop_map(|parts| parts.concat()).boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Symbol::*;
+    use super::*;
+    use crate::test_runner::TestRunner;
+
+    fn arithmetic() -> Grammar {
+        Grammar::builder()
+            .production("expr", 3, vec![N("term")])
+            .production("expr", 1, vec![N("expr"), T("+"), N("term")])
+            .production("term", 3, vec![N("factor")])
+            .production("term", 1, vec![N("term"), T("*"), N("factor")])
+            .production("factor", 3, vec![N("digit")])
+            .production("factor", 1, vec![T("("), N("expr"), T(")")])
+            .production("digit", 1, vec![T("0")])
+            .production("digit", 1, vec![T("1")])
+            .production("digit", 1, vec![T("2")])
+            .build("expr")
+    }
+
+    /// Evaluate an expression of the `arithmetic()` grammar, returning the
+    /// value and the unparsed remainder, or `None` on a syntax error.
+    fn parse_expr(s: &str) -> Option<(u64, &str)> {
+        let (mut value, mut rest) = parse_term(s)?;
+        while let Some(after) = rest.strip_prefix('+') {
+            let (term, after) = parse_term(after)?;
+            value += term;
+            rest = after;
+        }
+        Some((value, rest))
+    }
+
+    fn parse_term(s: &str) -> Option<(u64, &str)> {
+        let (mut value, mut rest) = parse_factor(s)?;
+        while let Some(after) = rest.strip_prefix('*') {
+            let (factor, after) = parse_factor(after)?;
+            value *= factor;
+            rest = after;
+        }
+        Some((value, rest))
+    }
+
+    fn parse_factor(s: &str) -> Option<(u64, &str)> {
+        if let Some(after) = s.strip_prefix('(') {
+            let (value, rest) = parse_expr(after)?;
+            return Some((value, rest.strip_prefix(')')?));
+        }
+        let digit = s.chars().next()?.to_digit(10)?;
+        Some((u64::from(digit), &s[1..]))
+    }
+
+    #[test]
+    fn generates_parseable_arithmetic() {
+        let strategy = arithmetic().strategy(8);
+        let mut runner = TestRunner::deterministic();
+        let mut longest = 0;
+        for _ in 0..256 {
+            let s = strategy.new_tree(&mut runner).unwrap().current();
+            assert_eq!(Some(""), parse_expr(&s).map(|(_, rest)| rest), "{}", s);
+            longest = longest.max(s.len());
+        }
+        assert!(longest > 5, "Only got strings up to {} long", longest);
+    }
+
+    #[test]
+    fn shrinks_to_shortest_derivation() {
+        let strategy = arithmetic().strategy(8);
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut tree = strategy.new_tree(&mut runner).unwrap();
+            while tree.simplify() {
+                let s = tree.current();
+                assert!(parse_expr(&s).is_some(), "{}", s);
+            }
+            assert_eq!("0", tree.current());
+        }
+    }
+
+    #[test]
+    fn depth_limits_nesting() {
+        let strategy = arithmetic().strategy(6);
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let s = strategy.new_tree(&mut runner).unwrap().current();
+            // The shallowest parenthesised expression is 6 levels deep, and
+            // nesting parentheses needs 9.
+            let mut nesting = 0;
+            let mut max_nesting = 0;
+            for c in s.chars() {
+                match c {
+                    '(' => nesting += 1,
+                    ')' => nesting -= 1,
+                    _ => (),
+                }
+                max_nesting = max_nesting.max(nesting);
+            }
+            assert!(max_nesting <= 1, "{}", s);
+        }
+
+        // Too shallow to derive anything but the shallowest derivations.
+        let strategy = arithmetic().strategy(0);
+        for _ in 0..64 {
+            let s = strategy.new_tree(&mut runner).unwrap().current();
+            assert!(["0", "1", "2"].contains(&&*s), "{}", s);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "nonterminal loop cannot derive")]
+    fn rejects_infinite_nonterminals() {
+        Grammar::builder()
+            .production("start", 1, vec![T("x")])
+            .production("start", 1, vec![N("loop")])
+            .production("loop", 1, vec![T("y"), N("loop")])
+            .build("start");
+    }
+}
@@ -0,0 +1,370 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Strategies for generating graphs which satisfy structural invariants.
+//!
+//! Graphs are represented as a node count `n` together with a list of edges
+//! `(i, j)` between nodes in `0..n`. Every edge satisfies `i < j`, and no edge
+//! appears twice. The invariant of each strategy (acyclicity or
+//! connectivity) holds for every generated value, including every value
+//! produced while shrinking.
+//!
+//! Shrinking first removes nodes, starting from the highest-numbered one and
+//! together with all edges incident to it, then removes the remaining edges
+//! one at a time.
+
+use crate::std_facade::Vec;
+
+use rand::Rng;
+
+use crate::collection::SizeRange;
+use crate::strategy::*;
+use crate::test_runner::*;
+
+/// A graph, given as its node count and its edges.
+pub type Graph = (usize, Vec<(usize, usize)>);
+
+/// Strategy to create directed acyclic graphs.
+///
+/// Created by the `dag()` function in the same module.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct DagStrategy {
+    node_count: SizeRange,
+    edge_density: f64,
+}
+
+/// Strategy to create connected undirected graphs.
+///
+/// Created by the `connected_graph()` function in the same module.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct ConnectedGraphStrategy {
+    node_count: SizeRange,
+    edge_density: f64,
+}
+
+fn check_density(edge_density: f64) {
+    assert!(
+        (0.0..=1.0).contains(&edge_density),
+        "edge_density must be between 0.0 and 1.0, got {}",
+        edge_density
+    );
+}
+
+/// Create a strategy to generate directed acyclic graphs with a number of
+/// nodes in `node_count`.
+///
+/// Each possible edge `i -> j` with `i < j` is included independently with
+/// probability `edge_density`. Since every edge leads from a lower-numbered
+/// node to a higher-numbered one, the graph can never contain a cycle, and
+/// `0..n` is always a valid topological order.
+///
+/// ## Panics
+///
+/// Panics if `edge_density` is not between `0.0` and `1.0`.
+pub fn dag(node_count: impl Into<SizeRange>, edge_density: f64) -> DagStrategy {
+    check_density(edge_density);
+    DagStrategy {
+        node_count: node_count.into(),
+        edge_density,
+    }
+}
+
+/// Create a strategy to generate connected undirected graphs with a number of
+/// nodes in `node_count`.
+///
+/// Every node other than `0` is joined to a randomly chosen lower-numbered
+/// node, forming a spanning tree. Each other possible edge is then included
+/// independently with probability `edge_density`. Shrinking never removes an
+/// edge of the spanning tree, except together with the node that it leads
+/// to, so the graph stays connected. (A graph with no nodes is considered
+/// connected.)
+///
+/// ## Panics
+///
+/// Panics if `edge_density` is not between `0.0` and `1.0`.
+pub fn connected_graph(
+    node_count: impl Into<SizeRange>,
+    edge_density: f64,
+) -> ConnectedGraphStrategy {
+    check_density(edge_density);
+    ConnectedGraphStrategy {
+        node_count: node_count.into(),
+        edge_density,
+    }
+}
+
+impl Strategy for DagStrategy {
+    type Tree = GraphValueTree;
+    type Value = Graph;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let node_count = self.node_count.sample(runner)?;
+        let rng = runner.rng();
+        let mut edges = Vec::new();
+        for j in 1..node_count {
+            for i in 0..j {
+                if rng.gen_bool(self.edge_density) {
+                    edges.push(Edge {
+                        ends: (i, j),
+                        removable: true,
+                    });
+                }
+            }
+        }
+
+        Ok(GraphValueTree::new(
+            node_count,
+            self.node_count.start(),
+            edges,
+        ))
+    }
+}
+
+impl Strategy for ConnectedGraphStrategy {
+    type Tree = GraphValueTree;
+    type Value = Graph;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let node_count = self.node_count.sample(runner)?;
+        let rng = runner.rng();
+        let mut edges = Vec::new();
+        for j in 1..node_count {
+            let parent = rng.gen_range(0..j);
+            for i in 0..j {
+                if i == parent {
+                    edges.push(Edge {
+                        ends: (i, j),
+                        removable: false,
+                    });
+                } else if rng.gen_bool(self.edge_density) {
+                    edges.push(Edge {
+                        ends: (i, j),
+                        removable: true,
+                    });
+                }
+            }
+        }
+
+        Ok(GraphValueTree::new(
+            node_count,
+            self.node_count.start(),
+            edges,
+        ))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Edge {
+    ends: (usize, usize),
+    // Whether the edge can be removed on its own without breaking the
+    // invariant of the strategy.
+    removable: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Shrink {
+    DeleteNode,
+    DeleteEdge(usize),
+}
+
+/// `ValueTree` corresponding to `DagStrategy` and `ConnectedGraphStrategy`.
+#[derive(Clone, Debug)]
+pub struct GraphValueTree {
+    node_count: usize,
+    min_node_count: usize,
+    edges: Vec<Edge>,
+    included_edges: Vec<bool>,
+    shrink: Shrink,
+    prev_shrink: Option<Shrink>,
+}
+
+impl GraphValueTree {
+    fn new(node_count: usize, min_node_count: usize, edges: Vec<Edge>) -> Self {
+        GraphValueTree {
+            node_count,
+            min_node_count,
+            included_edges: vec![true; edges.len()],
+            edges,
+            shrink: Shrink::DeleteNode,
+            prev_shrink: None,
+        }
+    }
+}
+
+impl ValueTree for GraphValueTree {
+    type Value = Graph;
+
+    fn current(&self) -> Graph {
+        let edges = self
+            .edges
+            .iter()
+            .zip(&self.included_edges)
+            .filter(|&(edge, &included)| {
+                included && edge.ends.1 < self.node_count
+            })
+            .map(|(edge, _)| edge.ends)
+            .collect();
+        (self.node_count, edges)
+    }
+
+    fn simplify(&mut self) -> bool {
+        // Removing the highest-numbered node drops only edges leading to it,
+        // which preserves both acyclicity and connectivity, since no node
+        // reaches the spanning tree through a higher-numbered one.
+        if let Shrink::DeleteNode = self.shrink {
+            if self.node_count > self.min_node_count {
+                self.node_count -= 1;
+                self.prev_shrink = Some(Shrink::DeleteNode);
+                return true;
+            }
+            self.shrink = Shrink::DeleteEdge(0);
+        }
+
+        while let Shrink::DeleteEdge(ix) = self.shrink {
+            if ix >= self.edges.len() {
+                return false;
+            }
+
+            self.shrink = Shrink::DeleteEdge(ix + 1);
+            let edge = self.edges[ix];
+            if self.included_edges[ix]
+                && edge.removable
+                && edge.ends.1 < self.node_count
+            {
+                self.included_edges[ix] = false;
+                self.prev_shrink = Some(Shrink::DeleteEdge(ix));
+                return true;
+            }
+        }
+
+        unreachable!("Unexpected shrink state")
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.prev_shrink.take() {
+            None => false,
+            Some(Shrink::DeleteNode) => {
+                // The node was needed; stop deleting nodes and move on to the
+                // edges.
+                self.node_count += 1;
+                self.shrink = Shrink::DeleteEdge(0);
+                true
+            }
+            Some(Shrink::DeleteEdge(ix)) => {
+                self.included_edges[ix] = true;
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_edges_valid(&(node_count, ref edges): &Graph) {
+        for (ix, &(i, j)) in edges.iter().enumerate() {
+            assert!(i < j && j < node_count, "Bad edge {:?}", (i, j));
+            assert!(!edges[..ix].contains(&(i, j)), "Duplicate {:?}", (i, j));
+        }
+    }
+
+    fn is_connected(&(node_count, ref edges): &Graph) -> bool {
+        // Union-find over the nodes.
+        let mut parent: Vec<usize> = (0..node_count).collect();
+        fn root(parent: &mut [usize], mut node: usize) -> usize {
+            while parent[node] != node {
+                parent[node] = parent[parent[node]];
+                node = parent[node];
+            }
+            node
+        }
+        for &(i, j) in edges {
+            let (i, j) = (root(&mut parent, i), root(&mut parent, j));
+            parent[i] = j;
+        }
+        (0..node_count)
+            .all(|node| root(&mut parent, node) == root(&mut parent, 0))
+    }
+
+    /// Drives shrinking for every generated graph, accepting or rejecting
+    /// each step at random, and checks every value seen.
+    fn check_every_value(
+        strategy: impl Strategy<Value = Graph>,
+        check: impl Fn(&Graph),
+    ) {
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut tree = strategy.new_tree(&mut runner).unwrap();
+            check(&tree.current());
+            loop {
+                let changed = if runner.rng().gen() {
+                    tree.simplify()
+                } else {
+                    tree.complicate()
+                };
+                check(&tree.current());
+                if !changed && !tree.simplify() {
+                    break;
+                }
+                check(&tree.current());
+            }
+        }
+    }
+
+    #[test]
+    fn dag_is_always_acyclic() {
+        check_every_value(dag(0..16, 0.3), |graph| {
+            assert!((0..16).contains(&graph.0));
+            // Every edge leading to a higher-numbered node rules out cycles.
+            assert_edges_valid(graph);
+        });
+    }
+
+    #[test]
+    fn connected_graph_is_always_connected() {
+        check_every_value(connected_graph(1..16, 0.2), |graph| {
+            assert!((1..16).contains(&graph.0));
+            assert_edges_valid(graph);
+            assert!(is_connected(graph), "Disconnected: {:?}", graph);
+        });
+    }
+
+    #[test]
+    fn dag_shrinks_to_minimum() {
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut tree = dag(2..8, 0.5).new_tree(&mut runner).unwrap();
+            while tree.simplify() {}
+            assert_eq!((2, vec![]), tree.current());
+        }
+    }
+
+    #[test]
+    fn connected_graph_shrinks_to_spanning_tree() {
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut tree =
+                connected_graph(4..8, 0.5).new_tree(&mut runner).unwrap();
+            while tree.simplify() {}
+            let graph = tree.current();
+            assert_eq!(4, graph.0);
+            assert_eq!(3, graph.1.len());
+            assert!(is_connected(&graph));
+        }
+    }
+
+    #[test]
+    fn test_sanity() {
+        check_strategy_sanity(dag(0..8, 0.5), None);
+        check_strategy_sanity(connected_graph(0..8, 0.5), None);
+    }
+}
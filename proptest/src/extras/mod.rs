@@ -0,0 +1,18 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Strategies for more specialised domains, which are too niche to deserve
+//! a top-level module of their own.
+
+pub mod csv;
+pub mod expr;
+pub mod fsa;
+pub mod grammar;
+pub mod graph;
+pub mod stlc;
@@ -0,0 +1,375 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Strategies for generating closed, well-typed terms of the simply-typed
+//! lambda calculus with booleans.
+//!
+//! This is mostly useful for testing type checkers, evaluators and compilers
+//! of functional languages, e.g., checking that evaluation preserves types,
+//! or that every well-typed term normalises.
+//!
+//! Terms are never rejected for being ill-typed. Instead, `prop_recursive()`
+//! generates the shape of a term, which is then filled in from the type it
+//! must have and the types of the variables in scope. Since every shape can
+//! be filled in this way, shrinking the shape shrinks the term without ever
+//! producing an ill-typed one.
+//!
+//! ```
+//! use proptest::extras::stlc::typed_term;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!   # /*
+//!   #[test]
+//!   # */
+//!   fn display_is_fully_parenthesised((term, _) in typed_term(8)) {
+//!     let printed = term.to_string();
+//!     prop_assert_eq!(
+//!       printed.matches('(').count(), printed.matches(')').count());
+//!   }
+//! }
+//! #
+//! # fn main() { display_is_fully_parenthesised(); }
+//! ```
+
+use crate::std_facade::{fmt, Box, Vec};
+
+use crate::strategy::*;
+
+/// A simple type.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Type {
+    /// The type of `true` and `false`.
+    Bool,
+    /// The type of functions from the first type to the second.
+    Arrow(Box<Type>, Box<Type>),
+}
+
+impl Type {
+    /// Return the type of functions from `from` to `to`.
+    pub fn arrow(from: Type, to: Type) -> Type {
+        Type::Arrow(Box::new(from), Box::new(to))
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Type::Bool => write!(f, "Bool"),
+            Type::Arrow(ref from, ref to) => match **from {
+                Type::Bool => write!(f, "Bool -> {}", to),
+                Type::Arrow(..) => write!(f, "({}) -> {}", from, to),
+            },
+        }
+    }
+}
+
+/// A term of the simply-typed lambda calculus with booleans.
+///
+/// Variables are de Bruijn indices, so terms which only differ in the names
+/// of their variables are equal.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Term {
+    /// The variable bound by the enclosing lambda with the given index,
+    /// counting outwards from 0 for the innermost one.
+    Var(usize),
+    /// A boolean literal.
+    Bool(bool),
+    /// A lambda binding a variable of the given type in its body.
+    Lam(Type, Box<Term>),
+    /// The application of a function to an argument.
+    App(Box<Term>, Box<Term>),
+    /// A conditional, with its condition, `then` and `else` branches.
+    If(Box<Term>, Box<Term>, Box<Term>),
+}
+
+impl Term {
+    fn fmt_in(&self, depth: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Term::Var(ix) => match depth.checked_sub(ix + 1) {
+                Some(level) => write!(f, "x{}", level),
+                None => write!(f, "?{}", ix - depth),
+            },
+            Term::Bool(value) => write!(f, "{}", value),
+            Term::Lam(ref ty, ref body) => {
+                write!(f, "(\\x{}: {}. ", depth, ty)?;
+                body.fmt_in(depth + 1, f)?;
+                write!(f, ")")
+            }
+            Term::App(ref fun, ref arg) => {
+                write!(f, "(")?;
+                fun.fmt_in(depth, f)?;
+                write!(f, " ")?;
+                arg.fmt_in(depth, f)?;
+                write!(f, ")")
+            }
+            Term::If(ref cond, ref then, ref other) => {
+                write!(f, "(if ")?;
+                cond.fmt_in(depth, f)?;
+                write!(f, " then ")?;
+                then.fmt_in(depth, f)?;
+                write!(f, " else ")?;
+                other.fmt_in(depth, f)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Terms are printed fully parenthesised, naming each variable `xN` after the
+/// number `N` of lambdas enclosing its binder, e.g. `(\x0: Bool. x0)`.
+/// Free variables are printed as `?N` instead, counting `N` outwards from 0
+/// past the outermost lambda.
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_in(0, f)
+    }
+}
+
+/// The shape of a term, which `fill()` turns into a term of any type.
+#[derive(Clone, Debug)]
+enum Shape {
+    /// A variable, literal, or lambda returning a leaf, picked by the given
+    /// number among those which have the required type.
+    Leaf(u32),
+    /// A conditional, application with an argument of the given type, or
+    /// lambda, picked by the given number among those which have the
+    /// required type.
+    Node(u32, Type, Box<(Shape, Shape, Shape)>),
+}
+
+/// Return a term of type `ty` with the given shape, in which the variable
+/// with index `ix` has type `ctx[ctx.len() - 1 - ix]`.
+fn fill(shape: &Shape, ctx: &mut Vec<Type>, ty: &Type) -> Term {
+    match *shape {
+        Shape::Leaf(choice) => {
+            // Literals and lambdas go first, so that terms shrink towards
+            // them rather than towards variables.
+            let intros = match *ty {
+                Type::Bool => 2,
+                Type::Arrow(..) => 1,
+            };
+            let vars = (0..ctx.len())
+                .filter(|&ix| ctx[ctx.len() - 1 - ix] == *ty)
+                .collect::<Vec<_>>();
+            let choice = choice as usize % (intros + vars.len());
+            match *ty {
+                _ if choice >= intros => Term::Var(vars[choice - intros]),
+                Type::Bool => Term::Bool(1 == choice),
+                // The body has a smaller type, so this terminates.
+                Type::Arrow(ref from, ref to) => lam(shape, ctx, from, to),
+            }
+        }
+        Shape::Node(choice, ref arg, ref children) => {
+            let kinds = match *ty {
+                Type::Bool => 2,
+                Type::Arrow(..) => 3,
+            };
+            let (ref first, ref second, ref third) = **children;
+            match (choice % kinds, ty) {
+                (0, _) => Term::If(
+                    Box::new(fill(first, ctx, &Type::Bool)),
+                    Box::new(fill(second, ctx, ty)),
+                    Box::new(fill(third, ctx, ty)),
+                ),
+                (1, _) => Term::App(
+                    Box::new(fill(
+                        first,
+                        ctx,
+                        &Type::arrow(arg.clone(), ty.clone()),
+                    )),
+                    Box::new(fill(second, ctx, arg)),
+                ),
+                (_, Type::Arrow(from, to)) => lam(first, ctx, from, to),
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+fn lam(body: &Shape, ctx: &mut Vec<Type>, from: &Type, to: &Type) -> Term {
+    ctx.push(from.clone());
+    let body = fill(body, ctx, to);
+    ctx.pop();
+    Term::Lam(from.clone(), Box::new(body))
+}
+
+/// Create a strategy to generate types with arrows nested at most `depth`
+/// deep.
+///
+/// Types shrink towards `Bool`.
+pub fn arb_type(depth: u32) -> BoxedStrategy<Type> {
+    Just(Type::Bool)
+        .prop_recursive(depth, 1 << depth.min(8), 2, |inner| {
+            (inner.clone(), inner).prop_map(|(from, to)| Type::arrow(from, to))
+        })
+        .boxed()
+}
+
+/// Create a strategy to generate closed terms of type `ty`.
+///
+/// `size` bounds the nesting of conditionals, applications and the lambdas
+/// picked among them, and about `size` of them are generated in total.
+/// Since every term must have type `ty`, lambdas are also added wherever a
+/// function is needed, so terms of larger types are larger.
+///
+/// Every value, including those produced while shrinking, has type `ty`.
+/// Terms shrink towards the smallest term of type `ty`, e.g. `false` for
+/// `Bool`.
+pub fn closed_term(ty: Type, size: u32) -> BoxedStrategy<Term> {
+    (0..8u32)
+        .prop_map(Shape::Leaf)
+        .prop_recursive(size, size, 3, |inner| {
+            (0..3u32, arb_type(2), inner.clone(), inner.clone(), inner)
+                .prop_map(|(choice, arg, first, second, third)| {
+                    Shape::Node(choice, arg, Box::new((first, second, third)))
+                })
+        })
+        .prop_map(move |shape| fill(&shape, &mut Vec::new(), &ty))
+        .boxed()
+}
+
+/// Create a strategy to generate closed terms together with their types, as
+/// `(term, type)` pairs, as by `closed_term(ty, size)` for types `ty` from
+/// `arb_type(2)`.
+pub fn typed_term(size: u32) -> BoxedStrategy<(Term, Type)> {
+    arb_type(2)
+        .prop_flat_map(move |ty| {
+            closed_term(ty.clone(), size)
+                .prop_map(move |term| (term, ty.clone()))
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::std_facade::string::ToString;
+    use crate::test_runner::*;
+
+    /// A reference type checker, returning the type of `term` if it is
+    /// well-typed when the variable with index `ix` has type
+    /// `ctx[ctx.len() - 1 - ix]`.
+    fn type_of(term: &Term, ctx: &mut Vec<Type>) -> Option<Type> {
+        match *term {
+            Term::Var(ix) => {
+                ctx.len().checked_sub(ix + 1).map(|pos| ctx[pos].clone())
+            }
+            Term::Bool(_) => Some(Type::Bool),
+            Term::Lam(ref from, ref body) => {
+                ctx.push(from.clone());
+                let to = type_of(body, ctx);
+                ctx.pop();
+                Some(Type::arrow(from.clone(), to?))
+            }
+            Term::App(ref fun, ref arg) => match type_of(fun, ctx)? {
+                Type::Arrow(from, to)
+                    if Some(&*from) == type_of(arg, ctx).as_ref() =>
+                {
+                    Some(*to)
+                }
+                _ => None,
+            },
+            Term::If(ref cond, ref then, ref other) => {
+                let ty = type_of(then, ctx)?;
+                if Some(Type::Bool) == type_of(cond, ctx)
+                    && Some(&ty) == type_of(other, ctx).as_ref()
+                {
+                    Some(ty)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    fn mark_kinds(term: &Term, seen: &mut [bool; 5]) {
+        match *term {
+            Term::Var(_) => seen[0] = true,
+            Term::Bool(_) => seen[1] = true,
+            Term::Lam(_, ref body) => {
+                seen[2] = true;
+                mark_kinds(body, seen);
+            }
+            Term::App(ref fun, ref arg) => {
+                seen[3] = true;
+                mark_kinds(fun, seen);
+                mark_kinds(arg, seen);
+            }
+            Term::If(ref cond, ref then, ref other) => {
+                seen[4] = true;
+                mark_kinds(cond, seen);
+                mark_kinds(then, seen);
+                mark_kinds(other, seen);
+            }
+        }
+    }
+
+    #[test]
+    fn terms_type_check_while_shrinking() {
+        let input = typed_term(8);
+        let mut runner = TestRunner::deterministic();
+        let mut seen = [false; 5];
+        for _ in 0..256 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            loop {
+                let (term, ty) = case.current();
+                assert_eq!(
+                    Some(ty),
+                    type_of(&term, &mut Vec::new()),
+                    "{}",
+                    term
+                );
+                mark_kinds(&term, &mut seen);
+                if !case.simplify() {
+                    break;
+                }
+            }
+        }
+        // All kinds of terms are generated, not just literals and lambdas.
+        assert_eq!([true; 5], seen);
+    }
+
+    #[test]
+    fn terms_shrink_to_smallest_of_type() {
+        let bool_to_bool = Type::arrow(Type::Bool, Type::Bool);
+        let input = closed_term(bool_to_bool.clone(), 8);
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            while case.simplify() {}
+            assert_eq!(
+                Term::Lam(Type::Bool, Box::new(Term::Bool(false))),
+                case.current()
+            );
+        }
+    }
+
+    #[test]
+    fn display_names_variables_by_binder() {
+        let term = Term::Lam(
+            Type::arrow(Type::Bool, Type::Bool),
+            Box::new(Term::Lam(
+                Type::Bool,
+                Box::new(Term::App(
+                    Box::new(Term::Var(1)),
+                    Box::new(Term::Var(0)),
+                )),
+            )),
+        );
+        assert_eq!(
+            "(\\x0: Bool -> Bool. (\\x1: Bool. (x0 x1)))",
+            term.to_string()
+        );
+        assert_eq!(
+            "(Bool -> Bool) -> Bool -> Bool",
+            type_of(&term, &mut Vec::new()).unwrap().to_string()
+        );
+    }
+}
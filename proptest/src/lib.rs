@@ -76,10 +76,14 @@ pub mod sugar;
 
 pub mod arbitrary;
 pub mod array;
+pub mod binary;
 pub mod bits;
 pub mod bool;
 pub mod char;
 pub mod collection;
+#[cfg(feature = "extras")]
+#[cfg_attr(docsrs, doc(cfg(feature = "extras")))]
+pub mod extras;
 pub mod num;
 pub mod strategy;
 pub mod test_runner;
@@ -91,6 +95,9 @@ pub mod option;
 pub mod path;
 pub mod result;
 pub mod sample;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub mod serde_support;
 #[cfg(feature = "std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub mod string;
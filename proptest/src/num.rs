@@ -38,6 +38,35 @@ pub fn sample_uniform_incl<X: SampleUniform>(
     Uniform::new_inclusive(start, end).sample(run.rng())
 }
 
+/// Generate a random non-negative integral value following a geometric
+/// distribution with success probability `p`; i.e., the number of failed
+/// Bernoulli trials before the first success.
+///
+/// The result is returned as an `f64` so that callers can saturate it to the
+/// range of their own integer type. `p` must be in `(0.0, 1.0]`.
+pub(crate) fn sample_geometric(run: &mut TestRunner, p: f64) -> f64 {
+    #[cfg(not(feature = "std"))]
+    use num_traits::float::Float;
+    use rand::Rng;
+
+    // `1.0 - u` is in `(0.0, 1.0]`, so the logarithm is always finite. When
+    // `p == 1.0` the denominator is negative infinity and the result is zero.
+    // `ln_1p` keeps the denominator non-zero even when `1.0 - p` rounds to
+    // `1.0`.
+    let u: f64 = run.rng().gen();
+    ((-u).ln_1p() / (-p).ln_1p()).floor()
+}
+
+/// An arithmetic operation, used to choose which boundary
+/// `overflow_pairs()` targets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ArithOp {
+    /// Addition, `a + b`.
+    Add,
+    /// Multiplication, `a * b`.
+    Mul,
+}
+
 macro_rules! int_any {
     ($typ: ident) => {
         /// Type of the `ANY` constant.
@@ -59,6 +88,227 @@ macro_rules! int_any {
     };
 }
 
+macro_rules! int_edgy {
+    ($typ: ident) => {
+        /// Strategy returned by [`edgy()`](fn.edgy.html).
+        #[derive(Clone, Debug)]
+        #[must_use = "strategies do nothing unless used"]
+        pub struct Edgy {
+            start: $typ,
+            end: $typ,
+            edges: crate::std_facade::Vec<$typ>,
+        }
+
+        /// Generates integers in `range`, favouring the values near which
+        /// bugs tend to lurk.
+        ///
+        /// Half of the time, one of the following values is picked (if it
+        /// lies within `range`): the bounds of `range`, `0`, `1`, `-1` (or
+        /// `MAX` for unsigned types), and positive and negative powers of
+        /// two. Otherwise, the value is drawn uniformly from `range`, just
+        /// like the strategy for the range itself.
+        ///
+        /// Shrinking behaves exactly like the strategy for `range`.
+        ///
+        /// ## Panics
+        ///
+        /// Panics if `range` is empty.
+        pub fn edgy(range: ::core::ops::RangeInclusive<$typ>) -> Edgy {
+            let (start, end) = range.into_inner();
+            assert!(start <= end, "edgy() called with empty range");
+
+            let minus_one = (0 as $typ).wrapping_sub(1);
+            let mut edges = vec![start, end, 0, 1, minus_one];
+            for shift in 1..<$typ>::BITS {
+                let power = (1 as $typ) << shift;
+                edges.push(power);
+                edges.extend(power.checked_neg());
+            }
+            edges.retain(|&v| v >= start && v <= end);
+            edges.sort_unstable();
+            edges.dedup();
+
+            Edgy { start, end, edges }
+        }
+
+        impl Strategy for Edgy {
+            type Tree = BinarySearch;
+            type Value = $typ;
+
+            fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+                let value = if runner.rng().gen() {
+                    self.edges[runner.rng().gen_range(0..self.edges.len())]
+                } else {
+                    $crate::num::sample_uniform_incl(
+                        runner, self.start, self.end,
+                    )
+                };
+                Ok(BinarySearch::new_clamped(self.start, value, self.end))
+            }
+        }
+    };
+}
+
+macro_rules! int_range_excluding {
+    ($typ: ident) => {
+        /// Strategy returned by
+        /// [`range_excluding()`](fn.range_excluding.html).
+        #[derive(Clone, Debug)]
+        #[must_use = "strategies do nothing unless used"]
+        pub struct RangeExcluding {
+            origin: $typ,
+            lo: $typ,
+            hi: $typ,
+            excluded: crate::std_facade::Arc<[$typ]>,
+        }
+
+        /// Generates integers in `range` other than those in `excluded`.
+        ///
+        /// Rather than rejecting excluded values like `prop_filter()`, the
+        /// allowed values are numbered consecutively and one of those numbers
+        /// is drawn uniformly, so no draws are wasted and every allowed value
+        /// is equally likely. Values in `excluded` outside of `range` are
+        /// ignored.
+        ///
+        /// Shrinks by binary searching towards the allowed value closest to
+        /// `0` (preferring the positive one in case of a tie), skipping over
+        /// excluded values.
+        ///
+        /// ## Panics
+        ///
+        /// Panics if `range` is empty or contains no values other than those
+        /// in `excluded`.
+        pub fn range_excluding(
+            range: ::core::ops::RangeInclusive<$typ>,
+            excluded: &[$typ],
+        ) -> RangeExcluding {
+            let (start, end) = range.into_inner();
+            assert!(start <= end, "range_excluding() called with empty range");
+            let mut excluded: crate::std_facade::Vec<$typ> = excluded
+                .iter()
+                .copied()
+                .filter(|&v| v >= start && v <= end)
+                .collect();
+            excluded.sort_unstable();
+            excluded.dedup();
+
+            // Search outwards from the value the plain range shrinks to for
+            // the closest one which is allowed.
+            let target = (0 as $typ).max(start).min(end);
+            let origin = match excluded.binary_search(&target) {
+                Err(_) => target,
+                Ok(ix) => {
+                    let mut up = target.checked_add(1);
+                    for &v in &excluded[ix + 1..] {
+                        if up != Some(v) {
+                            break;
+                        }
+                        up = v.checked_add(1);
+                    }
+                    let mut down = target.checked_sub(1);
+                    for &v in excluded[..ix].iter().rev() {
+                        if down != Some(v) {
+                            break;
+                        }
+                        down = v.checked_sub(1);
+                    }
+
+                    let up = up.filter(|&u| u <= end);
+                    let down = down.filter(|&d| d >= start);
+                    match (down, up) {
+                        (Some(d), Some(u)) => match target.checked_sub(d) {
+                            Some(distance) if distance < u - target => d,
+                            _ => u,
+                        },
+                        (None, Some(u)) => u,
+                        (Some(d), None) => d,
+                        (None, None) => panic!(
+                            "range_excluding() called with no allowed values"
+                        ),
+                    }
+                }
+            };
+
+            // The allowed values are numbered relative to `origin`, so that
+            // the number of every allowed value fits in the type. Wrapping
+            // arithmetic gives the right result even where intermediate
+            // values would overflow.
+            let below = excluded.partition_point(|&v| v < origin);
+            let lo = start.wrapping_add(below as $typ).wrapping_sub(origin);
+            let above = excluded.len() - below;
+            let hi = end.wrapping_sub(above as $typ).wrapping_sub(origin);
+
+            RangeExcluding {
+                origin,
+                lo,
+                hi,
+                excluded: excluded.into(),
+            }
+        }
+
+        impl Strategy for RangeExcluding {
+            type Tree = RangeExcludingValueTree;
+            type Value = $typ;
+
+            fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+                let index =
+                    $crate::num::sample_uniform_incl(runner, self.lo, self.hi);
+                Ok(RangeExcludingValueTree {
+                    origin: self.origin,
+                    excluded: crate::std_facade::Arc::clone(&self.excluded),
+                    index: BinarySearch::new(index),
+                })
+            }
+        }
+
+        /// `ValueTree` corresponding to `RangeExcluding`.
+        #[derive(Clone, Debug)]
+        pub struct RangeExcludingValueTree {
+            origin: $typ,
+            excluded: crate::std_facade::Arc<[$typ]>,
+            index: BinarySearch,
+        }
+
+        impl ValueTree for RangeExcludingValueTree {
+            type Value = $typ;
+
+            fn current(&self) -> $typ {
+                // Step away from `origin`, skipping one further value for
+                // every excluded value passed on the way.
+                let index = self.index.current();
+                let mut value = self.origin + index;
+                if index > 0 {
+                    for &v in self.excluded.iter().filter(|&&v| v > self.origin)
+                    {
+                        if v > value {
+                            break;
+                        }
+                        value += 1;
+                    }
+                } else {
+                    for &v in
+                        self.excluded.iter().rev().filter(|&&v| v < self.origin)
+                    {
+                        if v < value {
+                            break;
+                        }
+                        value -= 1;
+                    }
+                }
+                value
+            }
+
+            fn simplify(&mut self) -> bool {
+                self.index.simplify()
+            }
+
+            fn complicate(&mut self) -> bool {
+                self.index.complicate()
+            }
+        }
+    };
+}
+
 macro_rules! numeric_api {
     ($typ:ident, $epsilon:expr) => {
         numeric_api!($typ, $typ, $epsilon);
@@ -156,6 +406,83 @@ macro_rules! numeric_api {
     };
 }
 
+macro_rules! int_overflow_pairs {
+    ($typ: ident) => {
+        /// Strategy returned by
+        /// [`overflow_pairs()`](fn.overflow_pairs.html).
+        #[derive(Clone, Copy, Debug)]
+        #[must_use = "strategies do nothing unless used"]
+        pub struct OverflowPairs {
+            op: $crate::num::ArithOp,
+        }
+
+        /// Generates pairs of operands `(a, b)` for which `op` lands near
+        /// `MAX` or `MIN`, e.g., to test checked or wrapping arithmetic.
+        ///
+        /// Half of the time, `b` is chosen for a random `a` so that the exact
+        /// result is within 4 of `MAX` or `MIN` (for `ArithOp::Add`), or
+        /// within `2 * a.abs()` of it (for `ArithOp::Mul`), so that pairs
+        /// frequently fall just either side of the overflow boundary.
+        /// Otherwise, both operands are drawn uniformly from the whole range.
+        ///
+        /// Both operands shrink independently towards 0, so pairs shrink
+        /// towards `(0, 0)`.
+        pub fn overflow_pairs(op: $crate::num::ArithOp) -> OverflowPairs {
+            OverflowPairs { op }
+        }
+
+        impl Strategy for OverflowPairs {
+            type Tree =
+                crate::tuple::TupleValueTree<(BinarySearch, BinarySearch)>;
+            type Value = ($typ, $typ);
+
+            fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+                let rng = runner.rng();
+                let (a, b) = if rng.gen() {
+                    let target =
+                        if rng.gen() { <$typ>::MAX } else { <$typ>::MIN };
+                    match self.op {
+                        $crate::num::ArithOp::Add => {
+                            // `target - a` cannot overflow if `a` has the
+                            // same sign as `target`.
+                            let a = if target > 0 {
+                                rng.gen_range(0..=target)
+                            } else {
+                                rng.gen_range(target..0)
+                            };
+                            let offset = rng.gen_range(-4..=4);
+                            (a, (target - a).saturating_add(offset))
+                        }
+                        $crate::num::ArithOp::Mul => {
+                            // Pick the magnitude of `a` on a logarithmic
+                            // scale, so that both balanced and lopsided
+                            // pairs are common.
+                            let bits = rng.gen_range(1..<$typ>::BITS);
+                            let mut a = rng.gen_range(
+                                (1 as $typ) << (bits - 1)
+                                    ..=<$typ>::MAX >> (<$typ>::BITS - 1 - bits),
+                            );
+                            if rng.gen() {
+                                a = -a;
+                            }
+                            // Only `MIN / -1` overflows.
+                            let b =
+                                target.checked_div(a).unwrap_or(<$typ>::MAX);
+                            (a, b.saturating_add(rng.gen_range(-1..=1)))
+                        }
+                    }
+                } else {
+                    (rng.gen(), rng.gen())
+                };
+                Ok(crate::tuple::TupleValueTree::new((
+                    BinarySearch::new(a),
+                    BinarySearch::new(b),
+                )))
+            }
+        }
+    };
+}
+
 macro_rules! signed_integer_bin_search {
     ($typ:ident) => {
         #[allow(missing_docs)]
@@ -254,6 +581,9 @@ macro_rules! signed_integer_bin_search {
             }
 
             numeric_api!($typ, 1);
+            int_edgy!($typ);
+            int_range_excluding!($typ);
+            int_overflow_pairs!($typ);
         }
     };
 }
@@ -341,7 +671,49 @@ macro_rules! unsigned_integer_bin_search {
                 }
             }
 
+            /// Strategy returned by [`geometric()`](fn.geometric.html).
+            #[derive(Clone, Copy, Debug)]
+            #[must_use = "strategies do nothing unless used"]
+            pub struct Geometric(f64);
+
+            /// Generates integers following a geometric distribution with
+            /// success probability `p`.
+            ///
+            /// Small values are exponentially more likely than large ones:
+            /// `0` is generated with probability `p`, `1` with probability
+            /// `p * (1 - p)`, and so on, giving a mean of `(1 - p) / p`. This
+            /// makes it well suited to collection lengths and similar sizes
+            /// which should usually be small but occasionally large. Values
+            /// which would exceed the range of the type saturate to `MAX`.
+            ///
+            /// Shrinks by binary searching towards 0.
+            ///
+            /// ## Panics
+            ///
+            /// Panics if `p` is not in the range `(0.0, 1.0]`.
+            pub fn geometric(p: f64) -> Geometric {
+                assert!(
+                    p > 0.0 && p <= 1.0,
+                    "Geometric success probability must be in (0.0, 1.0], \
+                     got {}",
+                    p
+                );
+                Geometric(p)
+            }
+
+            impl Strategy for Geometric {
+                type Tree = BinarySearch;
+                type Value = $typ;
+
+                fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+                    let value = $crate::num::sample_geometric(runner, self.0);
+                    Ok(BinarySearch::new(value as $typ))
+                }
+            }
+
             numeric_api!($typ, 1);
+            int_edgy!($typ);
+            int_range_excluding!($typ);
         }
     };
 }
@@ -683,8 +1055,510 @@ macro_rules! float_any {
     }
 }
 
+macro_rules! float_any_bits {
+    ($typ:ident, $bits_typ:ident) => {
+        /// Strategy which generates every bit pattern of the float type with
+        /// equal probability.
+        ///
+        /// Created by `any_bits()` in the same module.
+        #[derive(Clone, Copy, Debug)]
+        #[must_use = "strategies do nothing unless used"]
+        pub struct AnyBits(());
+
+        /// Generate floats by picking a uniformly random bit pattern and
+        /// reinterpreting it as a float.
+        ///
+        /// Unlike `ANY` and the other class constants, this includes all NaN
+        /// payloads (quiet and signaling, with either sign), both infinities,
+        /// both zeroes and all subnormals, each in proportion to the number of
+        /// bit patterns representing it. This makes it mostly useful for
+        /// checking that serialisation and similar code round-trips every
+        /// value bit-exactly. Most generated values are very large or very
+        /// small normals; NaNs make up about one in every `2^E` values, where
+        /// `E` is the number of exponent bits (8 for `f32`, 11 for `f64`).
+        ///
+        /// Shrinking is performed on the bit pattern itself, towards the
+        /// pattern of `+0.0`.
+        pub fn any_bits() -> AnyBits {
+            AnyBits(())
+        }
+
+        impl Strategy for AnyBits {
+            type Tree = AnyBitsValueTree;
+            type Value = $typ;
+
+            fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+                Ok(AnyBitsValueTree(super::$bits_typ::BinarySearch::new(
+                    runner.rng().gen(),
+                )))
+            }
+        }
+
+        /// `ValueTree` corresponding to `AnyBits`.
+        #[derive(Clone, Copy, Debug)]
+        pub struct AnyBitsValueTree(super::$bits_typ::BinarySearch);
+
+        impl ValueTree for AnyBitsValueTree {
+            type Value = $typ;
+
+            fn current(&self) -> $typ {
+                $typ::from_bits(self.0.current())
+            }
+
+            fn simplify(&mut self) -> bool {
+                self.0.simplify()
+            }
+
+            fn complicate(&mut self) -> bool {
+                self.0.complicate()
+            }
+        }
+    };
+}
+
+macro_rules! float_near_zero {
+    ($typ:ident, $bits_typ:ident) => {
+        /// Strategy which generates small floats, emphasising subnormals.
+        ///
+        /// Created by `near_zero()` in the same module.
+        #[derive(Clone, Copy, Debug)]
+        #[must_use = "strategies do nothing unless used"]
+        pub struct NearZero(());
+
+        /// Generate floats in `(-1.0, 1.0)`, concentrated on the values which
+        /// tend to break numerical code.
+        ///
+        /// Uniform sampling practically never produces subnormals or values
+        /// close to machine epsilon. Instead, the sign is chosen at random
+        /// and then the magnitude is picked as follows:
+        ///
+        /// - One time in eight, `0.0`, so that both `+0.0` and `-0.0` occur.
+        ///
+        /// - One time in four, a subnormal.
+        ///
+        /// - One time in four, a normal within 4 binades of `EPSILON`.
+        ///
+        /// - Otherwise, a normal below `1.0`, with each binade equally
+        ///   likely.
+        ///
+        /// Within a binade, the mantissa is uniformly random.
+        ///
+        /// Shrinks by binary searching towards `+0.0`.
+        pub fn near_zero() -> NearZero {
+            NearZero(())
+        }
+
+        impl Strategy for NearZero {
+            type Tree = NearZeroValueTree;
+            type Value = $typ;
+
+            fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+                const MANTISSA_BITS: u32 = $typ::MANTISSA_DIGITS - 1;
+                // The biased exponents of 1.0 and EPSILON.
+                const ONE_EXP: $bits_typ =
+                    <$typ as FloatLayout>::EXP_ZERO >> MANTISSA_BITS;
+                const EPSILON_EXP: $bits_typ =
+                    ONE_EXP - MANTISSA_BITS as $bits_typ;
+
+                let rng = runner.rng();
+                let bucket = rng.gen_range(0..8);
+                let exp = match bucket {
+                    0..=2 => 0,
+                    3 | 4 => rng.gen_range(EPSILON_EXP - 4..=EPSILON_EXP + 4),
+                    _ => rng.gen_range(1..ONE_EXP),
+                };
+                let mantissa = match bucket {
+                    0 => 0,
+                    _ => {
+                        let mantissa = rng.gen::<$bits_typ>()
+                            & <$typ as FloatLayout>::MANTISSA_MASK;
+                        // A zero mantissa would make a subnormal zero.
+                        if 0 == exp {
+                            mantissa.max(1)
+                        } else {
+                            mantissa
+                        }
+                    }
+                };
+                let sign = if rng.gen() {
+                    <$typ as FloatLayout>::SIGN_MASK
+                } else {
+                    0
+                };
+
+                let value =
+                    $typ::from_bits(sign | exp << MANTISSA_BITS | mantissa);
+                Ok(if value == 0.0 && value.is_sign_negative() {
+                    NearZeroValueTree {
+                        inner: BinarySearch::new(0.0),
+                        negative_zero: NegativeZero::Start,
+                    }
+                } else {
+                    NearZeroValueTree {
+                        inner: BinarySearch::new(value),
+                        negative_zero: NegativeZero::No,
+                    }
+                })
+            }
+        }
+
+        /// `ValueTree` corresponding to `NearZero`.
+        #[derive(Clone, Copy, Debug)]
+        pub struct NearZeroValueTree {
+            inner: BinarySearch,
+            negative_zero: NegativeZero,
+        }
+
+        // Tracks trees starting at `-0.0`, which `inner` cannot shrink to
+        // `+0.0`. For these, `inner` is always at `+0.0`.
+        #[derive(Clone, Copy, Debug)]
+        enum NegativeZero {
+            // The tree did not start at `-0.0`, so `inner` is in charge.
+            No,
+            // The current value is `-0.0`, which may still be simplified.
+            Start,
+            // The current value is `+0.0`.
+            Simplified,
+            // The current value is `-0.0`, since `+0.0` was rejected.
+            Complicated,
+        }
+
+        impl ValueTree for NearZeroValueTree {
+            type Value = $typ;
+
+            fn current(&self) -> $typ {
+                match self.negative_zero {
+                    NegativeZero::No | NegativeZero::Simplified => {
+                        self.inner.current()
+                    }
+                    NegativeZero::Start | NegativeZero::Complicated => -0.0,
+                }
+            }
+
+            fn simplify(&mut self) -> bool {
+                match self.negative_zero {
+                    NegativeZero::No => self.inner.simplify(),
+                    NegativeZero::Start => {
+                        self.negative_zero = NegativeZero::Simplified;
+                        true
+                    }
+                    NegativeZero::Simplified | NegativeZero::Complicated => {
+                        false
+                    }
+                }
+            }
+
+            fn complicate(&mut self) -> bool {
+                match self.negative_zero {
+                    NegativeZero::No => self.inner.complicate(),
+                    NegativeZero::Simplified => {
+                        self.negative_zero = NegativeZero::Complicated;
+                        true
+                    }
+                    NegativeZero::Start | NegativeZero::Complicated => false,
+                }
+            }
+        }
+    };
+}
+
+macro_rules! float_classified {
+    ($typ:ident) => {
+        /// Relative weights of the float classes generated by `classified()`.
+        ///
+        /// Each field is a weight as for `prop_oneof!`, so `{ nan: 30,
+        /// positive_infinity: 10, negative_infinity: 10, normal: 50,
+        /// ..ClassWeights::default() }` generates NaN 30% of the time. The
+        /// default weight of every class is `0`.
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+        pub struct ClassWeights {
+            /// The weight of quiet NaNs, with either sign.
+            pub nan: u32,
+            /// The weight of positive infinity.
+            pub positive_infinity: u32,
+            /// The weight of negative infinity.
+            pub negative_infinity: u32,
+            /// The weight of subnormals, with either sign.
+            pub subnormal: u32,
+            /// The weight of normals, with either sign.
+            pub normal: u32,
+            /// The weight of `+0.0` and `-0.0`, which are equally likely.
+            pub zero: u32,
+        }
+
+        type Zero = TupleUnion<(WA<Just<$typ>>, WA<Just<$typ>>)>;
+
+        opaque_strategy_wrapper! {
+            /// Strategy which generates floats from each class in proportion
+            /// to its weight.
+            ///
+            /// Created by `classified()` in the same module.
+            #[derive(Clone, Debug)]
+            pub struct Classified[][](TupleUnion<(
+                WA<Zero>,
+                WA<Any>,
+                WA<Any>,
+                WA<Just<$typ>>,
+                WA<Just<$typ>>,
+                WA<Any>,
+            )>) -> ClassifiedValueTree;
+            /// `ValueTree` corresponding to `Classified`.
+            #[derive(Clone, Debug)]
+            pub struct ClassifiedValueTree[][](TupleUnionValueTree<(
+                LazyValueTree<Zero>,
+                Option<LazyValueTree<Any>>,
+                Option<LazyValueTree<Any>>,
+                Option<LazyValueTree<Just<$typ>>>,
+                Option<LazyValueTree<Just<$typ>>>,
+                Option<LazyValueTree<Any>>,
+            )>) -> $typ;
+        }
+
+        /// Generate floats from the classes given by `weights`, in proportion
+        /// to their weights.
+        ///
+        /// Subnormals, normals and NaNs are generated as by `SUBNORMAL`,
+        /// `NORMAL` and `QUIET_NAN`, each OR'ed with `POSITIVE | NEGATIVE`.
+        ///
+        /// Values shrink within their class first, and then towards `+0.0`,
+        /// even if `weights.zero` is `0`.
+        ///
+        /// ## Panics
+        ///
+        /// Panics if every weight is `0`.
+        pub fn classified(weights: ClassWeights) -> Classified {
+            let ClassWeights {
+                nan,
+                positive_infinity,
+                negative_infinity,
+                subnormal,
+                normal,
+                zero,
+            } = weights;
+            assert!(
+                nan > 0
+                    || positive_infinity > 0
+                    || negative_infinity > 0
+                    || subnormal > 0
+                    || normal > 0
+                    || zero > 0,
+                "ClassWeights must not all be zero"
+            );
+            let signed = POSITIVE | NEGATIVE;
+
+            // The classes are ordered so that each shrinks to the ones before
+            // it, ending at `+0.0`.
+            Classified(TupleUnion::new((
+                (
+                    zero,
+                    Arc::new(TupleUnion::new((
+                        (1, Arc::new(Just(0.0))),
+                        (1, Arc::new(Just(-0.0))),
+                    ))),
+                ),
+                (normal, Arc::new(signed | NORMAL)),
+                (subnormal, Arc::new(signed | SUBNORMAL)),
+                (positive_infinity, Arc::new(Just($typ::INFINITY))),
+                (negative_infinity, Arc::new(Just($typ::NEG_INFINITY))),
+                (nan, Arc::new(signed | QUIET_NAN)),
+            )))
+        }
+    };
+}
+
+macro_rules! float_sig_digits {
+    ($typ:ident) => {
+        /// Strategy which generates floats with a given number of significant
+        /// digits.
+        ///
+        /// Created by `with_sig_digits()` in the same module.
+        #[derive(Clone, Debug)]
+        #[must_use = "strategies do nothing unless used"]
+        pub struct SigDigits {
+            digits: SizeRange,
+        }
+
+        /// Generate nonzero floats whose shortest decimal representation
+        /// which round-trips, as printed by `Display`, has a number of
+        /// significant digits within `digits`, e.g., for testing the
+        /// formatting and parsing of decimals.
+        ///
+        /// Each value is built from a decimal mantissa of the chosen number
+        /// of digits, whose last digit is nonzero, scaled by a power of
+        /// ten, so that the leading digit is between the `1e-10` and `1e10`
+        /// places, and given a random sign. Any decimal with at most
+        /// `DIGITS` (6 for `f32` and 15 for `f64`) significant digits
+        /// round-trips through the nearest float, so the shortest
+        /// representation of the float is that decimal.
+        ///
+        /// Shrinking first removes trailing digits, down to the minimum in
+        /// `digits`, keeping the leading digit in place, then makes the
+        /// value positive, and then moves the leading digit towards the
+        /// ones place.
+        ///
+        /// ## Panics
+        ///
+        /// Panics if `digits` includes 0 or a number of digits greater than
+        /// `DIGITS`.
+        pub fn with_sig_digits(digits: impl Into<SizeRange>) -> SigDigits {
+            let digits = digits.into();
+            digits.assert_nonempty();
+            assert!(
+                digits.start() > 0
+                    && digits.end_incl() <= $typ::DIGITS as usize,
+                "with_sig_digits() needs between 1 and {} digits, got {}..={}",
+                $typ::DIGITS,
+                digits.start(),
+                digits.end_incl()
+            );
+            SigDigits { digits }
+        }
+
+        impl Strategy for SigDigits {
+            type Tree = SigDigitsValueTree;
+            type Value = $typ;
+
+            fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+                let digits = self.digits.sample(runner)?;
+                let rng = runner.rng();
+                // The last digit is nonzero, so that it is significant.
+                let mantissa = if 1 == digits {
+                    rng.gen_range(1..=9)
+                } else {
+                    let high = 10u64.pow(digits as u32 - 2);
+                    rng.gen_range(high..high * 10) * 10 + rng.gen_range(1..=9)
+                };
+                Ok(SigDigitsValueTree {
+                    decimal: Decimal {
+                        mantissa,
+                        digits,
+                        lead: rng.gen_range(-10..=10),
+                        negative: rng.gen(),
+                    },
+                    min_digits: self.digits.start(),
+                    shrink: SigDigitsShrink::Digits,
+                    prev: None,
+                })
+            }
+        }
+
+        /// A decimal of `digits` significant digits, with the leading one in
+        /// the `10^lead` place.
+        #[derive(Clone, Copy, Debug)]
+        struct Decimal {
+            mantissa: u64,
+            digits: usize,
+            lead: i32,
+            negative: bool,
+        }
+
+        #[derive(Clone, Copy, Debug)]
+        enum SigDigitsShrink {
+            Digits,
+            Sign,
+            Lead,
+            Done,
+        }
+
+        /// `ValueTree` corresponding to `SigDigits`.
+        #[derive(Clone, Copy, Debug)]
+        pub struct SigDigitsValueTree {
+            decimal: Decimal,
+            min_digits: usize,
+            shrink: SigDigitsShrink,
+            // The decimal before the last simplification.
+            prev: Option<Decimal>,
+        }
+
+        impl ValueTree for SigDigitsValueTree {
+            type Value = $typ;
+
+            fn current(&self) -> $typ {
+                let Decimal {
+                    mantissa,
+                    digits,
+                    lead,
+                    negative,
+                } = self.decimal;
+                // Parsing rounds correctly, which computing the power of ten
+                // in floating point would not.
+                let exp = lead - (digits as i32 - 1);
+                let value: $typ =
+                    format!("{}e{}", mantissa, exp).parse().unwrap();
+                if negative {
+                    -value
+                } else {
+                    value
+                }
+            }
+
+            fn simplify(&mut self) -> bool {
+                let mut next = self.decimal;
+                loop {
+                    match self.shrink {
+                        SigDigitsShrink::Digits
+                            if next.digits > self.min_digits =>
+                        {
+                            // Keep the last digit nonzero without carrying
+                            // into the ones before it.
+                            next.mantissa /= 10;
+                            if 0 == next.mantissa % 10 {
+                                next.mantissa += 1;
+                            }
+                            next.digits -= 1;
+                            break;
+                        }
+                        SigDigitsShrink::Sign if next.negative => {
+                            next.negative = false;
+                            break;
+                        }
+                        SigDigitsShrink::Lead if 0 != next.lead => {
+                            next.lead /= 2;
+                            break;
+                        }
+                        SigDigitsShrink::Digits => {
+                            self.shrink = SigDigitsShrink::Sign
+                        }
+                        SigDigitsShrink::Sign => {
+                            self.shrink = SigDigitsShrink::Lead
+                        }
+                        SigDigitsShrink::Lead | SigDigitsShrink::Done => {
+                            self.shrink = SigDigitsShrink::Done;
+                            self.prev = None;
+                            return false;
+                        }
+                    }
+                }
+
+                self.prev = Some(self.decimal);
+                self.decimal = next;
+                true
+            }
+
+            fn complicate(&mut self) -> bool {
+                match self.prev.take() {
+                    None => false,
+                    Some(prev) => {
+                        // The simpler value passed, so move on to the next
+                        // kind of simplification.
+                        self.decimal = prev;
+                        self.shrink = match self.shrink {
+                            SigDigitsShrink::Digits => SigDigitsShrink::Sign,
+                            SigDigitsShrink::Sign => SigDigitsShrink::Lead,
+                            SigDigitsShrink::Lead | SigDigitsShrink::Done => {
+                                SigDigitsShrink::Done
+                            }
+                        };
+                        true
+                    }
+                }
+            }
+        }
+    };
+}
+
 macro_rules! float_bin_search {
-    ($typ:ident, $sample_typ:ident) => {
+    ($typ:ident, $sample_typ:ident, $bits_typ:ident) => {
         #[allow(missing_docs)]
         pub mod $typ {
             use super::float_samplers::$sample_typ;
@@ -696,10 +1570,16 @@ macro_rules! float_bin_search {
             use rand::Rng;
 
             use super::{FloatLayout, FloatTypes};
+            use crate::collection::SizeRange;
+            use crate::std_facade::Arc;
             use crate::strategy::*;
             use crate::test_runner::TestRunner;
 
             float_any!($typ);
+            float_any_bits!($typ, $bits_typ);
+            float_near_zero!($typ, $bits_typ);
+            float_classified!($typ);
+            float_sig_digits!($typ);
 
             /// Shrinks a float towards 0, using binary search to find boundary
             /// points.
@@ -869,8 +1749,8 @@ macro_rules! float_bin_search {
     };
 }
 
-float_bin_search!(f32, F32U);
-float_bin_search!(f64, F64U);
+float_bin_search!(f32, F32U, u32);
+float_bin_search!(f64, F64U, u64);
 
 #[cfg(test)]
 mod test {
@@ -1103,6 +1983,223 @@ mod test {
         contract_sanity!(f64);
     }
 
+    #[test]
+    fn edgy_favours_boundary_values() {
+        let mut runner = TestRunner::deterministic();
+        let input = i64::edgy(-1000..=1000);
+        let samples = 10_000;
+        let mut boundary = 0;
+        for _ in 0..samples {
+            let v = input.new_tree(&mut runner).unwrap().current();
+            assert!((-1000..=1000).contains(&v), "{}", v);
+            if [-1000, -1, 0, 1, 1000].contains(&v) {
+                boundary += 1;
+            }
+        }
+
+        // Uniform generation would produce roughly 25 of these.
+        assert!(boundary > 500, "boundary = {}", boundary);
+    }
+
+    #[test]
+    fn edgy_includes_type_extremes_and_shrinks_like_range() {
+        let mut runner = TestRunner::deterministic();
+        let mut seen_min = false;
+        let mut seen_max = false;
+        for _ in 0..1000 {
+            let mut tree =
+                i8::edgy(i8::MIN..=i8::MAX).new_tree(&mut runner).unwrap();
+            seen_min |= i8::MIN == tree.current();
+            seen_max |= i8::MAX == tree.current();
+            while tree.simplify() {}
+            assert_eq!(0, tree.current());
+
+            let mut tree = u16::edgy(10..=20).new_tree(&mut runner).unwrap();
+            assert!(tree.current() >= 10 && tree.current() <= 20);
+            while tree.simplify() {}
+            assert_eq!(10, tree.current());
+        }
+        assert!(seen_min && seen_max);
+    }
+
+    #[test]
+    fn edgy_sanity() {
+        check_strategy_sanity(i32::edgy(-100..=100), None);
+        check_strategy_sanity(u32::edgy(5..=100), None);
+    }
+
+    #[test]
+    fn range_excluding_never_generates_excluded_values() {
+        let mut runner = TestRunner::deterministic();
+        let excluded = [-3, 0, 1, 2, 7, 100];
+        let input = i32::range_excluding(-5..=8, &excluded);
+        let mut counts = [0u32; 14];
+        for _ in 0..10_000 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            let v = tree.current();
+            assert!((-5..=8).contains(&v) && !excluded.contains(&v), "{}", v);
+            counts[(v + 5) as usize] += 1;
+            while tree.simplify() {
+                assert!(!excluded.contains(&tree.current()));
+            }
+            assert_eq!(-1, tree.current());
+        }
+
+        // Every allowed value is generated about equally often.
+        for (ix, &count) in counts.iter().enumerate() {
+            if excluded.contains(&(ix as i32 - 5)) {
+                assert_eq!(0, count);
+            } else {
+                assert!(count > 900 && count < 1300, "counts = {:?}", counts);
+            }
+        }
+    }
+
+    #[test]
+    fn range_excluding_shrinks_to_nearest_allowed_value() {
+        let mut runner = TestRunner::deterministic();
+        let cases: [(core::ops::RangeInclusive<i8>, &[i8], i8); 5] = [
+            (-10..=10, &[5], 0),
+            (-10..=10, &[0, 1], -1),
+            (5..=10, &[5, 6], 7),
+            (-10..=-5, &[-5], -6),
+            // -1 and 1 are equally close to 0; the positive one wins.
+            (i8::MIN..=i8::MAX, &[0], 1),
+        ];
+        for &(ref range, excluded, expected) in &cases {
+            let input = i8::range_excluding(range.clone(), excluded);
+            for _ in 0..256 {
+                let mut tree = input.new_tree(&mut runner).unwrap();
+                assert!(range.contains(&tree.current()));
+                assert!(!excluded.contains(&tree.current()));
+                while tree.simplify() {}
+                assert_eq!(expected, tree.current());
+            }
+        }
+
+        let mut seen = [false; 256];
+        let input = u8::range_excluding(0..=255, &[0, 254]);
+        for _ in 0..10_000 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            seen[tree.current() as usize] = true;
+            while tree.simplify() {}
+            assert_eq!(1, tree.current());
+        }
+        assert!(!seen[0] && seen[1] && !seen[254] && seen[255]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn range_excluding_rejects_fully_excluded_range() {
+        let _ = u32::range_excluding(1..=2, &[2, 1]);
+    }
+
+    #[test]
+    fn range_excluding_sanity() {
+        check_strategy_sanity(i32::range_excluding(-100..=100, &[0]), None);
+        check_strategy_sanity(u32::range_excluding(5..=100, &[5, 50]), None);
+    }
+
+    #[test]
+    fn overflow_pairs_straddle_the_boundary() {
+        let mut runner = TestRunner::deterministic();
+        for &op in &[ArithOp::Add, ArithOp::Mul] {
+            let input = i64::overflow_pairs(op);
+            let samples = 10_000;
+            let (mut overflows, mut near_misses) = (0, 0);
+            for _ in 0..samples {
+                let mut tree = input.new_tree(&mut runner).unwrap();
+                let (a, b) = tree.current();
+                let exact = match op {
+                    ArithOp::Add => i128::from(a) + i128::from(b),
+                    ArithOp::Mul => i128::from(a) * i128::from(b),
+                };
+                let slack = match op {
+                    ArithOp::Add => 4,
+                    ArithOp::Mul => i128::from(a).abs(),
+                };
+                let max = i128::from(i64::MAX);
+                let min = i128::from(i64::MIN);
+                if (exact - max).abs() <= slack || (exact - min).abs() <= slack
+                {
+                    if exact > max || exact < min {
+                        overflows += 1;
+                    } else {
+                        near_misses += 1;
+                    }
+                }
+                while tree.simplify() {}
+                assert_eq!((0, 0), tree.current());
+            }
+
+            // Uniform generation would essentially never land this close.
+            assert!(overflows > samples / 20, "{:?}: {}", op, overflows);
+            assert!(near_misses > samples / 20, "{:?}: {}", op, near_misses);
+        }
+    }
+
+    #[test]
+    fn overflow_pairs_sanity() {
+        check_strategy_sanity(i8::overflow_pairs(ArithOp::Add), None);
+        check_strategy_sanity(i32::overflow_pairs(ArithOp::Mul), None);
+    }
+
+    #[test]
+    fn geometric_frequencies_decay_exponentially() {
+        let mut runner = TestRunner::deterministic();
+        let mut counts = [0u32; 8];
+        let samples = 100_000;
+        for _ in 0..samples {
+            let v =
+                u32::geometric(0.5).new_tree(&mut runner).unwrap().current();
+            if (v as usize) < counts.len() {
+                counts[v as usize] += 1;
+            }
+        }
+
+        // P(0) = 0.5, and each subsequent value is half as likely as the one
+        // before it.
+        let p0 = counts[0] as f64 / samples as f64;
+        assert!((p0 - 0.5).abs() < 0.01, "P(0) = {}", p0);
+        for window in counts[..5].windows(2) {
+            let ratio = window[1] as f64 / window[0] as f64;
+            assert!((ratio - 0.5).abs() < 0.05, "counts = {:?}", counts);
+        }
+    }
+
+    #[test]
+    fn geometric_with_certain_success_is_always_zero() {
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let v = u8::geometric(1.0).new_tree(&mut runner).unwrap().current();
+            assert_eq!(0, v);
+        }
+    }
+
+    #[test]
+    fn geometric_saturates_and_shrinks_to_zero() {
+        let mut runner = TestRunner::deterministic();
+        let mut saw_max = false;
+        for _ in 0..256 {
+            let mut tree = u8::geometric(0.001).new_tree(&mut runner).unwrap();
+            saw_max |= tree.current() == u8::MAX;
+            while tree.simplify() {}
+            assert_eq!(0, tree.current());
+        }
+        assert!(saw_max);
+    }
+
+    #[test]
+    #[should_panic]
+    fn geometric_rejects_zero_probability() {
+        let _ = u32::geometric(0.0);
+    }
+
+    #[test]
+    fn geometric_sanity() {
+        check_strategy_sanity(u32::geometric(0.1), None);
+    }
+
     #[test]
     fn unsigned_integer_binsearch_simplify_complicate_contract_upheld() {
         check_strategy_sanity(0u32..1000u32, None);
@@ -1205,6 +2302,260 @@ mod test {
         assert_eq!(::std::f64::MIN_POSITIVE, value.current());
     }
 
+    #[test]
+    fn any_bits_reaches_every_float_class() {
+        use std::num::FpCategory;
+
+        let mut runner = TestRunner::deterministic();
+        let mut normals = 0;
+        let mut subnormals = 0;
+        let mut quiet_nans = 0;
+        let mut signaling_nans = 0;
+        for _ in 0..65536 {
+            let value =
+                f64::any_bits().new_tree(&mut runner).unwrap().current();
+            let quiet = value.to_bits() & (1 << 51) != 0;
+            match value.classify() {
+                FpCategory::Normal => normals += 1,
+                FpCategory::Subnormal => subnormals += 1,
+                FpCategory::Nan if quiet => quiet_nans += 1,
+                FpCategory::Nan => signaling_nans += 1,
+                FpCategory::Zero | FpCategory::Infinite => (),
+            }
+        }
+        assert!(normals > 0, "Never generated a normal");
+        assert!(subnormals > 0, "Never generated a subnormal");
+        assert!(quiet_nans > 0, "Never generated a quiet NaN");
+        assert!(signaling_nans > 0, "Never generated a signaling NaN");
+
+        // Zeroes and infinities are two bit patterns each, far too rare to
+        // sample, so feed them in directly to check they pass through
+        // unchanged.
+        for &expected in &[0.0, -0.0, f64::INFINITY, f64::NEG_INFINITY] {
+            let rng = TestRng::from_seed(
+                RngAlgorithm::PassThrough,
+                &expected.to_bits().to_le_bytes(),
+            );
+            let mut runner = TestRunner::new_with_rng(Config::default(), rng);
+            let value =
+                f64::any_bits().new_tree(&mut runner).unwrap().current();
+            assert_eq!(expected.to_bits(), value.to_bits());
+        }
+    }
+
+    #[test]
+    fn any_bits_shrinks_to_positive_zero() {
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut value = f64::any_bits().new_tree(&mut runner).unwrap();
+            while value.simplify() {}
+            assert_eq!(0, value.current().to_bits());
+
+            let mut value = f32::any_bits().new_tree(&mut runner).unwrap();
+            while value.simplify() {}
+            assert_eq!(0, value.current().to_bits());
+        }
+    }
+
+    #[test]
+    fn near_zero_emphasises_subnormals_and_epsilon() {
+        use std::num::FpCategory;
+
+        let mut runner = TestRunner::deterministic();
+        let samples = 10_000;
+        let mut subnormals = 0;
+        let mut near_epsilon = 0;
+        let mut positive_zeroes = 0;
+        let mut negative_zeroes = 0;
+        for _ in 0..samples {
+            let value =
+                f64::near_zero().new_tree(&mut runner).unwrap().current();
+            assert!(value.abs() < 1.0, "{}", value);
+            match value.classify() {
+                FpCategory::Subnormal => subnormals += 1,
+                FpCategory::Zero if value.is_sign_positive() => {
+                    positive_zeroes += 1
+                }
+                FpCategory::Zero => negative_zeroes += 1,
+                FpCategory::Normal => {
+                    let ratio = value.abs() / f64::EPSILON;
+                    if (1.0 / 16.0..32.0).contains(&ratio) {
+                        near_epsilon += 1;
+                    }
+                }
+                FpCategory::Infinite | FpCategory::Nan => {
+                    panic!("Generated {}", value)
+                }
+            }
+
+            let value =
+                f32::near_zero().new_tree(&mut runner).unwrap().current();
+            assert!(value.abs() < 1.0, "{}", value);
+        }
+
+        // A uniform sampler would essentially never produce these.
+        assert!(subnormals > samples / 5, "subnormals = {}", subnormals);
+        assert!(
+            near_epsilon > samples / 5,
+            "near_epsilon = {}",
+            near_epsilon
+        );
+        assert!(positive_zeroes > 0 && negative_zeroes > 0);
+    }
+
+    #[test]
+    fn near_zero_shrinks_to_positive_zero() {
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut value = f64::near_zero().new_tree(&mut runner).unwrap();
+            while value.simplify() {}
+            assert_eq!(0, value.current().to_bits());
+
+            let mut value = f32::near_zero().new_tree(&mut runner).unwrap();
+            while value.simplify() {}
+            assert_eq!(0, value.current().to_bits());
+        }
+    }
+
+    #[test]
+    fn near_zero_sanity() {
+        check_strategy_sanity(f64::near_zero(), None);
+        check_strategy_sanity(f32::near_zero(), None);
+    }
+
+    #[test]
+    fn classified_honours_class_weights() {
+        use std::num::FpCategory;
+
+        let weights = f64::ClassWeights {
+            nan: 30,
+            positive_infinity: 10,
+            negative_infinity: 10,
+            subnormal: 10,
+            normal: 30,
+            zero: 10,
+        };
+        let mut runner = TestRunner::deterministic();
+        let samples = 10_000;
+        // NaN, +inf, -inf, subnormal, normal, zero.
+        let mut counts = [0u32; 6];
+        let mut negative_zeroes = 0;
+        for _ in 0..samples {
+            let value = f64::classified(weights)
+                .new_tree(&mut runner)
+                .unwrap()
+                .current();
+            let class = match value.classify() {
+                FpCategory::Nan => 0,
+                FpCategory::Infinite if value > 0.0 => 1,
+                FpCategory::Infinite => 2,
+                FpCategory::Subnormal => 3,
+                FpCategory::Normal => 4,
+                FpCategory::Zero => {
+                    negative_zeroes += value.is_sign_negative() as u32;
+                    5
+                }
+            };
+            counts[class] += 1;
+        }
+
+        let expected = [30, 10, 10, 10, 30, 10];
+        for (&count, &percent) in counts.iter().zip(&expected) {
+            let expected = samples * percent / 100;
+            assert!(
+                count > expected * 9 / 10 && count < expected * 11 / 10,
+                "counts = {:?}",
+                counts
+            );
+        }
+        assert!(negative_zeroes > 0 && negative_zeroes < counts[5]);
+
+        let infinities = f32::classified(f32::ClassWeights {
+            positive_infinity: 1,
+            ..f32::ClassWeights::default()
+        });
+        for _ in 0..16 {
+            let value = infinities.new_tree(&mut runner).unwrap().current();
+            assert_eq!(f32::INFINITY, value);
+        }
+    }
+
+    #[test]
+    fn classified_shrinks_to_positive_zero() {
+        let weights = f64::ClassWeights {
+            nan: 1,
+            positive_infinity: 1,
+            negative_infinity: 1,
+            subnormal: 1,
+            normal: 1,
+            zero: 0,
+        };
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut value =
+                f64::classified(weights).new_tree(&mut runner).unwrap();
+            while value.simplify() {}
+            assert_eq!(0, value.current().to_bits());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "ClassWeights must not all be zero")]
+    fn classified_rejects_zero_weights() {
+        let _ = f64::classified(f64::ClassWeights::default());
+    }
+
+    /// The number of significant digits in a float printed by `Display`,
+    /// which never uses exponents, so trailing zeros before the decimal point
+    /// are only placeholders.
+    fn sig_digits(s: &str) -> usize {
+        s.trim_start_matches(['-', '0', '.'])
+            .trim_end_matches('0')
+            .chars()
+            .filter(char::is_ascii_digit)
+            .count()
+    }
+
+    #[test]
+    fn sig_digits_are_printed_while_shrinking() {
+        let mut runner = TestRunner::deterministic();
+        let mut seen = [false; 16];
+        for _ in 0..1024 {
+            let mut value =
+                f64::with_sig_digits(3..=15).new_tree(&mut runner).unwrap();
+            let mut prev = sig_digits(&format!("{}", value.current()));
+            assert!((3..=15).contains(&prev), "{}", value.current());
+            seen[prev] = true;
+            while value.simplify() {
+                let digits = sig_digits(&format!("{}", value.current()));
+                assert!(digits >= 3 && digits <= prev, "{}", value.current());
+                prev = digits;
+            }
+            assert_eq!(3, prev);
+            assert!(value.current() >= 1.0 && value.current() < 10.0);
+        }
+        assert!(seen[3..].iter().all(|&seen| seen));
+
+        for _ in 0..256 {
+            let value = f32::with_sig_digits(6)
+                .new_tree(&mut runner)
+                .unwrap()
+                .current();
+            assert_eq!(6, sig_digits(&format!("{}", value)), "{}", value);
+        }
+    }
+
+    #[test]
+    fn sig_digits_sanity() {
+        check_strategy_sanity(f64::with_sig_digits(1..8), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "needs between 1 and 15 digits")]
+    fn sig_digits_rejects_too_many_digits() {
+        let _ = f64::with_sig_digits(10..=16);
+    }
+
     macro_rules! float_generation_test_body {
         ($strategy:ident, $typ:ident) => {
             use std::num::FpCategory;
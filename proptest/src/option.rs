@@ -33,6 +33,27 @@ pub fn prob(from: impl Into<Probability>) -> Probability {
     from.into()
 }
 
+/// Creates the `Probability` of `Some` for values which should be `None` with
+/// probability `none_probability`.
+///
+/// This is useful for tuning how sparse `any_with::<Option<T>>()` is, since
+/// real data often has far fewer `None`s than the default of 50%:
+///
+/// ```
+/// use proptest::option::prob_none;
+/// use proptest::prelude::*;
+///
+/// let mostly_some = any_with::<Option<u32>>(prob_none(0.05).lift());
+/// # let _ = mostly_some;
+/// ```
+///
+/// # Panics
+///
+/// Panics if `none_probability` lies outside interval `[0.0, 1.0]`.
+pub fn prob_none(none_probability: impl Into<Probability>) -> Probability {
+    Probability(1.0 - f64::from(none_probability.into()))
+}
+
 impl Default for Probability {
     /// The default probability is 0.5, or 50% chance.
     fn default() -> Self {
@@ -234,6 +255,22 @@ pub fn weighted<T: Strategy>(
     )))
 }
 
+/// Return a strategy producing `Optional` values wrapping values from the
+/// given delegate strategy, which are `None` with probability
+/// `none_probability`.
+///
+/// This is the same as `weighted()`, but in terms of `None`, which is usually
+/// the rarer case in real data, e.g., optional fields of records generated
+/// with `collection::vec(option::sparse(0.05, any::<u32>()), 0..100)`.
+///
+/// `Some` values shrink to `None`.
+pub fn sparse<T: Strategy>(
+    none_probability: impl Into<Probability>,
+    t: T,
+) -> OptionStrategy<T> {
+    weighted(prob_none(none_probability), t)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -264,6 +301,24 @@ mod test {
         assert!(count > 50 && count < 150);
     }
 
+    #[test]
+    fn low_none_probability_yields_mostly_some() {
+        let count = count_some_of_1000(sparse(0.05, Just(42i32)));
+        assert!(count > 900 && count < 990);
+
+        let input =
+            crate::arbitrary::any_with::<Option<i32>>(prob_none(0.05).lift());
+        let mut runner = TestRunner::deterministic();
+        let mut count = 0;
+        for _ in 0..1000 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            count += case.current().is_some() as u32;
+            while case.simplify() {}
+            assert_eq!(None, case.current());
+        }
+        assert!(count > 900 && count < 990);
+    }
+
     #[test]
     fn test_sanity() {
         check_strategy_sanity(of(0i32..1000i32), None);
@@ -13,18 +13,21 @@
 //! is, the input collection is not itself a strategy, but is rather fixed when
 //! the strategy is created.
 
-use crate::std_facade::{Arc, Cow, Vec};
+use crate::std_facade::{Arc, Box, Cow, Vec};
+use core::cmp::Reverse;
 use core::fmt;
 use core::mem;
-use core::ops::Range;
+use core::ops::{Range, RangeInclusive};
 use core::u64;
 
 use rand::Rng;
 
 use crate::bits::{self, BitSetValueTree, SampledBitSetStrategy, VarBitSet};
+use crate::collection::{VecStrategy, VecValueTree};
 use crate::num;
 use crate::strategy::*;
 use crate::test_runner::*;
+use crate::tuple::TupleValueTree;
 
 /// Re-exported to make usage more ergonomic.
 pub use crate::collection::{size_range, SizeRange};
@@ -161,6 +164,509 @@ pub fn select<T: Clone + fmt::Debug + 'static>(
     Select(statics::Map::new(0..cow.len(), SelectMapFn(Arc::new(cow))))
 }
 
+#[derive(Debug, Clone)]
+struct NoRepeatMapFn<T: Clone + 'static>(Arc<Cow<'static, [T]>>);
+
+impl<T: fmt::Debug + Clone + 'static> statics::MapFn<(usize, Vec<usize>)>
+    for NoRepeatMapFn<T>
+{
+    type Output = Vec<T>;
+
+    fn apply(&self, (first, mut rest): (usize, Vec<usize>)) -> Vec<T> {
+        // `rest` determines the length of the output, but its last choice is
+        // not needed since `first` takes the place of the initial element.
+        // Every other choice is drawn from `0..len - 1` and picks among the
+        // values other than the previous one, so no shrink of the choices can
+        // introduce a repeat.
+        if rest.pop().is_none() {
+            return Vec::new();
+        }
+
+        let mut prev = first;
+        let mut values = Vec::with_capacity(rest.len() + 1);
+        values.push(self.0[first].clone());
+        for choice in rest {
+            prev = if choice < prev { choice } else { choice + 1 };
+            values.push(self.0[prev].clone());
+        }
+        values
+    }
+}
+
+opaque_strategy_wrapper! {
+    /// Strategy to produce sequences of values from a fixed collection of
+    /// options without immediate repeats.
+    ///
+    /// Created by the `select_no_repeat()` in the same module.
+    #[derive(Clone, Debug)]
+    pub struct SelectNoRepeat[<T>][where T : Clone + fmt::Debug + 'static](
+        statics::Map<
+            (Range<usize>, VecStrategy<Range<usize>>),
+            NoRepeatMapFn<T>,
+        >)
+        -> SelectNoRepeatValueTree<T>;
+    /// `ValueTree` corresponding to `SelectNoRepeat`.
+    #[derive(Clone, Debug)]
+    pub struct SelectNoRepeatValueTree[<T>]
+        [where T : Clone + fmt::Debug + 'static](
+        statics::Map<
+            TupleValueTree<(
+                num::usize::BinarySearch,
+                VecValueTree<num::usize::BinarySearch>,
+            )>,
+            NoRepeatMapFn<T>,
+        >)
+        -> Vec<T>;
+}
+
+/// Create a strategy which generates `Vec`s with a length in `size` whose
+/// elements are selected from `values`, such that no element is selected twice
+/// in a row.
+///
+/// `values` should be a `&'static [T]` or a `Vec<T>`, or potentially another
+/// type that can be coerced to `Cow<'static,[T]>`. Consecutive elements are
+/// compared by their position in `values`, so `values` should not itself
+/// contain duplicates.
+///
+/// This is useful for generating sequences such as protocol commands where
+/// immediately repeating an alternative is not meaningful. Each element after
+/// the first is selected uniformly from the values other than the one before
+/// it. Shrinking removes elements and moves each one towards the
+/// start of `values`, and never introduces an immediate repeat.
+///
+/// ## Panics
+///
+/// Panics if `values` has fewer than two elements.
+pub fn select_no_repeat<T: Clone + fmt::Debug + 'static>(
+    values: impl Into<Cow<'static, [T]>>,
+    size: impl Into<SizeRange>,
+) -> SelectNoRepeat<T> {
+    let cow = values.into();
+    assert!(
+        cow.len() >= 2,
+        "select_no_repeat() needs at least two values, got {}",
+        cow.len()
+    );
+
+    SelectNoRepeat(statics::Map::new(
+        (0..cow.len(), crate::collection::vec(0..cow.len() - 1, size)),
+        NoRepeatMapFn(Arc::new(cow)),
+    ))
+}
+
+/// Walker's alias table for sampling indices with fixed integer weights in
+/// constant time.
+///
+/// Column `ix` is chosen uniformly, and then yields `ix` itself with
+/// probability `threshold[ix] / total`, or `alias[ix]` otherwise. Everything
+/// is kept in integers, so the probabilities are exact.
+#[derive(Clone, Debug)]
+struct AliasTable {
+    threshold: Vec<u128>,
+    alias: Vec<usize>,
+    total: u128,
+}
+
+impl AliasTable {
+    fn new(weights: &[u64]) -> Self {
+        let n = weights.len() as u128;
+        let total = weights.iter().map(|&w| u128::from(w)).sum::<u128>();
+        // Scale each weight by the number of columns so that every column
+        // holds exactly `total`.
+        let mut scaled: Vec<u128> =
+            weights.iter().map(|&w| u128::from(w) * n).collect();
+        let mut alias: Vec<usize> = (0..weights.len()).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..weights.len()).partition(|&ix| scaled[ix] < total);
+
+        while let (Some(s), Some(&l)) = (small.pop(), large.last()) {
+            // Fill the rest of column `s` from `l`.
+            alias[s] = l;
+            scaled[l] -= total - scaled[s];
+            if scaled[l] < total {
+                large.pop();
+                small.push(l);
+            }
+        }
+        // Whatever is left fills its own column exactly.
+        for ix in small.into_iter().chain(large) {
+            scaled[ix] = total;
+        }
+
+        AliasTable {
+            threshold: scaled,
+            alias,
+            total,
+        }
+    }
+
+    fn sample(&self, rng: &mut impl Rng) -> usize {
+        let ix = rng.gen_range(0..self.alias.len());
+        if rng.gen_range(0..self.total) < self.threshold[ix] {
+            ix
+        } else {
+            self.alias[ix]
+        }
+    }
+}
+
+/// Strategy to produce values with the frequencies given by a histogram.
+///
+/// Created by the `from_histogram()` function in the same module.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct Histogram<T: Clone + 'static> {
+    values: SelectMapFn<T>,
+    table: Arc<AliasTable>,
+}
+
+/// Create a strategy which selects values from `histogram`, a collection of
+/// values and their weights such as a `HashMap<T, u64>`, with probability
+/// proportional to their weight, e.g., to replay the distribution of inputs
+/// observed in production.
+///
+/// Sampling uses an alias table, so takes constant time however many
+/// values there are. Values with a weight of 0 are never generated, and the
+/// weights of duplicate values are added together.
+///
+/// Values shrink by binary search towards the value with the highest weight,
+/// through values in order of decreasing weight. Values with equal weights
+/// are ordered by `Ord`, so that the order does not depend on that of
+/// `histogram`, which for a `HashMap` may differ from run to run.
+///
+/// ## Panics
+///
+/// Panics if `histogram` has no values with a positive weight.
+pub fn from_histogram<T: Clone + Ord + fmt::Debug + 'static>(
+    histogram: impl IntoIterator<Item = (T, u64)>,
+) -> Histogram<T> {
+    let mut entries: Vec<(T, u64)> = histogram
+        .into_iter()
+        .filter(|&(_, weight)| weight > 0)
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    entries.dedup_by(|dupe, kept| {
+        if dupe.0 == kept.0 {
+            kept.1 += dupe.1;
+            true
+        } else {
+            false
+        }
+    });
+    entries.sort_by_key(|&(_, weight)| Reverse(weight));
+    assert!(
+        !entries.is_empty(),
+        "from_histogram() needs at least one value with a positive weight"
+    );
+
+    let (values, weights): (Vec<T>, Vec<u64>) = entries.into_iter().unzip();
+    Histogram {
+        values: SelectMapFn(Arc::new(Cow::Owned(values))),
+        table: Arc::new(AliasTable::new(&weights)),
+    }
+}
+
+impl<T: Clone + fmt::Debug + 'static> Strategy for Histogram<T> {
+    type Tree = SelectValueTree<T>;
+    type Value = T;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let ix = self.table.sample(runner.rng());
+        Ok(SelectValueTree(statics::Map::new(
+            num::usize::BinarySearch::new(ix),
+            self.values.clone(),
+        )))
+    }
+}
+
+/// An integer discriminant generated by `valid_and_invalid_discriminants()`,
+/// tagged with whether it is one of the valid values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Discriminant {
+    /// The discriminant itself.
+    pub value: i64,
+    /// Whether `value` is one of the valid discriminants.
+    pub valid: bool,
+}
+
+opaque_strategy_wrapper! {
+    /// Strategy to produce valid and invalid enum discriminants.
+    ///
+    /// Created by the `valid_and_invalid_discriminants()` in the same module.
+    #[derive(Debug)]
+    pub struct DiscriminantStrategy[][]
+        (SBoxedStrategy<Discriminant>) -> DiscriminantValueTree;
+    /// `ValueTree` corresponding to `DiscriminantStrategy`.
+    pub struct DiscriminantValueTree[][]
+        (Box<dyn ValueTree<Value = Discriminant>>) -> Discriminant;
+}
+
+/// Create a strategy which generates discriminants of a C-like enum, such as
+/// one received as an integer over FFI, which are equally likely to be one of
+/// the `valid` values or an invalid value in `invalid_range`.
+///
+/// Values in `invalid_range` which are also in `valid` are never generated as
+/// invalid values, so the `valid` tag of the result is always accurate.
+///
+/// Invalid discriminants shrink towards valid ones, the simpler case; valid
+/// ones shrink towards the start of `valid`, and invalid ones towards the
+/// start of `invalid_range`.
+///
+/// ## Panics
+///
+/// Panics if `valid` is empty, or if `invalid_range` contains no values
+/// other than valid ones.
+pub fn valid_and_invalid_discriminants(
+    valid: &[i64],
+    invalid_range: RangeInclusive<i64>,
+) -> DiscriminantStrategy {
+    assert!(
+        !valid.is_empty(),
+        "valid_and_invalid_discriminants() needs at least one valid value"
+    );
+
+    let (start, end) = invalid_range.into_inner();
+    let mut excluded = valid
+        .iter()
+        .cloned()
+        .filter(|v| (start..=end).contains(v))
+        .collect::<Vec<_>>();
+    excluded.sort_unstable();
+    excluded.dedup();
+    // Can't overflow: a range of all `i64`s contains at least one valid value.
+    let invalid_count = (i128::from(end) - i128::from(start) + 1)
+        .max(0)
+        .saturating_sub(excluded.len() as i128) as u64;
+    assert!(
+        invalid_count > 0,
+        "valid_and_invalid_discriminants() has no invalid values in {}..={}",
+        start,
+        end
+    );
+
+    let valid = select(valid.to_vec())
+        .prop_map(|value| Discriminant { value, valid: true });
+    // Map the `ix`th invalid value to its discriminant by skipping over the
+    // excluded values up to it.
+    let invalid = (0..invalid_count).prop_map(move |ix| {
+        let mut value = start.wrapping_add(ix as i64);
+        for &e in &excluded {
+            if e <= value {
+                value += 1;
+            }
+        }
+        Discriminant {
+            value,
+            valid: false,
+        }
+    });
+
+    DiscriminantStrategy(prop_oneof![valid, invalid].sboxed())
+}
+
+/// The relationship between the values of a triple generated by
+/// `ordered_triple()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OrderRelation {
+    /// `a < b < c`.
+    Increasing,
+    /// `a <= b <= c`.
+    NonDecreasing,
+    /// `a == b`, with `c` unconstrained.
+    FirstTwoEqual,
+    /// `a == b == c`.
+    AllEqual,
+    /// `a != b`, `b != c` and `a != c`, in no particular order.
+    AllDistinct,
+}
+
+impl OrderRelation {
+    /// The number of independent values needed to build a triple.
+    fn values(self) -> usize {
+        match self {
+            OrderRelation::AllEqual => 1,
+            OrderRelation::FirstTwoEqual => 2,
+            _ => 3,
+        }
+    }
+
+    /// Whether the values must all differ.
+    fn strict(self) -> bool {
+        matches!(self, OrderRelation::Increasing | OrderRelation::AllDistinct)
+    }
+}
+
+/// Strategy to produce triples of values with a given order relation.
+///
+/// Created by the `ordered_triple()` function in the same module.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct OrderedTriple<S> {
+    elem: S,
+    relation: OrderRelation,
+}
+
+/// Create a strategy which generates triples `(a, b, c)` of values from
+/// `elem` satisfying `relation`, e.g., for testing comparators and sorting.
+///
+/// Triples are built from as many values as the relation leaves free, which
+/// are sorted for `Increasing` and `NonDecreasing` and copied for the
+/// equalities, so the relation holds by construction. The exception is that
+/// `elem` may produce equal values when `Increasing` or `AllDistinct` need
+/// distinct ones; such triples are rejected locally, so `elem` should have
+/// many more than three values. Shrinking simplifies each value in turn,
+/// undoing any simplification which would make two of them equal, so it
+/// preserves the relation too.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::sample::{ordered_triple, OrderRelation};
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn max_is_last(
+///     (a, b, c) in ordered_triple(any::<i32>(), OrderRelation::Increasing)
+///   ) {
+///     prop_assert_eq!(c, a.max(b).max(c));
+///   }
+/// }
+/// #
+/// # fn main() { max_is_last(); }
+/// ```
+pub fn ordered_triple<S: Strategy>(
+    elem: S,
+    relation: OrderRelation,
+) -> OrderedTriple<S>
+where
+    S::Value: Ord + Clone,
+{
+    OrderedTriple { elem, relation }
+}
+
+impl<S: Strategy> Strategy for OrderedTriple<S>
+where
+    S::Value: Ord + Clone,
+{
+    type Tree = OrderedTripleValueTree<S::Tree>;
+    type Value = (S::Value, S::Value, S::Value);
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        loop {
+            let elems = (0..self.relation.values())
+                .map(|_| self.elem.new_tree(runner))
+                .collect::<Result<Vec<_>, _>>()?;
+            let tree = OrderedTripleValueTree {
+                elems,
+                relation: self.relation,
+                shrink: 0,
+                prev_shrink: None,
+            };
+            if tree.holds() {
+                return Ok(tree);
+            }
+            runner.reject_local(
+                "ordered_triple() generated equal values for a strict relation",
+            )?;
+        }
+    }
+}
+
+/// `ValueTree` corresponding to `OrderedTriple`.
+#[derive(Clone, Debug)]
+pub struct OrderedTripleValueTree<T> {
+    elems: Vec<T>,
+    relation: OrderRelation,
+    shrink: usize,
+    prev_shrink: Option<usize>,
+}
+
+impl<T: ValueTree> OrderedTripleValueTree<T>
+where
+    T::Value: Ord + Clone,
+{
+    /// Whether the current values can build a triple, i.e., are distinct if
+    /// the relation needs them to be.
+    fn holds(&self) -> bool {
+        if !self.relation.strict() {
+            return true;
+        }
+        let values = self.elems.iter().map(|e| e.current()).collect::<Vec<_>>();
+        values[0] != values[1]
+            && values[1] != values[2]
+            && values[0] != values[2]
+    }
+
+    fn ensure_holds(&mut self, ix: usize) {
+        while !self.holds() {
+            if !self.elems[ix].complicate() {
+                panic!(
+                    "Unable to complicate ordered_triple() back into \
+                     distinct values"
+                );
+            }
+        }
+    }
+}
+
+impl<T: ValueTree> ValueTree for OrderedTripleValueTree<T>
+where
+    T::Value: Ord + Clone,
+{
+    type Value = (T::Value, T::Value, T::Value);
+
+    fn current(&self) -> Self::Value {
+        let mut values =
+            self.elems.iter().map(|e| e.current()).collect::<Vec<_>>();
+        match self.relation {
+            OrderRelation::AllEqual => {
+                (values[0].clone(), values[0].clone(), values.remove(0))
+            }
+            OrderRelation::FirstTwoEqual => {
+                let c = values.pop().unwrap();
+                (values[0].clone(), values.remove(0), c)
+            }
+            OrderRelation::Increasing
+            | OrderRelation::NonDecreasing
+            | OrderRelation::AllDistinct => {
+                if OrderRelation::AllDistinct != self.relation {
+                    values.sort();
+                }
+                let c = values.pop().unwrap();
+                let b = values.pop().unwrap();
+                (values.pop().unwrap(), b, c)
+            }
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        while self.shrink < self.elems.len() {
+            let ix = self.shrink;
+            if self.elems[ix].simplify() {
+                self.ensure_holds(ix);
+                self.prev_shrink = Some(ix);
+                return true;
+            }
+            self.shrink += 1;
+        }
+        false
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.prev_shrink {
+            Some(ix) if self.elems[ix].complicate() => {
+                self.ensure_holds(ix);
+                true
+            }
+            _ => {
+                self.prev_shrink = None;
+                false
+            }
+        }
+    }
+}
+
 /// A stand-in for an index into a slice or similar collection or conceptually
 /// similar things.
 ///
@@ -472,6 +978,19 @@ mod test {
         }
     }
 
+    #[test]
+    fn subsequence_honours_size_distribution() {
+        let mut runner = TestRunner::deterministic();
+        let input = subsequence(
+            (1..=10).collect::<Vec<_>>(),
+            SizeRange::with_distribution(0..=10, crate::strategy::Just(0)),
+        );
+
+        for _ in 0..64 {
+            assert!(input.new_tree(&mut runner).unwrap().current().is_empty());
+        }
+    }
+
     #[test]
     fn sample_vec() {
         // Just test that the types work out
@@ -515,6 +1034,133 @@ mod test {
         check_strategy_sanity(select(vec![0, 1, 2, 3, 4]), None);
     }
 
+    #[test]
+    fn select_no_repeat_never_repeats_while_shrinking() {
+        let mut runner = TestRunner::deterministic();
+        let input = select_no_repeat(vec!['a', 'b', 'c'], 1..32);
+        let mut counts = [0; 3];
+
+        for _ in 0..256 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            let mut first = true;
+            loop {
+                let value = case.current();
+                assert!(!value.is_empty() && value.len() < 32);
+                assert!(
+                    value.windows(2).all(|w| w[0] != w[1]),
+                    "Immediate repeat in {:?}",
+                    value
+                );
+                if first {
+                    for &c in &value {
+                        counts[(c as u8 - b'a') as usize] += 1;
+                    }
+                    first = false;
+                }
+                if !case.simplify() {
+                    break;
+                }
+            }
+            assert_eq!(vec!['a'], case.current());
+        }
+
+        for (ix, &count) in counts.iter().enumerate() {
+            assert!(count > 0, "Never selected value {}", ix);
+        }
+    }
+
+    #[test]
+    fn select_no_repeat_picks_successors_uniformly() {
+        let mut runner = TestRunner::deterministic();
+        let input = select_no_repeat(vec![0, 1, 2], 2..3);
+        // Counts of the second element, indexed by the first.
+        let mut counts = [[0u32; 3]; 3];
+
+        for _ in 0..3000 {
+            let value = input.new_tree(&mut runner).unwrap().current();
+            counts[value[0]][value[1]] += 1;
+        }
+
+        for (prev, successors) in counts.iter().enumerate() {
+            let total: u32 = successors.iter().sum();
+            for (next, &count) in successors.iter().enumerate() {
+                if next != prev {
+                    assert!(
+                        count * 10 > total * 4 && count * 10 < total * 6,
+                        "{} followed {} {} times out of {}",
+                        next,
+                        prev,
+                        count,
+                        total
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_select_no_repeat_sanity() {
+        check_strategy_sanity(select_no_repeat(vec![0, 1, 2, 3], 0..8), None);
+    }
+
+    #[test]
+    fn histogram_frequencies_match_weights() {
+        let mut histogram = std::collections::HashMap::new();
+        histogram.insert('a', 1u64);
+        histogram.insert('b', 3);
+        histogram.insert('c', 12);
+        histogram.insert('d', 0);
+        histogram.insert('e', 4);
+        let input = from_histogram(histogram.clone());
+
+        let mut runner = TestRunner::deterministic();
+        let mut counts = std::collections::HashMap::new();
+        for _ in 0..20_000 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            *counts.entry(tree.current()).or_insert(0u64) += 1;
+            while tree.simplify() {}
+            assert_eq!('c', tree.current());
+        }
+
+        assert!(!counts.contains_key(&'d'));
+        for (value, weight) in histogram {
+            // Each unit of weight is expected 1000 times.
+            let count = counts.get(&value).cloned().unwrap_or(0);
+            let expected = weight * 1000;
+            assert!(
+                count + 200 + expected / 10 > expected
+                    && count < expected + 200 + expected / 10,
+                "Generated {:?} {} times, expected about {}",
+                value,
+                count,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn histogram_shrinks_by_decreasing_weight() {
+        let input = from_histogram(vec![(3, 5u64), (1, 7), (2, 5), (3, 1)]);
+        assert_eq!(&[1, 3, 2][..], &**input.values.0);
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            let mut prev = tree.current();
+            while tree.simplify() {
+                let ix = |v| input.values.0.iter().position(|&x| x == v);
+                assert!(ix(tree.current()) < ix(prev));
+                prev = tree.current();
+            }
+            assert_eq!(1, tree.current());
+        }
+    }
+
+    #[test]
+    fn test_histogram_sanity() {
+        check_strategy_sanity(from_histogram(vec![(0, 1u64), (1, 2)]), None);
+    }
+
     #[test]
     fn subseq_empty_vec_works() {
         let mut runner = TestRunner::deterministic();
@@ -571,4 +1217,136 @@ mod test {
 
         assert_eq!(col, seen);
     }
+
+    #[test]
+    fn discriminants_are_tagged_accurately() {
+        const VALID: &[i64] = &[3, 1, 2, 10];
+        let input = valid_and_invalid_discriminants(VALID, 0..=10);
+        let mut runner = TestRunner::deterministic();
+        let mut counts = [0; 2];
+        let mut invalid_seen = BTreeSet::new();
+
+        for _ in 0..1024 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            let discriminant = tree.current();
+            assert_eq!(VALID.contains(&discriminant.value), discriminant.valid);
+            assert!(discriminant.valid || discriminant.value <= 10);
+            counts[discriminant.valid as usize] += 1;
+            if !discriminant.valid {
+                invalid_seen.insert(discriminant.value);
+            }
+
+            while tree.simplify() {
+                let discriminant = tree.current();
+                assert_eq!(
+                    VALID.contains(&discriminant.value),
+                    discriminant.valid
+                );
+            }
+            assert_eq!(
+                Discriminant {
+                    value: 3,
+                    valid: true
+                },
+                tree.current()
+            );
+        }
+
+        assert!(counts[0] > 400 && counts[1] > 400, "{:?}", counts);
+        assert_eq!(
+            vec![0, 4, 5, 6, 7, 8, 9],
+            invalid_seen.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn discriminants_cover_extreme_ranges() {
+        let input = valid_and_invalid_discriminants(&[0], i64::MIN..=i64::MAX);
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let discriminant = input.new_tree(&mut runner).unwrap().current();
+            assert_eq!(0 == discriminant.value, discriminant.valid);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "no invalid values")]
+    fn discriminants_need_invalid_values() {
+        let _ = valid_and_invalid_discriminants(&[1, 2, 3], 1..=3);
+    }
+
+    fn relation_holds<T: Ord>(
+        relation: OrderRelation,
+        (a, b, c): (T, T, T),
+    ) -> bool {
+        match relation {
+            OrderRelation::Increasing => a < b && b < c,
+            OrderRelation::NonDecreasing => a <= b && b <= c,
+            OrderRelation::FirstTwoEqual => a == b,
+            OrderRelation::AllEqual => a == b && b == c,
+            OrderRelation::AllDistinct => a != b && b != c && a != c,
+        }
+    }
+
+    #[test]
+    fn ordered_triples_satisfy_relation_while_shrinking() {
+        let mut runner = TestRunner::deterministic();
+        for &relation in &[
+            OrderRelation::Increasing,
+            OrderRelation::NonDecreasing,
+            OrderRelation::FirstTwoEqual,
+            OrderRelation::AllEqual,
+            OrderRelation::AllDistinct,
+        ] {
+            // A small range makes ties common.
+            let input = ordered_triple(0..6u32, relation);
+            for _ in 0..256 {
+                let mut tree = input.new_tree(&mut runner).unwrap();
+                loop {
+                    let triple = tree.current();
+                    assert!(
+                        relation_holds(relation, triple),
+                        "{:?}: {:?}",
+                        relation,
+                        triple
+                    );
+                    if !tree.simplify() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ordered_triples_shrink_to_smallest() {
+        let mut runner = TestRunner::deterministic();
+        for &(relation, smallest) in &[
+            (OrderRelation::Increasing, (0, 1, 2)),
+            (OrderRelation::NonDecreasing, (0, 0, 0)),
+            (OrderRelation::AllEqual, (0, 0, 0)),
+        ] {
+            let input = ordered_triple(0..1000u32, relation);
+            let result = runner.run(&input, |_| Err(TestCaseError::fail("")));
+            match result {
+                Err(TestError::Fail(_, triple, _, _)) => {
+                    assert_eq!(smallest, triple, "{:?}", relation)
+                }
+                e => panic!("Unexpected result: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_ordered_triple_sanity() {
+        check_strategy_sanity(
+            ordered_triple(0..100u32, OrderRelation::Increasing),
+            Some(CheckStrategySanityOptions {
+                // Undoing a simplification which makes two values equal can
+                // converge back to what `complicate()` would do.
+                strict_complicate_after_simplify: false,
+                ..CheckStrategySanityOptions::default()
+            }),
+        );
+    }
 }
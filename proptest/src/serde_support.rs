@@ -0,0 +1,170 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Helpers for testing serialization with `serde`.
+//!
+//! This module is only available with the `serde` feature.
+
+use crate::std_facade::{fmt, Arc, Vec};
+
+use serde::Serialize;
+
+use crate::strategy::*;
+use crate::test_runner::*;
+
+/// Strategy to create values paired with their encoding.
+///
+/// Created by the `arb_and_encoded()` function in the same module.
+#[must_use = "strategies do nothing unless used"]
+pub struct ArbAndEncoded<S, F> {
+    source: S,
+    encode: Arc<F>,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for ArbAndEncoded<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ArbAndEncoded")
+            .field("source", &self.source)
+            .field("encode", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for ArbAndEncoded<S, F> {
+    fn clone(&self) -> Self {
+        ArbAndEncoded {
+            source: self.source.clone(),
+            encode: Arc::clone(&self.encode),
+        }
+    }
+}
+
+/// Create a strategy to generate values from `strategy` together with the
+/// bytes `encode` serializes them to, e.g., with `serde_json::to_vec()`.
+///
+/// The bytes are recomputed from the value whenever it changes, so shrinking
+/// the value shrinks the bytes along with it, and a minimal failing value is
+/// reported alongside its own encoding.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::serde_support::arb_and_encoded;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn json_round_trips((value, bytes) in arb_and_encoded(
+///       prop::collection::vec(any::<(u32, bool)>(), 0..8),
+///       |v| serde_json::to_vec(v).unwrap(),
+///   )) {
+///     let decoded: Vec<(u32, bool)> =
+///         serde_json::from_slice(&bytes).unwrap();
+///     prop_assert_eq!(value, decoded);
+///   }
+/// }
+/// #
+/// # fn main() { json_round_trips(); }
+/// ```
+pub fn arb_and_encoded<S, F>(strategy: S, encode: F) -> ArbAndEncoded<S, F>
+where
+    S: Strategy,
+    S::Value: Serialize,
+    F: Fn(&S::Value) -> Vec<u8>,
+{
+    ArbAndEncoded {
+        source: strategy,
+        encode: Arc::new(encode),
+    }
+}
+
+impl<S: Strategy, F: Fn(&S::Value) -> Vec<u8>> Strategy for ArbAndEncoded<S, F>
+where
+    S::Value: Serialize,
+{
+    type Tree = ArbAndEncodedValueTree<S::Tree, F>;
+    type Value = (S::Value, Vec<u8>);
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        Ok(ArbAndEncodedValueTree {
+            source: self.source.new_tree(runner)?,
+            encode: Arc::clone(&self.encode),
+        })
+    }
+}
+
+/// `ValueTree` corresponding to `ArbAndEncoded`.
+pub struct ArbAndEncodedValueTree<T, F> {
+    source: T,
+    encode: Arc<F>,
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for ArbAndEncodedValueTree<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ArbAndEncodedValueTree")
+            .field("source", &self.source)
+            .field("encode", &"<function>")
+            .finish()
+    }
+}
+
+impl<T: Clone, F> Clone for ArbAndEncodedValueTree<T, F> {
+    fn clone(&self) -> Self {
+        ArbAndEncodedValueTree {
+            source: self.source.clone(),
+            encode: Arc::clone(&self.encode),
+        }
+    }
+}
+
+impl<T: ValueTree, F: Fn(&T::Value) -> Vec<u8>> ValueTree
+    for ArbAndEncodedValueTree<T, F>
+where
+    T::Value: Serialize,
+{
+    type Value = (T::Value, Vec<u8>);
+
+    fn current(&self) -> (T::Value, Vec<u8>) {
+        let value = self.source.current();
+        let bytes = (self.encode)(&value);
+        (value, bytes)
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.source.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.source.complicate()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::arbitrary::any;
+
+    #[test]
+    fn minimal_value_has_minimal_bytes() {
+        let input =
+            arb_and_encoded(any::<u32>(), |v| serde_json::to_vec(v).unwrap());
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            loop {
+                let (value, bytes) = tree.current();
+                assert_eq!(format!("{}", value).into_bytes(), bytes);
+                if !tree.simplify() {
+                    break;
+                }
+            }
+            assert_eq!((0, b"0".to_vec()), tree.current());
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{fmt, Arc};
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// `Strategy` adaptor which pairs each value with a corrupted copy of it.
+///
+/// See `Strategy::prop_corrupt()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct Corrupt<S, F> {
+    pub(super) source: S,
+    pub(super) corrupt: Arc<F>,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for Corrupt<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Corrupt")
+            .field("source", &self.source)
+            .field("corrupt", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for Corrupt<S, F> {
+    fn clone(&self) -> Self {
+        Corrupt {
+            source: self.source.clone(),
+            corrupt: Arc::clone(&self.corrupt),
+        }
+    }
+}
+
+impl<S: Strategy, F: Fn(&S::Value, &mut TestRng) -> S::Value> Strategy
+    for Corrupt<S, F>
+where
+    S::Value: PartialEq,
+{
+    type Tree = CorruptValueTree<S::Tree, F>;
+    type Value = (S::Value, S::Value);
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        loop {
+            let tree = CorruptValueTree {
+                source: self.source.new_tree(runner)?,
+                corrupt: Arc::clone(&self.corrupt),
+                // A fresh copy is made for every corruption, which is much
+                // cheaper with XorShift than with ChaCha.
+                rng: runner.rng().gen_rng_with(RngAlgorithm::XorShift),
+            };
+            if tree.is_corrupted() {
+                return Ok(tree);
+            }
+            runner.reject_local("prop_corrupt() left the value unchanged")?;
+        }
+    }
+}
+
+/// `ValueTree` corresponding to `Corrupt`.
+pub struct CorruptValueTree<T, F> {
+    source: T,
+    corrupt: Arc<F>,
+    /// Each corruption starts from a copy of this, so that the same value is
+    /// always corrupted the same way.
+    rng: TestRng,
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for CorruptValueTree<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CorruptValueTree")
+            .field("source", &self.source)
+            .field("corrupt", &"<function>")
+            .field("rng", &self.rng)
+            .finish()
+    }
+}
+
+impl<T: Clone, F> Clone for CorruptValueTree<T, F> {
+    fn clone(&self) -> Self {
+        CorruptValueTree {
+            source: self.source.clone(),
+            corrupt: Arc::clone(&self.corrupt),
+            rng: self.rng.clone(),
+        }
+    }
+}
+
+impl<T: ValueTree, F: Fn(&T::Value, &mut TestRng) -> T::Value>
+    CorruptValueTree<T, F>
+where
+    T::Value: PartialEq,
+{
+    fn corrupted(&self, value: &T::Value) -> T::Value {
+        (self.corrupt)(value, &mut self.rng.clone())
+    }
+
+    fn is_corrupted(&self) -> bool {
+        let value = self.source.current();
+        self.corrupted(&value) != value
+    }
+
+    fn ensure_corrupted(&mut self) {
+        while !self.is_corrupted() {
+            if !self.source.complicate() {
+                panic!(
+                    "Unable to complicate corrupted strategy back into a \
+                     value which can be corrupted"
+                );
+            }
+        }
+    }
+}
+
+impl<T: ValueTree, F: Fn(&T::Value, &mut TestRng) -> T::Value> ValueTree
+    for CorruptValueTree<T, F>
+where
+    T::Value: PartialEq,
+{
+    type Value = (T::Value, T::Value);
+
+    fn current(&self) -> (T::Value, T::Value) {
+        let value = self.source.current();
+        let corrupted = self.corrupted(&value);
+        (value, corrupted)
+    }
+
+    fn simplify(&mut self) -> bool {
+        if self.source.simplify() {
+            self.ensure_corrupted();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        if self.source.complicate() {
+            self.ensure_corrupted();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::collection::vec;
+    use crate::std_facade::Vec;
+    use rand::Rng;
+
+    /// Replaces one element with a value above 100.
+    fn corrupt_element() -> impl Fn(&Vec<u32>, &mut TestRng) -> Vec<u32> {
+        |v, rng| {
+            let mut v = v.clone();
+            if !v.is_empty() {
+                let ix = rng.gen_range(0..v.len());
+                v[ix] = rng.gen_range(101..1000);
+            }
+            v
+        }
+    }
+
+    #[test]
+    fn corrupted_copy_differs_while_shrinking() {
+        let input = vec(0..=100u32, 0..10).prop_corrupt(corrupt_element());
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            loop {
+                let (valid, invalid) = case.current();
+                assert_eq!(valid.len(), invalid.len());
+                let changed = valid
+                    .iter()
+                    .zip(&invalid)
+                    .filter(|&(a, b)| a != b)
+                    .map(|(_, &b)| b)
+                    .collect::<Vec<_>>();
+                assert_eq!(1, changed.len(), "{:?} {:?}", valid, invalid);
+                assert!(changed[0] > 100);
+                // The same value is corrupted the same way every time.
+                assert_eq!((valid, invalid), case.current());
+                if !case.simplify() {
+                    break;
+                }
+            }
+            assert_eq!((vec![0], vec![case.current().1[0]]), case.current());
+        }
+    }
+
+    #[test]
+    fn test_sanity() {
+        check_strategy_sanity(
+            vec(0..=100u32, 1..10).prop_corrupt(corrupt_element()),
+            None,
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{fmt, Arc};
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// `Strategy` and `ValueTree` adaptor which logs every state transition of
+/// the value tree.
+///
+/// See `Strategy::prop_debug_tree()`.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct DebugTree<S> {
+    source: S,
+    label: Reason,
+}
+
+impl<S> DebugTree<S> {
+    pub(super) fn new(source: S, label: Reason) -> Self {
+        DebugTree { source, label }
+    }
+}
+
+impl<S: Strategy> Strategy for DebugTree<S> {
+    type Tree = DebugTreeValueTree<S::Tree>;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let source = self.source.new_tree(runner)?;
+        let telemetry = runner
+            .config()
+            .telemetry
+            .as_ref()
+            .map(|telemetry| Arc::from(telemetry.box_clone()));
+        let tree = DebugTreeValueTree {
+            source,
+            label: self.label.clone(),
+            telemetry,
+        };
+        tree.log(format_args!("new_tree() = {:?}", tree.source.current()));
+        Ok(tree)
+    }
+}
+
+/// `ValueTree` corresponding to `DebugTree`.
+#[derive(Clone, Debug)]
+pub struct DebugTreeValueTree<T> {
+    source: T,
+    label: Reason,
+    telemetry: Option<Arc<dyn Telemetry>>,
+}
+
+impl<T: ValueTree> DebugTreeValueTree<T> {
+    fn log(&self, event: fmt::Arguments) {
+        match self.telemetry {
+            Some(ref telemetry) => {
+                telemetry.record(&format!("[{}] {}", self.label, event))
+            }
+            #[cfg(feature = "std")]
+            None => eprintln!("[{}] {}", self.label, event),
+            #[cfg(not(feature = "std"))]
+            None => (),
+        }
+    }
+
+    fn log_step(&self, step: &str, changed: bool) {
+        self.log(format_args!(
+            "{}() -> {}; current = {:?}",
+            step,
+            changed,
+            self.source.current()
+        ));
+    }
+}
+
+impl<T: ValueTree> ValueTree for DebugTreeValueTree<T> {
+    type Value = T::Value;
+
+    fn current(&self) -> T::Value {
+        let current = self.source.current();
+        self.log(format_args!("current() = {:?}", current));
+        current
+    }
+
+    fn simplify(&mut self) -> bool {
+        let changed = self.source.simplify();
+        self.log_step("simplify", changed);
+        changed
+    }
+
+    fn complicate(&mut self) -> bool {
+        let changed = self.source.complicate();
+        self.log_step("complicate", changed);
+        changed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::std_facade::{Box, String, Vec};
+    use core::any::Any;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<String>>>);
+
+    impl Telemetry for Capture {
+        fn record(&self, message: &str) {
+            self.0.lock().unwrap().push(message.into());
+        }
+
+        fn box_clone(&self) -> Box<dyn Telemetry> {
+            Box::new(self.clone())
+        }
+
+        fn eq(&self, other: &dyn Telemetry) -> bool {
+            other
+                .as_any()
+                .downcast_ref::<Self>()
+                .map_or(false, |other| Arc::ptr_eq(&self.0, &other.0))
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    /// Starts binary search shrinking from a fixed value.
+    #[derive(Debug)]
+    struct Start(u32);
+
+    impl Strategy for Start {
+        type Tree = crate::num::u32::BinarySearch;
+        type Value = u32;
+
+        fn new_tree(&self, _: &mut TestRunner) -> NewTree<Self> {
+            Ok(crate::num::u32::BinarySearch::new(self.0))
+        }
+    }
+
+    #[test]
+    fn debug_tree_logs_integer_shrink() {
+        let capture = Capture::default();
+        let mut runner = TestRunner::new(Config {
+            telemetry: Some(Box::new(capture.clone())),
+            ..Config::default()
+        });
+
+        // Shrink towards the smallest value which is at least 3.
+        let mut tree = Start(5)
+            .prop_debug_tree("int")
+            .new_tree(&mut runner)
+            .unwrap();
+        let mut plain = Start(5).new_tree(&mut runner).unwrap();
+        loop {
+            let current = tree.current();
+            assert_eq!(plain.current(), current);
+            let changed = if current >= 3 {
+                let changed = tree.simplify();
+                assert_eq!(plain.simplify(), changed);
+                changed
+            } else {
+                let changed = tree.complicate();
+                assert_eq!(plain.complicate(), changed);
+                changed
+            };
+            if !changed {
+                break;
+            }
+        }
+        assert_eq!(3, tree.current());
+
+        assert_eq!(
+            vec![
+                "[int] new_tree() = 5",
+                "[int] current() = 5",
+                "[int] simplify() -> true; current = 2",
+                "[int] current() = 2",
+                "[int] complicate() -> true; current = 4",
+                "[int] current() = 4",
+                "[int] simplify() -> true; current = 3",
+                "[int] current() = 3",
+                "[int] simplify() -> false; current = 3",
+                "[int] current() = 3",
+            ],
+            *capture.0.lock().unwrap()
+        );
+    }
+}
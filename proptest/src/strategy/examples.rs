@@ -0,0 +1,207 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{Arc, Vec};
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// `Strategy` adaptor which has a test runner try a fixed list of examples
+/// before any random cases.
+///
+/// See `Strategy::prop_with_examples()`.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct WithExamples<S: Strategy>
+where
+    S::Value: Clone,
+{
+    pub(super) source: S,
+    pub(super) examples: Arc<Vec<S::Value>>,
+}
+
+impl<S: Strategy> Strategy for WithExamples<S>
+where
+    S::Value: Clone,
+{
+    type Tree = WithExamplesValueTree<S::Tree>;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let example = runner.take_example(&self.examples);
+        Ok(WithExamplesValueTree {
+            state: if example.is_some() {
+                State::Example
+            } else {
+                State::Source
+            },
+            example,
+            source: self.source.new_tree(runner)?,
+            tried: false,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum State {
+    /// The example is the current value.
+    Example,
+    /// The value of the source is tried in place of the example.
+    Candidate,
+    /// Generation and shrinking are delegated to the source.
+    Source,
+}
+
+/// `ValueTree` corresponding to `WithExamples`.
+#[derive(Clone, Debug)]
+pub struct WithExamplesValueTree<T: ValueTree>
+where
+    T::Value: Clone,
+{
+    example: Option<T::Value>,
+    source: T,
+    state: State,
+    /// Whether the value of the source has been tried as it was generated.
+    tried: bool,
+}
+
+impl<T: ValueTree> ValueTree for WithExamplesValueTree<T>
+where
+    T::Value: Clone,
+{
+    type Value = T::Value;
+
+    fn current(&self) -> T::Value {
+        match (self.state, &self.example) {
+            (State::Example, Some(example)) => example.clone(),
+            _ => self.source.current(),
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        match self.state {
+            State::Example => {
+                // There is no value tree for the example, so the candidates
+                // are the values of the source, down to its simplest one.
+                // The first is its value as generated, and the others are
+                // only reached once the ones before them pass.
+                if !self.tried || self.source.simplify() {
+                    self.tried = true;
+                    self.state = State::Candidate;
+                    true
+                } else {
+                    false
+                }
+            }
+            State::Candidate => {
+                // The candidate failed as well, so it replaces the example
+                // for good.
+                self.state = State::Source;
+                self.source.simplify()
+            }
+            State::Source => self.source.simplify(),
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.state {
+            State::Candidate => {
+                self.state = State::Example;
+                true
+            }
+            State::Example => false,
+            State::Source => self.source.complicate(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::std_facade::vec;
+    use std::cell::RefCell;
+
+    fn tested_values<S: Strategy<Value = u32>>(
+        input: &S,
+        test: impl Fn(u32) -> bool,
+    ) -> (Vec<u32>, Result<(), TestError<u32>>) {
+        let mut runner = TestRunner::deterministic();
+        let seen = RefCell::new(Vec::new());
+        let result = runner.run(input, |v| {
+            seen.borrow_mut().push(v);
+            prop_assert!(test(v));
+            Ok(())
+        });
+        (seen.into_inner(), result)
+    }
+
+    #[test]
+    fn examples_run_before_random_cases() {
+        let input = (0..1000u32).prop_with_examples(vec![7, 2000]);
+        let (seen, result) = tested_values(&input, |v| v < 1000);
+        assert_eq!(&[7, 2000], &seen[..2]);
+        // Every value the example shrinks to passes, so it is reported, but
+        // not persisted.
+        match result {
+            Err(TestError::Fail(_, v, seed, _)) => {
+                assert_eq!(2000, v);
+                assert!(seed.is_none());
+            }
+            e => panic!("Unexpected result: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn failing_example_shrinks_under_test_feedback() {
+        let input = (0..1000u32).prop_with_examples(vec![998]);
+        let (seen, result) = tested_values(&input, |v| 1 == v % 2);
+        match result {
+            Err(TestError::Fail(_, v, _, _)) => {
+                assert_eq!(0, v % 2);
+                assert!(v < 998);
+            }
+            e => panic!("Unexpected result: {:?}", e),
+        }
+        // The candidates get simpler until one fails, which the source
+        // shrinks from there. The runner tests the example again after each
+        // candidate which passes.
+        let shrunk = seen[1..]
+            .iter()
+            .cloned()
+            .filter(|&v| v != 998)
+            .collect::<Vec<_>>();
+        let first_failure = shrunk.iter().position(|v| 0 == v % 2).unwrap();
+        assert!(first_failure > 0, "{:?}", shrunk);
+        let candidates = &shrunk[..=first_failure];
+        assert!(candidates.windows(2).all(|w| w[1] < w[0]), "{:?}", shrunk);
+    }
+
+    #[test]
+    fn examples_leave_random_cases_alone() {
+        let plain = tested_values(&(0..1000u32), |_| true).0;
+        let input = (0..1000u32).prop_with_examples(vec![7, 2000]);
+        let (seen, _) = tested_values(&input, |_| true);
+        assert_eq!(vec![7, 2000], seen[..2].to_vec());
+        assert_eq!(plain, seen[2..].to_vec());
+    }
+
+    #[test]
+    fn examples_are_only_generated_by_the_runner() {
+        let input = (0..10u32).prop_with_examples(vec![100, 200]);
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..16 {
+            assert!(input.new_tree(&mut runner).unwrap().current() < 10);
+        }
+        // Every run tries the examples again.
+        for _ in 0..2 {
+            let (seen, _) = tested_values(&input, |_| true);
+            assert_eq!(&[100, 200], &seen[..2]);
+        }
+    }
+}
@@ -144,4 +144,23 @@ mod test {
             }),
         );
     }
+
+    #[test]
+    fn assume_inner_attributes_rejections_to_call_site() {
+        let line = line!() + 1;
+        let input = (0..256).prop_assume_inner("odd", |&v| 1 == v % 2);
+        let mut runner = TestRunner::deterministic();
+        runner
+            .run(&input, |v| {
+                assert!(1 == v % 2);
+                Ok(())
+            })
+            .unwrap();
+
+        let stats = format!("{}", runner);
+        let whence = format!("times at {}:{}:", file!(), line);
+        let detail = stats.lines().find(|l| l.contains(&whence));
+        assert!(detail.map_or(false, |l| l.ends_with(": odd")), "{}", stats);
+        assert!(!stats.contains("\tlocal rejects: 0\n"), "{}", stats);
+    }
 }
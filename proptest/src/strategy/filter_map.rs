@@ -52,6 +52,29 @@ impl<S: Clone, F> Clone for FilterMap<S, F> {
     }
 }
 
+/// Function deciding which source values a `FilterMapValueTree` accepts, and
+/// what each accepted value maps to.
+///
+/// This is implemented for every closure usable with `prop_filter_map()`. It
+/// exists so that other rejection-sampling adaptors, such as
+/// `prop_try_map()`, can share `FilterMapValueTree` while reporting their
+/// rejections in their own way.
+pub trait FilterMapFn<T> {
+    /// The output type of this function.
+    type Output;
+
+    /// Maps `value` to its output, or returns `None` if it is rejected.
+    fn filter_map(&self, value: T) -> Option<Self::Output>;
+}
+
+impl<T, O, F: Fn(T) -> Option<O>> FilterMapFn<T> for F {
+    type Output = O;
+
+    fn filter_map(&self, value: T) -> Option<O> {
+        self(value)
+    }
+}
+
 impl<S: Strategy, F: Fn(S::Value) -> Option<O>, O: fmt::Debug> Strategy
     for FilterMap<S, F>
 {
@@ -77,7 +100,7 @@ pub struct FilterMapValueTree<V, F, O> {
     fun: Arc<F>,
 }
 
-impl<V: Clone + ValueTree, F: Fn(V::Value) -> Option<O>, O> Clone
+impl<V: Clone + ValueTree, F: FilterMapFn<V::Value, Output = O>, O> Clone
     for FilterMapValueTree<V, F, O>
 {
     fn clone(&self) -> Self {
@@ -95,10 +118,10 @@ impl<V: fmt::Debug, F, O> fmt::Debug for FilterMapValueTree<V, F, O> {
     }
 }
 
-impl<V: ValueTree, F: Fn(V::Value) -> Option<O>, O>
+impl<V: ValueTree, F: FilterMapFn<V::Value, Output = O>, O>
     FilterMapValueTree<V, F, O>
 {
-    fn new(source: V, fun: &Arc<F>, current: O) -> Self {
+    pub(super) fn new(source: V, fun: &Arc<F>, current: O) -> Self {
         Self {
             source,
             current: Cell::new(Some(current)),
@@ -107,13 +130,14 @@ impl<V: ValueTree, F: Fn(V::Value) -> Option<O>, O>
     }
 
     fn fresh_current(&self) -> O {
-        (self.fun)(self.source.current())
+        self.fun
+            .filter_map(self.source.current())
             .expect("internal logic error; this is a bug!")
     }
 
     fn ensure_acceptable(&mut self) {
         loop {
-            if let Some(current) = (self.fun)(self.source.current()) {
+            if let Some(current) = self.fun.filter_map(self.source.current()) {
                 // Found an acceptable element!
                 self.current = Cell::new(Some(current));
                 break;
@@ -127,8 +151,8 @@ impl<V: ValueTree, F: Fn(V::Value) -> Option<O>, O>
     }
 }
 
-impl<V: ValueTree, F: Fn(V::Value) -> Option<O>, O: fmt::Debug> ValueTree
-    for FilterMapValueTree<V, F, O>
+impl<V: ValueTree, F: FilterMapFn<V::Value, Output = O>, O: fmt::Debug>
+    ValueTree for FilterMapValueTree<V, F, O>
 {
     type Value = O;
 
@@ -7,7 +7,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::std_facade::{fmt, Arc};
+use crate::std_facade::{fmt, Arc, Vec};
 use core::mem;
 
 use crate::strategy::fuse::Fuse;
@@ -257,12 +257,258 @@ impl<S: Strategy, R: Strategy, F: Fn(S::Value) -> R> Strategy
     }
 }
 
+/// Similar to `Flatten` over a `Map`, but remembers the derived `ValueTree`s
+/// for each input it has seen.
+///
+/// See `Strategy::prop_ind_flat_map_cached()` for more details.
+#[must_use = "strategies do nothing unless used"]
+pub struct FlattenCached<S, F> {
+    pub(super) source: S,
+    pub(super) fun: Arc<F>,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for FlattenCached<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FlattenCached")
+            .field("source", &self.source)
+            .field("fun", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for FlattenCached<S, F> {
+    fn clone(&self) -> Self {
+        FlattenCached {
+            source: self.source.clone(),
+            fun: Arc::clone(&self.fun),
+        }
+    }
+}
+
+impl<S: Strategy, R: Strategy, F: Fn(S::Value) -> R> Strategy
+    for FlattenCached<S, F>
+where
+    S::Value: Clone + PartialEq,
+{
+    type Tree = FlattenCachedValueTree<S::Tree, R, F>;
+    type Value = R::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let outer = self.source.new_tree(runner)?;
+        let current_key = outer.current();
+        let current = (self.fun)(current_key.clone()).new_tree(runner)?;
+        Ok(FlattenCachedValueTree {
+            outer: Fuse::new(outer),
+            fun: Arc::clone(&self.fun),
+            current_key,
+            current: Fuse::new(current),
+            final_complication: None,
+            cache: Vec::new(),
+            runner: runner.partial_clone(),
+            complicate_regen_remaining: 0,
+        })
+    }
+}
+
+/// The `ValueTree` produced by `FlattenCached`.
+pub struct FlattenCachedValueTree<S: ValueTree, R: Strategy, F> {
+    outer: Fuse<S>,
+    fun: Arc<F>,
+    // The outer value `current` was derived from.
+    current_key: S::Value,
+    current: Fuse<R::Tree>,
+    final_complication: Option<(S::Value, Fuse<R::Tree>)>,
+    // Derived trees which have been replaced while they were in a failing
+    // state, keyed by the outer value they were derived from. Holds at most
+    // `max_flat_map_regens` entries, evicting the oldest first.
+    cache: Vec<(S::Value, Fuse<R::Tree>)>,
+    runner: TestRunner,
+    complicate_regen_remaining: u32,
+}
+
+impl<S: ValueTree + Clone, R: Strategy, F> Clone
+    for FlattenCachedValueTree<S, R, F>
+where
+    S::Value: Clone,
+    R::Tree: Clone,
+{
+    fn clone(&self) -> Self {
+        FlattenCachedValueTree {
+            outer: self.outer.clone(),
+            fun: Arc::clone(&self.fun),
+            current_key: self.current_key.clone(),
+            current: self.current.clone(),
+            final_complication: self.final_complication.clone(),
+            cache: self.cache.clone(),
+            runner: self.runner.clone(),
+            complicate_regen_remaining: self.complicate_regen_remaining,
+        }
+    }
+}
+
+impl<S: ValueTree + fmt::Debug, R: Strategy, F> fmt::Debug
+    for FlattenCachedValueTree<S, R, F>
+where
+    R::Tree: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FlattenCachedValueTree")
+            .field("outer", &self.outer)
+            .field("fun", &"<function>")
+            .field("current_key", &self.current_key)
+            .field("current", &self.current)
+            .field("final_complication", &self.final_complication)
+            .field("cache", &self.cache)
+            .field(
+                "complicate_regen_remaining",
+                &self.complicate_regen_remaining,
+            )
+            .finish()
+    }
+}
+
+impl<S: ValueTree, R: Strategy, F: Fn(S::Value) -> R>
+    FlattenCachedValueTree<S, R, F>
+where
+    S::Value: Clone + PartialEq,
+{
+    /// Return the derived tree for `key`, and whether it had to be generated
+    /// afresh rather than being taken from the cache.
+    fn derived_tree(
+        &mut self,
+        key: &S::Value,
+    ) -> Option<(Fuse<R::Tree>, bool)> {
+        if self
+            .final_complication
+            .as_ref()
+            .map_or(false, |(k, _)| k == key)
+        {
+            return self.final_complication.take().map(|(_, t)| (t, false));
+        }
+
+        if let Some(ix) = self.cache.iter().position(|(k, _)| k == key) {
+            return Some((self.cache.remove(ix).1, false));
+        }
+
+        (self.fun)(key.clone())
+            .new_tree(&mut self.runner)
+            .ok()
+            .map(|tree| (Fuse::new(tree), true))
+    }
+
+    fn stash(&mut self, key: S::Value, tree: Fuse<R::Tree>) {
+        if let Some(ix) = self.cache.iter().position(|(k, _)| *k == key) {
+            self.cache[ix].1 = tree;
+            return;
+        }
+
+        let limit = self.runner.config().max_flat_map_regens as usize;
+        if limit == 0 {
+            return;
+        } else if self.cache.len() >= limit {
+            let _ = self.cache.remove(0);
+        }
+        self.cache.push((key, tree));
+    }
+
+    fn stash_final_complication(&mut self) {
+        if let Some((key, tree)) = self.final_complication.take() {
+            self.stash(key, tree);
+        }
+    }
+}
+
+impl<S: ValueTree, R: Strategy, F: Fn(S::Value) -> R> ValueTree
+    for FlattenCachedValueTree<S, R, F>
+where
+    S::Value: Clone + PartialEq,
+{
+    type Value = R::Value;
+
+    fn current(&self) -> R::Value {
+        self.current.current()
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.complicate_regen_remaining = 0;
+
+        if self.current.simplify() {
+            // As in `FlattenValueTree`, but the tree we won't complicate
+            // back to is still failing for its outer value, so keep it.
+            self.outer.disallow_complicate();
+            self.stash_final_complication();
+            true
+        } else if !self.outer.simplify() {
+            false
+        } else {
+            let key = self.outer.current();
+            let (tree, fresh) = match self.derived_tree(&key) {
+                Some(derived) => derived,
+                None => return false,
+            };
+
+            self.current.disallow_complicate();
+            self.stash_final_complication();
+            let prev_key = mem::replace(&mut self.current_key, key);
+            let prev = mem::replace(&mut self.current, tree);
+            self.final_complication = Some((prev_key, prev));
+            // A cached tree is already known to fail, so there is nothing
+            // to search for.
+            if fresh {
+                self.complicate_regen_remaining = self.runner.config().cases;
+            }
+            true
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        if self.complicate_regen_remaining > 0 {
+            if self.runner.flat_map_regen() {
+                self.complicate_regen_remaining -= 1;
+
+                let source = (self.fun)(self.current_key.clone());
+                if let Ok(v) = source.new_tree(&mut self.runner) {
+                    self.current = Fuse::new(v);
+                    return true;
+                }
+            } else {
+                self.complicate_regen_remaining = 0;
+            }
+        }
+
+        if self.current.complicate() {
+            return true;
+        } else if self.outer.complicate() {
+            let key = self.outer.current();
+            if let Some((tree, fresh)) = self.derived_tree(&key) {
+                if fresh {
+                    self.complicate_regen_remaining =
+                        self.runner.config().cases;
+                }
+                self.current_key = key;
+                self.current = tree;
+                return true;
+            }
+        }
+
+        if let Some((key, tree)) = self.final_complication.take() {
+            self.current_key = key;
+            self.current = tree;
+            true
+        } else {
+            false
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    use std::ops::Range;
     use std::u32;
 
+    use crate::collection::{vec, VecStrategy};
     use crate::strategy::just::Just;
     use crate::test_runner::Config;
 
@@ -293,7 +539,7 @@ mod test {
 
             match result {
                 Ok(_) => {}
-                Err(TestError::Fail(_, v)) => {
+                Err(TestError::Fail(_, v, _, _)) => {
                     failures += 1;
                     assert_eq!((10001, 10002), v);
                 }
@@ -356,4 +602,60 @@ mod test {
             None,
         );
     }
+
+    #[test]
+    fn test_flat_map_cached_sanity() {
+        check_strategy_sanity(
+            (0..65536).prop_ind_flat_map_cached(|a| (Just(a), (a - 5..a + 5))),
+            None,
+        );
+    }
+
+    #[test]
+    fn flat_map_cached_shrinks_tighter() {
+        // Fails whenever there are at least two rows and the first row
+        // starts with 500 or more, so the minimal input is
+        // `[[500], [0]]`. Removing a row always passes, so shrinking keeps
+        // restoring it; plain `prop_flat_map()` then has to redo the
+        // shrinking of all the rows from a fresh value.
+        fn count_minimal<S: Strategy<Value = Vec<Vec<u32>>>>(input: S) -> u32 {
+            let mut runner = TestRunner::new_with_rng(
+                Config {
+                    max_shrink_iters: 1000,
+                    ..Config::default()
+                },
+                TestRng::deterministic_rng(RngAlgorithm::default()),
+            );
+            let mut minimal = 0;
+            for _ in 0..200 {
+                let case = input.new_tree(&mut runner).unwrap();
+                let result = runner.run_one(case, |rows| {
+                    prop_assert!(rows.len() < 2 || rows[0][0] < 500);
+                    Ok(())
+                });
+                match result {
+                    Ok(_) => {}
+                    Err(TestError::Fail(_, v, _, _)) => {
+                        if v == vec![vec![500], vec![0]] {
+                            minimal += 1;
+                        }
+                    }
+                    result => panic!("Unexpected result: {:?}", result),
+                }
+            }
+            minimal
+        }
+
+        fn rows(lengths: Vec<usize>) -> Vec<VecStrategy<Range<u32>>> {
+            lengths
+                .into_iter()
+                .map(|len| vec(0..1000u32, len))
+                .collect()
+        }
+
+        let plain = count_minimal(vec(1..8usize, 1..6).prop_flat_map(rows));
+        let cached =
+            count_minimal(vec(1..8usize, 1..6).prop_ind_flat_map_cached(rows));
+        assert!(cached > plain, "cached: {}, plain: {}", cached, plain);
+    }
 }
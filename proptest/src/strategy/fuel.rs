@@ -0,0 +1,84 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::Arc;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// `Strategy` adaptor which generates at most a fixed number of values.
+///
+/// Clones share the same fuel.
+///
+/// See `Strategy::prop_fuel()`.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct Fuel<S> {
+    pub(super) source: S,
+    pub(super) used: Arc<AtomicUsize>,
+    pub(super) max_calls: usize,
+}
+
+impl<S> Fuel<S> {
+    /// Returns how many more values can be generated.
+    pub fn remaining(&self) -> usize {
+        self.max_calls - self.used.load(Ordering::Relaxed)
+    }
+}
+
+impl<S: Strategy> Strategy for Fuel<S> {
+    type Tree = S::Tree;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let max_calls = self.max_calls;
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                if used < max_calls {
+                    Some(used + 1)
+                } else {
+                    None
+                }
+            })
+            .map_err(|_| format!("Out of fuel after {} values", max_calls))?;
+        self.source.new_tree(runner)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn errors_after_max_calls() {
+        let input = (0..10u32).prop_fuel(3);
+        let mut runner = TestRunner::deterministic();
+        for remaining in (0..3).rev() {
+            assert!(input.new_tree(&mut runner).is_ok());
+            assert_eq!(remaining, input.remaining());
+        }
+        assert_eq!(
+            "Out of fuel after 3 values",
+            input.new_tree(&mut runner).unwrap_err().message()
+        );
+        assert!(input.new_tree(&mut runner).is_err());
+    }
+
+    #[test]
+    fn clones_share_fuel() {
+        let input = (0..10u32).prop_fuel(2);
+        let copy = input.clone();
+        let mut runner = TestRunner::deterministic();
+        assert!(input.new_tree(&mut runner).is_ok());
+        assert!(copy.new_tree(&mut runner).is_ok());
+        assert!(input.new_tree(&mut runner).is_err());
+        assert!(copy.new_tree(&mut runner).is_err());
+    }
+}
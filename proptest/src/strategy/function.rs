@@ -0,0 +1,261 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{fmt, Vec};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+
+use rand::Rng;
+
+use crate::collection::{self, VecStrategy, VecValueTree};
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// The most distinct outputs a function generated by `fun()` has.
+const MAX_OUTPUTS: usize = 16;
+
+/// A deterministic, total function from `I` to `O`, generated by `fun()`.
+///
+/// Each input is hashed together with a seed chosen when the function is
+/// generated to pick one of a fixed list of outputs, so the same input always
+/// maps to the same output.
+pub struct Fun<I, O> {
+    seed: u64,
+    outputs: Vec<O>,
+    input: PhantomData<fn(I)>,
+}
+
+impl<I: Hash, O: Clone> Fun<I, O> {
+    /// Apply the function to `input`.
+    pub fn call(&self, input: &I) -> O {
+        let mut hasher = Fnv1a(self.seed);
+        input.hash(&mut hasher);
+        let ix = hasher.finish() % self.outputs.len() as u64;
+        self.outputs[ix as usize].clone()
+    }
+
+    /// Convert this into a closure, e.g., to pass to the code under test.
+    pub fn into_fn(self) -> impl Fn(I) -> O + Clone {
+        move |input| self.call(&input)
+    }
+}
+
+impl<I, O: fmt::Debug> fmt::Debug for Fun<I, O> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let [ref constant] = self.outputs[..] {
+            f.debug_struct("Fun").field("constant", constant).finish()
+        } else {
+            f.debug_struct("Fun")
+                .field("seed", &self.seed)
+                .field("outputs", &self.outputs)
+                .finish()
+        }
+    }
+}
+
+impl<I, O: Clone> Clone for Fun<I, O> {
+    fn clone(&self) -> Self {
+        Fun {
+            seed: self.seed,
+            outputs: self.outputs.clone(),
+            input: PhantomData,
+        }
+    }
+}
+
+/// The 64-bit FNV-1a hash, started from a seed rather than the usual offset
+/// basis. Unlike `std`'s hashers, this is available without `std`.
+struct Fnv1a(u64);
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x100_0000_01b3);
+        }
+    }
+}
+
+/// Strategy to create functions whose outputs are drawn from another
+/// strategy.
+///
+/// Created by the `fun()` function in the same module.
+#[must_use = "strategies do nothing unless used"]
+pub struct FunStrategy<I, S: Strategy> {
+    outputs: VecStrategy<S>,
+    input: PhantomData<fn(I)>,
+}
+
+impl<I, S: Strategy + fmt::Debug> fmt::Debug for FunStrategy<I, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FunStrategy")
+            .field("outputs", &self.outputs)
+            .finish()
+    }
+}
+
+impl<I, S: Strategy + Clone> Clone for FunStrategy<I, S> {
+    fn clone(&self) -> Self {
+        FunStrategy {
+            outputs: self.outputs.clone(),
+            input: PhantomData,
+        }
+    }
+}
+
+/// Create a strategy to generate pure functions from `I` to values drawn
+/// from `output`, e.g., to test higher-order code.
+///
+/// Each function has between 1 and 16 distinct outputs, and maps every input
+/// to one of them by hashing it, so it is defined for every input and always
+/// returns the same output for the same input. Which inputs map to which
+/// output is determined by a seed drawn from the test runner's RNG, so the
+/// same runner seed generates the same function. See `Fun`.
+///
+/// Shrinking removes outputs, which moves towards a constant function, and
+/// shrinks the outputs themselves.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::strategy::fun;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn map_applies_function(f in fun::<u32, _>(0..100u32),
+///                           input in prop::collection::vec(any::<u32>(), 0..10)) {
+///     let mapped: Vec<u32> = input.iter().cloned().map(f.clone().into_fn()).collect();
+///     for (x, y) in input.iter().zip(mapped) {
+///       prop_assert_eq!(f.call(x), y);
+///     }
+///   }
+/// }
+/// #
+/// # fn main() { map_applies_function(); }
+/// ```
+pub fn fun<I: Hash, S: Strategy>(output: S) -> FunStrategy<I, S>
+where
+    S::Value: Clone,
+{
+    FunStrategy {
+        outputs: collection::vec(output, 1..=MAX_OUTPUTS),
+        input: PhantomData,
+    }
+}
+
+impl<I: Hash, S: Strategy> Strategy for FunStrategy<I, S>
+where
+    S::Value: Clone,
+{
+    type Tree = FunValueTree<I, S::Tree>;
+    type Value = Fun<I, S::Value>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let seed = runner.rng().gen();
+        Ok(FunValueTree {
+            seed,
+            outputs: self.outputs.new_tree(runner)?,
+            input: PhantomData,
+        })
+    }
+}
+
+/// `ValueTree` corresponding to `FunStrategy`.
+pub struct FunValueTree<I, T: ValueTree> {
+    seed: u64,
+    outputs: VecValueTree<T>,
+    input: PhantomData<fn(I)>,
+}
+
+impl<I, T: ValueTree + fmt::Debug> fmt::Debug for FunValueTree<I, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FunValueTree")
+            .field("seed", &self.seed)
+            .field("outputs", &self.outputs)
+            .finish()
+    }
+}
+
+impl<I, T: ValueTree + Clone> Clone for FunValueTree<I, T> {
+    fn clone(&self) -> Self {
+        FunValueTree {
+            seed: self.seed,
+            outputs: self.outputs.clone(),
+            input: PhantomData,
+        }
+    }
+}
+
+impl<I: Hash, T: ValueTree> ValueTree for FunValueTree<I, T>
+where
+    T::Value: Clone,
+{
+    type Value = Fun<I, T::Value>;
+
+    fn current(&self) -> Fun<I, T::Value> {
+        Fun {
+            seed: self.seed,
+            outputs: self.outputs.current(),
+            input: PhantomData,
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.outputs.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.outputs.complicate()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::std_facade::string::ToString;
+    use crate::std_facade::String;
+
+    #[test]
+    fn same_input_gives_same_output() {
+        let input = fun::<String, _>(0..1000u32);
+        let mut runner = TestRunner::deterministic();
+        let mut varied = false;
+        for _ in 0..256 {
+            let f = input.new_tree(&mut runner).unwrap().current();
+            let words = ["", "a", "b", "hello", "world"];
+            let first = words.iter().map(|w| f.call(&w.to_string()));
+            let first = first.collect::<Vec<_>>();
+            let g = f.clone().into_fn();
+            for (word, &output) in words.iter().zip(&first) {
+                assert_eq!(output, f.call(&word.to_string()));
+                assert_eq!(output, g(word.to_string()));
+            }
+            varied |= first.iter().any(|&output| output != first[0]);
+        }
+        assert!(varied, "Only generated constant functions");
+    }
+
+    #[test]
+    fn shrinks_to_constant() {
+        let input = fun::<u32, _>(0..1000u32);
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            while tree.simplify() {}
+            let f = tree.current();
+            assert!((0..100).all(|x| 0 == f.call(&x)));
+            assert_eq!("Fun { constant: 0 }", format!("{:?}", f));
+        }
+    }
+}
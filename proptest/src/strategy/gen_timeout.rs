@@ -0,0 +1,87 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::time::{Duration, Instant};
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// `Strategy` adaptor which rejects values which take too long to generate.
+///
+/// See `Strategy::prop_gen_timeout()`.
+#[derive(Clone, Copy, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct GenTimeout<S> {
+    pub(super) source: S,
+    pub(super) timeout: Duration,
+}
+
+impl<S: Strategy> Strategy for GenTimeout<S> {
+    type Tree = S::Tree;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        loop {
+            let start = Instant::now();
+            let tree = self.source.new_tree(runner)?;
+            if start.elapsed() <= self.timeout {
+                return Ok(tree);
+            }
+            runner.reject_local(format!(
+                "value generation exceeded timeout of {:?}",
+                self.timeout
+            ))?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strategy::Just;
+    use std::cell::Cell;
+    use std::thread;
+
+    /// Generates its count of calls, sleeping on every other call.
+    #[derive(Debug)]
+    struct SometimesSlow(Cell<u32>);
+
+    impl Strategy for SometimesSlow {
+        type Tree = Just<u32>;
+        type Value = u32;
+
+        fn new_tree(&self, _: &mut TestRunner) -> NewTree<Self> {
+            let calls = self.0.get() + 1;
+            self.0.set(calls);
+            if 0 == calls % 2 {
+                thread::sleep(Duration::from_millis(50));
+            }
+            Ok(Just(calls))
+        }
+    }
+
+    #[test]
+    fn slow_values_are_rejected_locally() {
+        let input = (
+            SometimesSlow(Cell::new(0))
+                .prop_gen_timeout(Duration::from_millis(20)),
+            (0..10u32).prop_gen_timeout(Duration::from_secs(60)),
+        );
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..4 {
+            let (calls, _) = input.new_tree(&mut runner).unwrap().current();
+            assert_eq!(1, calls % 2);
+        }
+
+        assert!(runner.stats().local_rejects >= 3);
+        let detail = format!("{}", runner);
+        assert!(detail.contains("exceeded timeout of 20ms"), "{}", detail);
+        assert!(!detail.contains("exceeded timeout of 60s"), "{}", detail);
+    }
+}
@@ -0,0 +1,323 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{fmt, Arc};
+use core::mem;
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// How many values `SameGroup` draws looking for one with the same key as
+/// the first before pairing the first with a copy of itself.
+const SAME_GROUP_ATTEMPTS: u32 = 64;
+
+/// `Strategy` adaptor which groups values by a key.
+///
+/// This generates the same values as its source; use `prop_same_group()` to
+/// generate pairs of values in the same group.
+///
+/// See `Strategy::prop_group_by()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct GroupBy<S, F> {
+    pub(super) source: S,
+    pub(super) key: Arc<F>,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for GroupBy<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GroupBy")
+            .field("source", &self.source)
+            .field("key", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for GroupBy<S, F> {
+    fn clone(&self) -> Self {
+        GroupBy {
+            source: self.source.clone(),
+            key: Arc::clone(&self.key),
+        }
+    }
+}
+
+impl<S: Strategy, K: PartialEq, F: Fn(&S::Value) -> K> GroupBy<S, F> {
+    /// Returns a strategy which generates pairs of values from the source
+    /// with the same key, e.g., to test that a cache or memoized function
+    /// treats values in the same group alike.
+    ///
+    /// The second value is drawn from the source until one has the same key
+    /// as the first, so the values usually differ if a reasonable fraction of
+    /// values share each key. If no such value turns up after a number of
+    /// attempts, the first value is paired with a copy of itself.
+    ///
+    /// Shrinking first simplifies the second value while keeping its key,
+    /// then replaces it with a copy of the first value, which is simplified
+    /// in turn.
+    pub fn prop_same_group(self) -> SameGroup<S, F> {
+        SameGroup {
+            source: self.source,
+            key: self.key,
+        }
+    }
+}
+
+impl<S: Strategy, F> Strategy for GroupBy<S, F> {
+    type Tree = S::Tree;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.source.new_tree(runner)
+    }
+}
+
+/// `Strategy` which generates pairs of values with the same key.
+///
+/// See `GroupBy::prop_same_group()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct SameGroup<S, F> {
+    source: S,
+    key: Arc<F>,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for SameGroup<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SameGroup")
+            .field("source", &self.source)
+            .field("key", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for SameGroup<S, F> {
+    fn clone(&self) -> Self {
+        SameGroup {
+            source: self.source.clone(),
+            key: Arc::clone(&self.key),
+        }
+    }
+}
+
+impl<S: Strategy, K: PartialEq, F: Fn(&S::Value) -> K> Strategy
+    for SameGroup<S, F>
+{
+    type Tree = SameGroupValueTree<S::Tree, F>;
+    type Value = (S::Value, S::Value);
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let (source, key) = (&self.source, &self.key);
+        let start = runner.rng().clone();
+        let first = source.new_tree(runner)?;
+        let first_key = key(&first.current());
+
+        let mut second = None;
+        for _ in 0..SAME_GROUP_ATTEMPTS {
+            let candidate = source.new_tree(runner)?;
+            if key(&candidate.current()) == first_key {
+                second = Some(candidate);
+                break;
+            }
+        }
+        let second = match second {
+            Some(second) => second,
+            None => {
+                // Generate the first value again from the same RNG state.
+                let end = mem::replace(runner.rng(), start);
+                let copy = source.new_tree(runner);
+                *runner.rng() = end;
+                copy?
+            }
+        };
+
+        Ok(SameGroupValueTree {
+            first,
+            second,
+            key: Arc::clone(key),
+            shrink: SameGroupShrink::Second,
+            prev_shrink: None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SameGroupShrink {
+    /// Simplify the second value, keeping its key.
+    Second,
+    /// The second value is replaced by the first; simplify that.
+    First,
+    /// Replacing the second value made the test pass, so leave both alone.
+    Done,
+}
+
+/// `ValueTree` corresponding to `SameGroup`.
+pub struct SameGroupValueTree<T, F> {
+    first: T,
+    second: T,
+    key: Arc<F>,
+    shrink: SameGroupShrink,
+    prev_shrink: Option<SameGroupShrink>,
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for SameGroupValueTree<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SameGroupValueTree")
+            .field("first", &self.first)
+            .field("second", &self.second)
+            .field("key", &"<function>")
+            .field("shrink", &self.shrink)
+            .field("prev_shrink", &self.prev_shrink)
+            .finish()
+    }
+}
+
+impl<T: Clone, F> Clone for SameGroupValueTree<T, F> {
+    fn clone(&self) -> Self {
+        SameGroupValueTree {
+            first: self.first.clone(),
+            second: self.second.clone(),
+            key: Arc::clone(&self.key),
+            shrink: self.shrink,
+            prev_shrink: self.prev_shrink,
+        }
+    }
+}
+
+impl<T: ValueTree, K: PartialEq, F: Fn(&T::Value) -> K>
+    SameGroupValueTree<T, F>
+{
+    fn ensure_same_key(&mut self) {
+        let key = (self.key)(&self.first.current());
+        while (self.key)(&self.second.current()) != key {
+            if !self.second.complicate() {
+                panic!(
+                    "Unable to complicate second value back into the \
+                     same group"
+                );
+            }
+        }
+    }
+}
+
+impl<T: ValueTree, K: PartialEq, F: Fn(&T::Value) -> K> ValueTree
+    for SameGroupValueTree<T, F>
+{
+    type Value = (T::Value, T::Value);
+
+    fn current(&self) -> (T::Value, T::Value) {
+        match self.shrink {
+            SameGroupShrink::First => {
+                (self.first.current(), self.first.current())
+            }
+            _ => (self.first.current(), self.second.current()),
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        match self.shrink {
+            SameGroupShrink::Second => {
+                if self.second.simplify() {
+                    self.ensure_same_key();
+                } else {
+                    self.shrink = SameGroupShrink::First;
+                }
+                self.prev_shrink = Some(SameGroupShrink::Second);
+                true
+            }
+            SameGroupShrink::First => {
+                if self.first.simplify() {
+                    self.prev_shrink = Some(SameGroupShrink::First);
+                    true
+                } else {
+                    false
+                }
+            }
+            SameGroupShrink::Done => false,
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.prev_shrink {
+            None => false,
+            Some(SameGroupShrink::Second)
+                if self.shrink == SameGroupShrink::First =>
+            {
+                // Undo replacing the second value. It can't be simplified
+                // any further, and the first value can't be simplified
+                // without changing its key.
+                self.shrink = SameGroupShrink::Done;
+                self.prev_shrink = None;
+                true
+            }
+            Some(SameGroupShrink::Second) => {
+                if self.second.complicate() {
+                    self.ensure_same_key();
+                    true
+                } else {
+                    self.prev_shrink = None;
+                    false
+                }
+            }
+            Some(_) => {
+                if self.first.complicate() {
+                    true
+                } else {
+                    self.prev_shrink = None;
+                    false
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn same_group_pairs_share_key() {
+        let input = (0..1000u32).prop_group_by(|&v| v % 10).prop_same_group();
+        let mut runner = TestRunner::deterministic();
+        let mut differed = false;
+        for _ in 0..256 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            let (a, b) = case.current();
+            differed |= a != b;
+            assert_eq!(a % 10, b % 10);
+            while case.simplify() {
+                let (a, b) = case.current();
+                assert_eq!(a % 10, b % 10);
+            }
+            assert_eq!((0, 0), case.current());
+        }
+        assert!(differed, "Only generated identical pairs");
+    }
+
+    #[test]
+    fn same_group_falls_back_to_copy() {
+        let input = (0..u64::MAX).prop_group_by(|&v| v).prop_same_group();
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..16 {
+            let (a, b) = input.new_tree(&mut runner).unwrap().current();
+            assert_eq!(a, b);
+        }
+    }
+
+    #[test]
+    fn same_group_sanity() {
+        check_strategy_sanity(
+            (0..1000u32).prop_group_by(|&v| v % 10).prop_same_group(),
+            Some(CheckStrategySanityOptions {
+                // As with `prop_filter()`, keeping the key can complicate
+                // the second value back to where it was.
+                strict_complicate_after_simplify: false,
+                ..CheckStrategySanityOptions::default()
+            }),
+        );
+    }
+}
@@ -7,10 +7,11 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::std_facade::Arc;
+use crate::std_facade::{Arc, String, Vec};
 use core::fmt;
 use core::marker::PhantomData;
 
+use crate::collection::SizeRange;
 use crate::strategy::traits::*;
 use crate::test_runner::*;
 
@@ -147,6 +148,133 @@ where
     }
 }
 
+//==============================================================================
+// MapUnique
+//==============================================================================
+
+/// The most source shrinking steps `MapUniqueValueTree` takes in one call
+/// while looking for a different mapped value.
+const MAX_MAP_UNIQUE_SKIPS: u32 = 64;
+
+/// `Strategy` map adaptor which skips shrinking steps that do not change the
+/// mapped value.
+///
+/// See `Strategy::prop_map_unique()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct MapUnique<S, F> {
+    pub(super) source: S,
+    pub(super) fun: Arc<F>,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for MapUnique<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MapUnique")
+            .field("source", &self.source)
+            .field("fun", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for MapUnique<S, F> {
+    fn clone(&self) -> Self {
+        MapUnique {
+            source: self.source.clone(),
+            fun: Arc::clone(&self.fun),
+        }
+    }
+}
+
+impl<S: Strategy, O: fmt::Debug, F: Fn(S::Value) -> O> Strategy
+    for MapUnique<S, F>
+{
+    type Tree = MapUniqueValueTree<S::Tree, F>;
+    type Value = O;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let source = self.source.new_tree(runner)?;
+        let reported = format!("{:?}", (self.fun)(source.current()));
+        Ok(MapUniqueValueTree {
+            source,
+            fun: Arc::clone(&self.fun),
+            reported,
+        })
+    }
+}
+
+/// `ValueTree` corresponding to `MapUnique`.
+///
+/// See `Strategy::prop_map_unique()`.
+pub struct MapUniqueValueTree<S, F> {
+    source: S,
+    fun: Arc<F>,
+    /// The `Debug` output of the current value.
+    reported: String,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for MapUniqueValueTree<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MapUniqueValueTree")
+            .field("source", &self.source)
+            .field("fun", &"<function>")
+            .field("reported", &self.reported)
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for MapUniqueValueTree<S, F> {
+    fn clone(&self) -> Self {
+        MapUniqueValueTree {
+            source: self.source.clone(),
+            fun: Arc::clone(&self.fun),
+            reported: self.reported.clone(),
+        }
+    }
+}
+
+impl<S: ValueTree, O: fmt::Debug, F: Fn(S::Value) -> O>
+    MapUniqueValueTree<S, F>
+{
+    /// Repeat `step` on the source until the mapped value changes, giving up
+    /// after `MAX_MAP_UNIQUE_SKIPS` steps.
+    ///
+    /// Skipping a value identical to the current one is what the shrinker
+    /// would do anyway: the test gives the same result for it, so the
+    /// shrinker would take the same step again.
+    fn step_until_changed(&mut self, step: impl Fn(&mut S) -> bool) -> bool {
+        for _ in 0..MAX_MAP_UNIQUE_SKIPS {
+            if !step(&mut self.source) {
+                return false;
+            }
+
+            let reported = format!("{:?}", (self.fun)(self.source.current()));
+            if reported != self.reported {
+                self.reported = reported;
+                return true;
+            }
+        }
+
+        true
+    }
+}
+
+impl<S: ValueTree, O: fmt::Debug, F: Fn(S::Value) -> O> ValueTree
+    for MapUniqueValueTree<S, F>
+{
+    type Value = O;
+
+    fn current(&self) -> O {
+        (self.fun)(self.source.current())
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.step_until_changed(S::simplify)
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.step_until_changed(S::complicate)
+    }
+}
+
 //==============================================================================
 // Perturb
 //==============================================================================
@@ -242,6 +370,437 @@ impl<S: ValueTree, O: fmt::Debug, F: Fn(S::Value, TestRng) -> O> ValueTree
     }
 }
 
+//==============================================================================
+// WithMutation
+//==============================================================================
+
+/// `Strategy` adaptor pairing each value with a mutation of it.
+///
+/// See `Strategy::prop_with_mutation()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct WithMutation<S, F> {
+    pub(super) source: S,
+    pub(super) fun: Arc<F>,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for WithMutation<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WithMutation")
+            .field("source", &self.source)
+            .field("fun", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for WithMutation<S, F> {
+    fn clone(&self) -> Self {
+        WithMutation {
+            source: self.source.clone(),
+            fun: Arc::clone(&self.fun),
+        }
+    }
+}
+
+impl<S: Strategy, F: Fn(&S::Value, &mut TestRng) -> S::Value> Strategy
+    for WithMutation<S, F>
+{
+    type Tree = WithMutationValueTree<S::Tree, F>;
+    type Value = (S::Value, S::Value);
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let rng = runner.new_rng();
+
+        self.source
+            .new_tree(runner)
+            .map(|source| WithMutationValueTree {
+                source,
+                rng,
+                fun: Arc::clone(&self.fun),
+            })
+    }
+}
+
+/// `ValueTree` adaptor pairing each value with a mutation of it.
+///
+/// See `Strategy::prop_with_mutation()`.
+pub struct WithMutationValueTree<S, F> {
+    source: S,
+    fun: Arc<F>,
+    rng: TestRng,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for WithMutationValueTree<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WithMutationValueTree")
+            .field("source", &self.source)
+            .field("fun", &"<function>")
+            .field("rng", &self.rng)
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for WithMutationValueTree<S, F> {
+    fn clone(&self) -> Self {
+        WithMutationValueTree {
+            source: self.source.clone(),
+            fun: Arc::clone(&self.fun),
+            rng: self.rng.clone(),
+        }
+    }
+}
+
+impl<S: ValueTree, F: Fn(&S::Value, &mut TestRng) -> S::Value> ValueTree
+    for WithMutationValueTree<S, F>
+{
+    type Value = (S::Value, S::Value);
+
+    fn current(&self) -> (S::Value, S::Value) {
+        let value = self.source.current();
+        let mutated = (self.fun)(&value, &mut self.rng.clone());
+        (value, mutated)
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.source.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.source.complicate()
+    }
+}
+
+//==============================================================================
+// WithOracle
+//==============================================================================
+
+/// `Strategy` and `ValueTree` adaptor pairing each value with the output of
+/// a reference implementation for it.
+///
+/// See `Strategy::prop_with_oracle()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct WithOracle<S, F> {
+    pub(super) source: S,
+    pub(super) oracle: Arc<F>,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for WithOracle<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WithOracle")
+            .field("source", &self.source)
+            .field("oracle", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for WithOracle<S, F> {
+    fn clone(&self) -> Self {
+        WithOracle {
+            source: self.source.clone(),
+            oracle: Arc::clone(&self.oracle),
+        }
+    }
+}
+
+impl<S: Strategy, O: fmt::Debug, F: Fn(&S::Value) -> O> Strategy
+    for WithOracle<S, F>
+{
+    type Tree = WithOracle<S::Tree, F>;
+    type Value = (S::Value, O);
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.source.new_tree(runner).map(|source| WithOracle {
+            source,
+            oracle: Arc::clone(&self.oracle),
+        })
+    }
+}
+
+impl<S: ValueTree, O: fmt::Debug, F: Fn(&S::Value) -> O> ValueTree
+    for WithOracle<S, F>
+{
+    type Value = (S::Value, O);
+
+    fn current(&self) -> (S::Value, O) {
+        let value = self.source.current();
+        let expected = (self.oracle)(&value);
+        (value, expected)
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.source.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.source.complicate()
+    }
+}
+
+//==============================================================================
+// Constrain
+//==============================================================================
+
+/// `Strategy` and `ValueTree` adaptor which repairs every value to satisfy an
+/// invariant.
+///
+/// See `Strategy::prop_constrain()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct Constrain<S, F> {
+    pub(super) source: S,
+    pub(super) fix: Arc<F>,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for Constrain<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Constrain")
+            .field("source", &self.source)
+            .field("fix", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for Constrain<S, F> {
+    fn clone(&self) -> Self {
+        Constrain {
+            source: self.source.clone(),
+            fix: Arc::clone(&self.fix),
+        }
+    }
+}
+
+impl<S: Strategy, F: Fn(&S::Value) -> S::Value> Strategy for Constrain<S, F> {
+    type Tree = Constrain<S::Tree, F>;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.source.new_tree(runner).map(|v| Constrain {
+            source: v,
+            fix: Arc::clone(&self.fix),
+        })
+    }
+}
+
+impl<S: ValueTree, F: Fn(&S::Value) -> S::Value> ValueTree for Constrain<S, F> {
+    type Value = S::Value;
+
+    fn current(&self) -> S::Value {
+        (self.fix)(&self.source.current())
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.source.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.source.complicate()
+    }
+}
+
+//==============================================================================
+// Iterate
+//==============================================================================
+
+/// `Strategy` adaptor which generates a sequence of states by repeatedly
+/// applying a transition function to an initial state.
+///
+/// See `Strategy::prop_iterate()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct Iterate<S, F> {
+    pub(super) source: S,
+    pub(super) step: Arc<F>,
+    pub(super) len: SizeRange,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for Iterate<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Iterate")
+            .field("source", &self.source)
+            .field("step", &"<function>")
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for Iterate<S, F> {
+    fn clone(&self) -> Self {
+        Iterate {
+            source: self.source.clone(),
+            step: Arc::clone(&self.step),
+            len: self.len.clone(),
+        }
+    }
+}
+
+impl<S: Strategy, F: Fn(&S::Value, &mut TestRng) -> S::Value> Strategy
+    for Iterate<S, F>
+{
+    type Tree = IterateValueTree<S::Tree, F>;
+    type Value = Vec<S::Value>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let len = self.len.sample(runner)?;
+        let rng = runner.new_rng();
+
+        self.source.new_tree(runner).map(|source| IterateValueTree {
+            source,
+            step: Arc::clone(&self.step),
+            rng,
+            len,
+            min_len: self.len.start(),
+            shrink_len: true,
+            prev_shrink: None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum IterateShrink {
+    Len,
+    Source,
+}
+
+/// `ValueTree` corresponding to `Iterate`.
+///
+/// See `Strategy::prop_iterate()`.
+pub struct IterateValueTree<S, F> {
+    source: S,
+    step: Arc<F>,
+    rng: TestRng,
+    len: usize,
+    min_len: usize,
+    shrink_len: bool,
+    prev_shrink: Option<IterateShrink>,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for IterateValueTree<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IterateValueTree")
+            .field("source", &self.source)
+            .field("step", &"<function>")
+            .field("rng", &self.rng)
+            .field("len", &self.len)
+            .field("min_len", &self.min_len)
+            .field("shrink_len", &self.shrink_len)
+            .field("prev_shrink", &self.prev_shrink)
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for IterateValueTree<S, F> {
+    fn clone(&self) -> Self {
+        IterateValueTree {
+            source: self.source.clone(),
+            step: Arc::clone(&self.step),
+            rng: self.rng.clone(),
+            len: self.len,
+            min_len: self.min_len,
+            shrink_len: self.shrink_len,
+            prev_shrink: self.prev_shrink,
+        }
+    }
+}
+
+impl<S: ValueTree, F: Fn(&S::Value, &mut TestRng) -> S::Value> ValueTree
+    for IterateValueTree<S, F>
+{
+    type Value = Vec<S::Value>;
+
+    fn current(&self) -> Vec<S::Value> {
+        let mut states = Vec::with_capacity(self.len);
+        if 0 == self.len {
+            return states;
+        }
+
+        // Replaying the same RNG means each shrink of the initial state is
+        // followed by the same sequence of random transitions.
+        let mut rng = self.rng.clone();
+        states.push(self.source.current());
+        while states.len() < self.len {
+            let next = (self.step)(&states[states.len() - 1], &mut rng);
+            states.push(next);
+        }
+        states
+    }
+
+    fn simplify(&mut self) -> bool {
+        // Drop states from the end one at a time, then shrink the initial
+        // state.
+        if self.shrink_len {
+            if self.len > self.min_len {
+                self.len -= 1;
+                self.prev_shrink = Some(IterateShrink::Len);
+                return true;
+            }
+            self.shrink_len = false;
+        }
+
+        if self.source.simplify() {
+            self.prev_shrink = Some(IterateShrink::Source);
+            true
+        } else {
+            self.prev_shrink = None;
+            false
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.prev_shrink {
+            None => false,
+            Some(IterateShrink::Len) => {
+                // The last state was needed; keep it and move on to the
+                // initial state.
+                self.len += 1;
+                self.shrink_len = false;
+                self.prev_shrink = None;
+                true
+            }
+            Some(IterateShrink::Source) => {
+                if self.source.complicate() {
+                    true
+                } else {
+                    self.prev_shrink = None;
+                    false
+                }
+            }
+        }
+    }
+}
+
+//==============================================================================
+// Duplicate
+//==============================================================================
+
+/// `Strategy` and `ValueTree` adaptor producing each value twice.
+///
+/// See `Strategy::prop_duplicate()`.
+#[derive(Clone, Copy, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct Duplicate<S>(pub(super) S);
+
+impl<S: Strategy> Strategy for Duplicate<S> {
+    type Tree = Duplicate<S::Tree>;
+    type Value = (S::Value, S::Value);
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.0.new_tree(runner).map(Duplicate)
+    }
+}
+
+impl<S: ValueTree> ValueTree for Duplicate<S> {
+    type Value = (S::Value, S::Value);
+
+    fn current(&self) -> (S::Value, S::Value) {
+        (self.0.current(), self.0.current())
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.0.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.0.complicate()
+    }
+}
+
 //==============================================================================
 // Tests
 //==============================================================================
@@ -275,6 +834,50 @@ mod test {
             .unwrap();
     }
 
+    #[test]
+    fn map_unique_shrinks_lossy_map_in_fewer_runs() {
+        fn shrink_runs<S: Strategy<Value = u32>>(input: S) -> (u32, u32) {
+            let config = Config {
+                failure_persistence: None,
+                ..Config::default()
+            };
+            let algorithm = config.rng_algorithm;
+            let mut runner = TestRunner::new_with_rng(
+                config,
+                TestRng::deterministic_rng(algorithm),
+            );
+            let runs = core::cell::Cell::new(0);
+            let result = runner.run(&input, |v| {
+                runs.set(runs.get() + 1);
+                prop_assert!(v < 500);
+                Ok(())
+            });
+            match result {
+                Err(TestError::Fail(_, v, _, _)) => (v, runs.get()),
+                result => panic!("Unexpected result: {:?}", result),
+            }
+        }
+
+        let source = 0..1_000_000u32;
+        let (plain, plain_runs) =
+            shrink_runs(source.clone().prop_map(|n| n / 1000));
+        let (unique, unique_runs) =
+            shrink_runs(source.prop_map_unique(|n| n / 1000));
+        assert_eq!(500, plain);
+        assert_eq!(500, unique);
+        assert!(
+            unique_runs < plain_runs,
+            "{} runs with prop_map_unique, {} with prop_map",
+            unique_runs,
+            plain_runs
+        );
+    }
+
+    #[test]
+    fn map_unique_sanity() {
+        check_strategy_sanity((0..1000u32).prop_map_unique(|n| n / 10), None);
+    }
+
     #[test]
     fn perturb_uses_same_rng_every_time() {
         let mut runner = TestRunner::default();
@@ -298,4 +901,178 @@ mod test {
 
         assert_eq!(64, seen.len());
     }
+
+    #[test]
+    fn with_mutation_rederives_mutation_while_shrinking() {
+        use crate::std_facade::Vec;
+        use rand::Rng;
+
+        let input = crate::collection::vec(0u8..10, 1..20).prop_with_mutation(
+            |v, rng| {
+                let mut mutated = v.clone();
+                let ix = rng.gen_range(0..v.len());
+                mutated[ix] = (mutated[ix] + 1) % 10;
+                mutated
+            },
+        );
+        let differences = |(a, b): (Vec<u8>, Vec<u8>)| {
+            assert_eq!(a.len(), b.len());
+            a.iter().zip(&b).filter(|(x, y)| x != y).count()
+        };
+
+        let mut runner = TestRunner::default();
+        for _ in 0..64 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            assert_eq!(case.current(), case.current());
+            assert_eq!(1, differences(case.current()));
+            while case.simplify() {
+                assert_eq!(1, differences(case.current()));
+            }
+        }
+    }
+
+    #[test]
+    fn with_oracle_tracks_shrunk_input() {
+        let input = (0u32..1000).prop_with_oracle(|&v| v * 2);
+
+        let mut runner = TestRunner::default();
+        for _ in 0..64 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            let (value, expected) = case.current();
+            assert_eq!(value * 2, expected);
+            while case.simplify() {
+                let (value, expected) = case.current();
+                assert_eq!(value * 2, expected);
+            }
+            assert_eq!((0, 0), case.current());
+        }
+    }
+
+    #[test]
+    fn constrain_holds_invariant_while_shrinking() {
+        let input = (0i32..1000, 0i32..1000).prop_constrain(|&(a, b)| {
+            if a < b {
+                (a, b)
+            } else {
+                (b, a + 1)
+            }
+        });
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            // Shrink towards the smallest pair still spanning at least 10.
+            loop {
+                let (a, b) = case.current();
+                assert!(a < b, "({}, {})", a, b);
+                let progressed = if b - a >= 10 {
+                    case.simplify()
+                } else {
+                    case.complicate()
+                };
+                if !progressed {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn iterate_generates_markov_chain_and_shrinks_from_end() {
+        use rand::Rng;
+
+        // A walk over 0..10 which moves by at most one step at a time.
+        let input = (0u32..10).prop_iterate(
+            |&state, rng| match rng.gen_range(0..3) {
+                0 => state.saturating_sub(1),
+                1 => state,
+                _ => (state + 1).min(9),
+            },
+            2..16,
+        );
+        let is_walk = |states: &[u32]| {
+            states.iter().all(|&s| s < 10)
+                && states
+                    .windows(2)
+                    .all(|w| w[0].max(w[1]) - w[0].min(w[1]) <= 1)
+        };
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            let mut prev = case.current();
+            assert!((2..16).contains(&prev.len()));
+            assert!(is_walk(&prev), "{:?}", prev);
+            while case.simplify() {
+                let states = case.current();
+                assert!(is_walk(&states), "{:?}", states);
+                if states.len() < prev.len() {
+                    // Shortening only ever drops states from the end.
+                    assert_eq!(&prev[..states.len()], &states[..]);
+                }
+                prev = states;
+            }
+            let states = case.current();
+            assert_eq!(2, states.len());
+            assert_eq!(0, states[0]);
+        }
+    }
+
+    #[test]
+    fn duplicate_values_hash_consistently() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        fn hash(value: &impl Hash) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let input =
+            (crate::collection::hash_set(0u32..100, 0..16), "[a-z]{0,8}")
+                .prop_map(|(set, name)| {
+                    let mut sorted = set.into_iter().collect::<Vec<_>>();
+                    sorted.sort();
+                    (sorted, name)
+                })
+                .prop_duplicate();
+
+        TestRunner::default()
+            .run(&input, |(a, b)| {
+                prop_assert_eq!(&a, &b);
+                prop_assert_eq!(hash(&a), hash(&b));
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn duplicate_shrinks_both_values_together() {
+        let input = (0u32..1000).prop_duplicate();
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let case = input.new_tree(&mut runner).unwrap();
+            let result = runner.run_one(case, |(a, b)| {
+                prop_assert_eq!(a, b);
+                prop_assert!(a < 500);
+                Ok(())
+            });
+            match result {
+                Ok(true) => (),
+                Err(TestError::Fail(_, value, _, _)) => {
+                    assert_eq!((500, 500), value)
+                }
+                e => panic!("Unexpected result: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_iterate_sanity() {
+        check_strategy_sanity(
+            (0u32..10).prop_iterate(|&s, rng| s ^ rng.next_u32(), 0..8),
+            None,
+        );
+    }
 }
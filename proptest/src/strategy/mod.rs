@@ -9,28 +9,66 @@
 
 //! Defines the core traits used by Proptest.
 
+mod corrupt;
+mod debug_tree;
+mod examples;
 mod filter;
 mod filter_map;
 mod flatten;
+mod fuel;
+mod function;
 mod fuse;
+#[cfg(feature = "std")]
+mod gen_timeout;
+mod group;
 mod just;
 mod lazy;
 mod map;
 mod recursive;
+mod report;
+mod serialized_size;
+mod shrink_toward;
+mod shrink_with;
 mod shuffle;
+#[cfg(feature = "std")]
+mod struct_strategy;
+#[cfg(feature = "std")]
+mod tally;
 mod traits;
+mod try_map;
 mod unions;
+mod with_rng;
 
+pub use self::corrupt::*;
+pub use self::debug_tree::*;
+pub use self::examples::*;
 pub use self::filter::*;
 pub use self::filter_map::*;
 pub use self::flatten::*;
+pub use self::fuel::*;
+pub use self::function::*;
 pub use self::fuse::*;
+#[cfg(feature = "std")]
+pub use self::gen_timeout::*;
+pub use self::group::*;
 pub use self::just::*;
 pub use self::lazy::*;
 pub use self::map::*;
 pub use self::recursive::*;
+pub use self::report::*;
+pub use self::serialized_size::*;
+pub use self::shrink_toward::*;
+pub use self::shrink_with::*;
 pub use self::shuffle::*;
+#[cfg(feature = "std")]
+pub use self::struct_strategy::*;
+#[cfg(feature = "std")]
+pub use self::tally::*;
 pub use self::traits::*;
+pub use self::try_map::*;
 pub use self::unions::*;
+pub use self::with_rng::*;
+
+pub use crate::dyn_union;
 
 pub mod statics;
@@ -8,6 +8,7 @@
 // except according to those terms.
 
 use crate::std_facade::{fmt, Arc, Box, Vec};
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::strategy::traits::*;
 use crate::strategy::unions::float_to_weight;
@@ -68,18 +69,23 @@ impl<
             expected_branch_size,
         }
     }
-}
 
-impl<
-        T: fmt::Debug + 'static,
-        R: Strategy<Value = T> + 'static,
-        F: Fn(BoxedStrategy<T>) -> R,
-    > Strategy for Recursive<T, F>
-{
-    type Tree = Box<dyn ValueTree<Value = T>>;
-    type Value = T;
+    /// Pair each value with its `Provenance`, i.e., whether it is a leaf or
+    /// a branch, and in the latter case how deep it is.
+    ///
+    /// The provenance is recomputed whenever the value is, so it describes
+    /// the value after shrinking too, and is shown in failure reports
+    /// alongside the minimal failing value.
+    pub fn with_provenance(self) -> RecursiveWithProvenance<T, F> {
+        RecursiveWithProvenance { recursive: self }
+    }
 
-    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+    /// Build the strategy for one value, passing every recursive choice
+    /// through `mark_branch`.
+    fn layers(
+        &self,
+        mark_branch: impl Fn(BoxedStrategy<T>) -> BoxedStrategy<T>,
+    ) -> BoxedStrategy<T> {
         // Since the generator is stateless, we can't implement any "absolutely
         // X many items" rule. We _can_, however, with extremely high
         // probability, obtain a value near what we want by using decaying
@@ -119,7 +125,7 @@ impl<
         let mut strat = self.base.clone();
         while let Some(branch_probability) = branch_probabilities.pop() {
             let recursed = (self.recurse)(strat.clone());
-            let recursive_choice = recursed.boxed();
+            let recursive_choice = mark_branch(recursed.boxed());
             let non_recursive_choice = strat;
             // Clamp the maximum branch probability to 0.9 to ensure we can
             // generate non-recursive cases reasonably often.
@@ -133,7 +139,176 @@ impl<
             strat = branch.boxed();
         }
 
-        strat.new_tree(runner)
+        strat
+    }
+}
+
+impl<
+        T: fmt::Debug + 'static,
+        R: Strategy<Value = T> + 'static,
+        F: Fn(BoxedStrategy<T>) -> R,
+    > Strategy for Recursive<T, F>
+{
+    type Tree = Box<dyn ValueTree<Value = T>>;
+    type Value = T;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.layers(|branch| branch).new_tree(runner)
+    }
+}
+
+/// Where a value generated by `Strategy::prop_recursive()` came from.
+///
+/// See `Recursive::with_provenance()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Provenance {
+    /// The value was generated by the base (leaf) strategy.
+    Leaf,
+    /// The value was generated by the recursive strategy. `depth` counts the
+    /// levels of branches in it, just as the `depth` passed to
+    /// `prop_recursive()` does; e.g., a branch containing only leaves has
+    /// depth 1.
+    Branch {
+        /// The number of levels of branches in the value.
+        depth: u32,
+    },
+}
+
+impl Provenance {
+    fn from_depth(depth: usize) -> Self {
+        match depth {
+            0 => Provenance::Leaf,
+            depth => Provenance::Branch {
+                depth: depth as u32,
+            },
+        }
+    }
+
+    /// The number of levels of branches in the value, which is 0 for a leaf.
+    pub fn depth(self) -> u32 {
+        match self {
+            Provenance::Leaf => 0,
+            Provenance::Branch { depth } => depth,
+        }
+    }
+}
+
+/// `Strategy` adaptor which pairs each value of a `Recursive` with its
+/// `Provenance`.
+///
+/// See `Recursive::with_provenance()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct RecursiveWithProvenance<T, F> {
+    recursive: Recursive<T, F>,
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for RecursiveWithProvenance<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RecursiveWithProvenance")
+            .field("recursive", &self.recursive)
+            .finish()
+    }
+}
+
+impl<T, F> Clone for RecursiveWithProvenance<T, F> {
+    fn clone(&self) -> Self {
+        RecursiveWithProvenance {
+            recursive: self.recursive.clone(),
+        }
+    }
+}
+
+impl<
+        T: fmt::Debug + 'static,
+        R: Strategy<Value = T> + 'static,
+        F: Fn(BoxedStrategy<T>) -> R,
+    > Strategy for RecursiveWithProvenance<T, F>
+{
+    type Tree = RecursiveWithProvenanceValueTree<T>;
+    type Value = (T, Provenance);
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let deepest = Arc::new(AtomicUsize::new(0));
+        let source = self
+            .recursive
+            .layers(|branch| {
+                MarkBranch {
+                    source: branch,
+                    deepest: Arc::clone(&deepest),
+                }
+                .boxed()
+            })
+            .new_tree(runner)?;
+        Ok(RecursiveWithProvenanceValueTree { source, deepest })
+    }
+}
+
+/// `ValueTree` corresponding to `RecursiveWithProvenance`.
+pub struct RecursiveWithProvenanceValueTree<T> {
+    source: Box<dyn ValueTree<Value = T>>,
+    deepest: Arc<AtomicUsize>,
+}
+
+impl<T: fmt::Debug> ValueTree for RecursiveWithProvenanceValueTree<T> {
+    type Value = (T, Provenance);
+
+    fn current(&self) -> (T, Provenance) {
+        self.deepest.store(0, Ordering::Relaxed);
+        let value = self.source.current();
+        let depth = self.deepest.load(Ordering::Relaxed);
+        (value, Provenance::from_depth(depth))
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.source.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.source.complicate()
+    }
+}
+
+/// Wraps each recursive choice so that producing a value records its depth.
+///
+/// The values of a branch's children are produced while producing the
+/// branch's own, so `deepest` acts as a dynamically scoped variable: a branch
+/// clears it, produces its value, and then raises it to one more than the
+/// deepest child for its own parent to see.
+#[derive(Debug)]
+struct MarkBranch<S> {
+    source: S,
+    deepest: Arc<AtomicUsize>,
+}
+
+impl<S: Strategy> Strategy for MarkBranch<S> {
+    type Tree = MarkBranch<S::Tree>;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        Ok(MarkBranch {
+            source: self.source.new_tree(runner)?,
+            deepest: Arc::clone(&self.deepest),
+        })
+    }
+}
+
+impl<S: ValueTree> ValueTree for MarkBranch<S> {
+    type Value = S::Value;
+
+    fn current(&self) -> S::Value {
+        let outer = self.deepest.swap(0, Ordering::Relaxed);
+        let value = self.source.current();
+        let depth = self.deepest.load(Ordering::Relaxed) + 1;
+        self.deepest.store(outer.max(depth), Ordering::Relaxed);
+        value
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.source.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.source.complicate()
     }
 }
 
@@ -192,6 +367,56 @@ mod test {
         assert!(max_count > 48, "Only got max count {}", max_count);
     }
 
+    #[test]
+    fn provenance_matches_depth_through_shrinking() {
+        let strat = Just(Tree::Leaf)
+            .prop_recursive(4, 64, 16, |element| {
+                crate::collection::vec(element, 0..8).prop_map(Tree::Branch)
+            })
+            .with_provenance();
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut value = strat.new_tree(&mut runner).unwrap();
+            loop {
+                let (tree, provenance) = value.current();
+                assert_eq!(tree.stats().0, provenance.depth(), "{:?}", tree);
+                assert_eq!(tree == Tree::Leaf, provenance == Provenance::Leaf);
+                if !value.simplify() {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reports_provenance_of_minimal_failing_value() {
+        let strat = Just(Tree::Leaf)
+            .prop_recursive(4, 64, 16, |element| {
+                crate::collection::vec(element, 0..8).prop_map(Tree::Branch)
+            })
+            .with_provenance();
+
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let result = runner.run(&strat, |(tree, _)| {
+            prop_assert!(tree.stats().0 < 2);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, value, _, _)) => assert_eq!(
+                (
+                    Tree::Branch(vec![Tree::Branch(vec![])]),
+                    Provenance::Branch { depth: 2 }
+                ),
+                value
+            ),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
     #[test]
     fn simplifies_to_non_recursive() {
         let strat = Just(Tree::Leaf).prop_recursive(4, 64, 16, |element| {
@@ -0,0 +1,255 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{Arc, String};
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+type ReportFn<T> = Arc<dyn Fn(&T) -> String + Send + Sync>;
+
+/// A value whose `Debug` output, and hence its representation in failure
+/// messages, is produced by a custom function.
+///
+/// `T` need not implement `Debug`, so wrapping a value in `Reported` allows
+/// generating types which cannot be printed otherwise, e.g., from
+/// `Strategy::prop_map()`:
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::strategy::Reported;
+///
+/// // Does not implement `Debug`.
+/// struct Handle(u32);
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn test_handle(handle in (0..10u32).prop_map(|id| Reported::new(
+///       Handle(id), |handle| format!("Handle #{}", handle.0))))
+///   {
+///       prop_assert!(handle.0 < 10);
+///   }
+/// }
+/// #
+/// # fn main() { test_handle(); }
+/// ```
+///
+/// `Reported` dereferences to the wrapped value.
+pub struct Reported<T> {
+    value: T,
+    report: ReportFn<T>,
+}
+
+impl<T> Reported<T> {
+    /// Wrap `value`, describing it by the output of `report`.
+    pub fn new(
+        value: T,
+        report: impl Fn(&T) -> String + Send + Sync + 'static,
+    ) -> Self {
+        Reported {
+            value,
+            report: Arc::new(report),
+        }
+    }
+
+    /// Unwrap the value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> fmt::Debug for Reported<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&(self.report)(&self.value))
+    }
+}
+
+impl<T: Clone> Clone for Reported<T> {
+    fn clone(&self) -> Self {
+        Reported {
+            value: self.value.clone(),
+            report: Arc::clone(&self.report),
+        }
+    }
+}
+
+/// Compares the wrapped values only.
+impl<T: PartialEq> PartialEq for Reported<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for Reported<T> {}
+
+impl<T> Deref for Reported<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Reported<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+/// `Strategy` and `ValueTree` adaptor which reports values with a custom
+/// function.
+///
+/// See `Strategy::prop_report_with()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct ReportWith<S: Strategy> {
+    source: S,
+    report: ReportFn<S::Value>,
+}
+
+impl<S: Strategy> ReportWith<S> {
+    pub(super) fn new(
+        source: S,
+        report: impl Fn(&S::Value) -> String + Send + Sync + 'static,
+    ) -> Self {
+        ReportWith {
+            source,
+            report: Arc::new(report),
+        }
+    }
+}
+
+impl<S: Strategy + fmt::Debug> fmt::Debug for ReportWith<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReportWith")
+            .field("source", &self.source)
+            .field("report", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Strategy + Clone> Clone for ReportWith<S> {
+    fn clone(&self) -> Self {
+        ReportWith {
+            source: self.source.clone(),
+            report: Arc::clone(&self.report),
+        }
+    }
+}
+
+impl<S: Strategy> Strategy for ReportWith<S> {
+    type Tree = ReportWithValueTree<S::Tree>;
+    type Value = Reported<S::Value>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        Ok(ReportWithValueTree {
+            source: self.source.new_tree(runner)?,
+            report: Arc::clone(&self.report),
+        })
+    }
+}
+
+/// `ValueTree` corresponding to `ReportWith`.
+pub struct ReportWithValueTree<T: ValueTree> {
+    source: T,
+    report: ReportFn<T::Value>,
+}
+
+impl<T: ValueTree + fmt::Debug> fmt::Debug for ReportWithValueTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReportWithValueTree")
+            .field("source", &self.source)
+            .field("report", &"<function>")
+            .finish()
+    }
+}
+
+impl<T: ValueTree + Clone> Clone for ReportWithValueTree<T> {
+    fn clone(&self) -> Self {
+        ReportWithValueTree {
+            source: self.source.clone(),
+            report: Arc::clone(&self.report),
+        }
+    }
+}
+
+impl<T: ValueTree> ValueTree for ReportWithValueTree<T> {
+    type Value = Reported<T::Value>;
+
+    fn current(&self) -> Reported<T::Value> {
+        Reported {
+            value: self.source.current(),
+            report: Arc::clone(&self.report),
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.source.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.source.complicate()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::std_facade::string::ToString;
+
+    // Deliberately does not implement `Debug`.
+    struct Secret(u32);
+
+    #[test]
+    fn failure_message_uses_custom_report() {
+        let input = (0..1000u32).prop_map(|n| {
+            Reported::new(Secret(n), |secret| format!("Secret({})", secret.0))
+        });
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let error = runner
+            .run(&input, |secret| {
+                prop_assert!(secret.0 < 500);
+                Ok(())
+            })
+            .unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .contains("minimal failing input: Secret(500)"),
+            "Unexpected message: {}",
+            error
+        );
+    }
+
+    #[test]
+    fn report_with_replaces_debug_output() {
+        let input = "[a-z]{1,8}".prop_report_with(|s| s.to_uppercase());
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let value = input.new_tree(&mut runner).unwrap().current();
+            assert_eq!(value.to_uppercase(), format!("{:?}", value));
+            assert_eq!(*value, value.clone().into_inner());
+        }
+    }
+
+    #[test]
+    fn test_sanity() {
+        check_strategy_sanity(
+            (0..100i32).prop_report_with(|n| format!("n = {}", n)),
+            None,
+        );
+    }
+}
@@ -0,0 +1,191 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{fmt, Arc};
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// `Strategy` and `ValueTree` adaptor which only produces values within a
+/// serialized size.
+///
+/// See `Strategy::prop_filter_serialized_size()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct FilterSerializedSize<S, F> {
+    pub(super) source: S,
+    pub(super) max_size: usize,
+    pub(super) size_fn: Arc<F>,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for FilterSerializedSize<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FilterSerializedSize")
+            .field("source", &self.source)
+            .field("max_size", &self.max_size)
+            .field("size_fn", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for FilterSerializedSize<S, F> {
+    fn clone(&self) -> Self {
+        FilterSerializedSize {
+            source: self.source.clone(),
+            max_size: self.max_size,
+            size_fn: Arc::clone(&self.size_fn),
+        }
+    }
+}
+
+impl<S, F> FilterSerializedSize<S, F> {
+    fn with_source<T>(&self, source: T) -> FilterSerializedSize<T, F> {
+        FilterSerializedSize {
+            source,
+            max_size: self.max_size,
+            size_fn: Arc::clone(&self.size_fn),
+        }
+    }
+}
+
+impl<S: Strategy, F: Fn(&S::Value) -> usize> Strategy
+    for FilterSerializedSize<S, F>
+{
+    type Tree = FilterSerializedSize<S::Tree, F>;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        loop {
+            let mut tree = self.with_source(self.source.new_tree(runner)?);
+            // Rather than discarding an oversized value, look for one that
+            // fits among its simplifications, which are usually smaller. This
+            // biases generation towards smaller values, but rejects far fewer
+            // of them than `prop_filter()` would.
+            while !tree.fits() {
+                if !tree.source.simplify() {
+                    break;
+                }
+            }
+            if tree.fits() {
+                return Ok(tree);
+            }
+            runner.reject_local(format!(
+                "value exceeds serialized size of {}",
+                self.max_size
+            ))?;
+        }
+    }
+}
+
+impl<S: ValueTree, F: Fn(&S::Value) -> usize> FilterSerializedSize<S, F> {
+    fn fits(&self) -> bool {
+        (self.size_fn)(&self.source.current()) <= self.max_size
+    }
+
+    fn ensure_fits(&mut self) {
+        while !self.fits() {
+            if !self.source.complicate() {
+                panic!(
+                    "Unable to complicate size-filtered strategy back into \
+                     a value within the serialized size"
+                );
+            }
+        }
+    }
+}
+
+impl<S: ValueTree, F: Fn(&S::Value) -> usize> ValueTree
+    for FilterSerializedSize<S, F>
+{
+    type Value = S::Value;
+
+    fn current(&self) -> S::Value {
+        self.source.current()
+    }
+
+    fn simplify(&mut self) -> bool {
+        if self.source.simplify() {
+            self.ensure_fits();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        if self.source.complicate() {
+            self.ensure_fits();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::collection::vec;
+    use crate::num;
+
+    /// A length-prefixed encoding, as for a network message.
+    fn encoded_len(v: &[u8]) -> usize {
+        4 + v.len()
+    }
+
+    #[test]
+    fn values_fit_with_few_rejections() {
+        let input = vec(num::u8::ANY, 0..200)
+            .prop_filter_serialized_size(40, |v| encoded_len(v));
+        let mut runner = TestRunner::deterministic();
+        let mut longest = 0;
+        for _ in 0..256 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            longest = longest.max(tree.current().len());
+            loop {
+                assert!(encoded_len(&tree.current()) <= 40);
+                if !tree.simplify() {
+                    break;
+                }
+            }
+        }
+
+        // `prop_filter()` would reject more than four in five values.
+        assert_eq!(36, longest);
+        assert!(
+            runner.stats().local_rejects < 8,
+            "{} local rejects",
+            runner.stats().local_rejects
+        );
+    }
+
+    #[test]
+    fn unshrinkable_values_are_rejected() {
+        let input = (100..200usize).prop_filter_serialized_size(9, |&v| v / 10);
+        let mut runner = TestRunner::new_with_rng(
+            Config {
+                max_local_rejects: 8,
+                ..Config::default()
+            },
+            TestRng::deterministic_rng(RngAlgorithm::default()),
+        );
+        let result = runner.run(&input, |_| Ok(()));
+        assert!(result.is_err());
+        let detail = format!("{}", runner);
+        assert!(detail.contains("value exceeds serialized size of 9"));
+    }
+
+    #[test]
+    fn test_sanity() {
+        check_strategy_sanity(
+            vec(num::u8::ANY, 0..50)
+                .prop_filter_serialized_size(20, |v| encoded_len(v)),
+            None,
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::strategy::*;
+use crate::test_runner::*;
+
+/// `Strategy` adaptor which tries a fixed target value first when shrinking.
+///
+/// See `Strategy::prop_shrink_toward()`.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct ShrinkToward<S: Strategy>
+where
+    S::Value: Clone + Eq,
+{
+    source: S,
+    target: S::Value,
+}
+
+impl<S: Strategy> ShrinkToward<S>
+where
+    S::Value: Clone + Eq,
+{
+    pub(super) fn new(source: S, target: S::Value) -> Self {
+        ShrinkToward { source, target }
+    }
+}
+
+impl<S: Strategy> Strategy for ShrinkToward<S>
+where
+    S::Value: Clone + Eq,
+{
+    type Tree = ShrinkTowardValueTree<S::Tree>;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        Ok(ShrinkTowardValueTree {
+            source: self.source.new_tree(runner)?,
+            target: self.target.clone(),
+            state: State::Initial,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum State {
+    // `simplify()` has not been called yet.
+    Initial,
+    // The target is the current value.
+    AtTarget,
+    // The target passed the test, and the source has not been shrunk since.
+    Reverted,
+    // Shrinking is delegated to the source.
+    Source,
+}
+
+/// `ValueTree` corresponding to `ShrinkToward`.
+#[derive(Clone, Debug)]
+pub struct ShrinkTowardValueTree<T: ValueTree>
+where
+    T::Value: Clone + Eq,
+{
+    source: T,
+    target: T::Value,
+    state: State,
+}
+
+impl<T: ValueTree> ValueTree for ShrinkTowardValueTree<T>
+where
+    T::Value: Clone + Eq,
+{
+    type Value = T::Value;
+
+    fn current(&self) -> T::Value {
+        match self.state {
+            State::AtTarget => self.target.clone(),
+            State::Initial | State::Reverted | State::Source => {
+                self.source.current()
+            }
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        match self.state {
+            // Nothing is simpler than the target, even if the source could
+            // shrink it further.
+            State::AtTarget => false,
+            _ if self.source.current() == self.target => false,
+            State::Initial => {
+                self.state = State::AtTarget;
+                true
+            }
+            State::Reverted | State::Source => {
+                self.state = State::Source;
+                self.source.simplify()
+            }
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.state {
+            State::Initial | State::Reverted => false,
+            State::AtTarget => {
+                // The target does not reproduce the failure; go back to the
+                // failing value and shrink it the usual way instead.
+                self.state = State::Reverted;
+                true
+            }
+            State::Source => self.source.complicate(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn shrinks_to_target_if_it_fails() {
+        let input = (0..1000u32).prop_shrink_toward(500);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..256 {
+            let case = input.new_tree(&mut runner).unwrap();
+            let result = runner.run_one(case, |v| {
+                prop_assert!(v < 400);
+                Ok(())
+            });
+
+            match result {
+                Ok(true) => (),
+                Err(TestError::Fail(_, v, _, _)) => assert_eq!(500, v),
+                e => panic!("Unexpected result: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn falls_back_to_default_shrinking() {
+        let input = (0..1000u32).prop_shrink_toward(5);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..256 {
+            let case = input.new_tree(&mut runner).unwrap();
+            let result = runner.run_one(case, |v| {
+                prop_assert!(v < 400);
+                Ok(())
+            });
+
+            match result {
+                Ok(true) => (),
+                Err(TestError::Fail(_, v, _, _)) => assert_eq!(400, v),
+                e => panic!("Unexpected result: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_sanity() {
+        check_strategy_sanity((0..100i32).prop_shrink_toward(50), None);
+    }
+}
@@ -0,0 +1,203 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{fmt, Arc};
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// `Strategy` adaptor which tries a custom simplifier before shrinking the
+/// source.
+///
+/// See `Strategy::prop_shrink_with()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct ShrinkWith<S, F> {
+    pub(super) source: S,
+    pub(super) simplifier: Arc<F>,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for ShrinkWith<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ShrinkWith")
+            .field("source", &self.source)
+            .field("simplifier", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for ShrinkWith<S, F> {
+    fn clone(&self) -> Self {
+        ShrinkWith {
+            source: self.source.clone(),
+            simplifier: Arc::clone(&self.simplifier),
+        }
+    }
+}
+
+impl<S: Strategy, F: Fn(&S::Value) -> Option<S::Value>> Strategy
+    for ShrinkWith<S, F>
+where
+    S::Value: Clone,
+{
+    type Tree = ShrinkWithValueTree<S::Tree, F>;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        Ok(ShrinkWithValueTree {
+            source: self.source.new_tree(runner)?,
+            simplifier: Arc::clone(&self.simplifier),
+            custom: None,
+            exhausted: false,
+            prev_shrink: None,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+enum PrevShrink<V> {
+    /// The simplifier replaced the given custom value, if any.
+    Custom(Option<V>),
+    /// The source was simplified.
+    Source,
+}
+
+/// `ValueTree` corresponding to `ShrinkWith`.
+pub struct ShrinkWithValueTree<T: ValueTree, F> {
+    source: T,
+    simplifier: Arc<F>,
+    /// The value produced by the simplifier, which replaces the value of the
+    /// source.
+    custom: Option<T::Value>,
+    /// Whether the simplifier has nothing (more) to offer for the current
+    /// value.
+    exhausted: bool,
+    prev_shrink: Option<PrevShrink<T::Value>>,
+}
+
+impl<T: ValueTree + fmt::Debug, F> fmt::Debug for ShrinkWithValueTree<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ShrinkWithValueTree")
+            .field("source", &self.source)
+            .field("simplifier", &"<function>")
+            .field("custom", &self.custom)
+            .field("exhausted", &self.exhausted)
+            .field("prev_shrink", &self.prev_shrink)
+            .finish()
+    }
+}
+
+impl<T: ValueTree + Clone, F> Clone for ShrinkWithValueTree<T, F>
+where
+    T::Value: Clone,
+{
+    fn clone(&self) -> Self {
+        ShrinkWithValueTree {
+            source: self.source.clone(),
+            simplifier: Arc::clone(&self.simplifier),
+            custom: self.custom.clone(),
+            exhausted: self.exhausted,
+            prev_shrink: self.prev_shrink.clone(),
+        }
+    }
+}
+
+impl<T: ValueTree, F: Fn(&T::Value) -> Option<T::Value>> ValueTree
+    for ShrinkWithValueTree<T, F>
+where
+    T::Value: Clone,
+{
+    type Value = T::Value;
+
+    fn current(&self) -> T::Value {
+        match self.custom {
+            Some(ref custom) => custom.clone(),
+            None => self.source.current(),
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        if !self.exhausted {
+            if let Some(simpler) = (self.simplifier)(&self.current()) {
+                let prev = self.custom.replace(simpler);
+                self.prev_shrink = Some(PrevShrink::Custom(prev));
+                return true;
+            }
+            self.exhausted = true;
+        }
+
+        // The source knows nothing about values from the simplifier, so
+        // there is no way to shrink those any further.
+        if self.custom.is_none() && self.source.simplify() {
+            self.exhausted = false;
+            self.prev_shrink = Some(PrevShrink::Source);
+            true
+        } else {
+            self.prev_shrink = None;
+            false
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.prev_shrink.take() {
+            None => false,
+            Some(PrevShrink::Custom(prev)) => {
+                // The simplified value passed, so the simplifier can't do
+                // any better from here.
+                self.custom = prev;
+                self.exhausted = true;
+                true
+            }
+            Some(PrevShrink::Source) => {
+                let complicated = self.source.complicate();
+                if complicated {
+                    self.exhausted = false;
+                    self.prev_shrink = Some(PrevShrink::Source);
+                }
+                complicated
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn minimal_failure<S: Strategy<Value = u32>>(input: S) -> u32 {
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&input, |v| {
+            prop_assert!(v % 2 == 1 || v < 1000);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, v, _, _)) => v,
+            e => panic!("Unexpected result: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn simplifier_finds_smaller_minimum() {
+        let default = minimal_failure(0..1_000_000u32);
+        // Halving might make the value odd, which makes the test pass.
+        let custom = minimal_failure(
+            (0..1_000_000u32).prop_shrink_with(|&v| Some((v / 2) & !1)),
+        );
+        assert_eq!(0, custom % 2);
+        assert!(custom >= 1000);
+        assert!(custom < default, "{} >= {}", custom, default);
+    }
+
+    #[test]
+    fn test_sanity() {
+        check_strategy_sanity(
+            (0..1000u32).prop_shrink_with(|&v| Some(v / 2).filter(|_| v > 0)),
+            None,
+        );
+    }
+}
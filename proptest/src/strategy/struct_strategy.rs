@@ -0,0 +1,453 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::{fmt, mem};
+use std::boxed::Box;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::vec::Vec;
+
+use crate::strategy::*;
+use crate::test_runner::*;
+
+/// The fields generated by a `StructStrategy`, by name.
+pub type Fields<V> = HashMap<&'static str, V>;
+
+/// Builder for strategies of structs whose fields depend on each other,
+/// e.g., where one field is an index into another.
+///
+/// Every field is generated as a value of the same type `V`, typically an
+/// enum with a variant for each type of field. Fields added with `field()`
+/// are independent, while those added with `dependent_field()` are generated
+/// by a strategy chosen from the values of the fields before them.
+///
+/// Shrinking simplifies the fields in dependency order: all the fields
+/// before a dependent field are shrunk first, regenerating the dependent
+/// field after each change to them so the dependency always holds, and only
+/// then is the dependent field itself shrunk. Once a dependent field has
+/// started shrinking, the fields before it no longer change, so it is never
+/// regenerated again.
+///
+/// ## Example
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::strategy::StructStrategy;
+///
+/// #[derive(Clone, Debug)]
+/// enum Field {
+///     Items(Vec<u32>),
+///     Index(usize),
+/// }
+///
+/// #[derive(Debug)]
+/// struct Cursor {
+///     items: Vec<u32>,
+///     index: usize,
+/// }
+///
+/// fn cursors() -> impl Strategy<Value = Cursor> {
+///     StructStrategy::new()
+///         .field("items", prop::collection::vec(any::<u32>(), 1..10)
+///             .prop_map(Field::Items))
+///         .dependent_field("index", |fields| match fields["items"] {
+///             Field::Items(ref items) => {
+///                 (0..items.len()).prop_map(Field::Index)
+///             }
+///             _ => unreachable!(),
+///         })
+///         .build_with(|mut fields| match (
+///             fields.remove("items"),
+///             fields.remove("index"),
+///         ) {
+///             (Some(Field::Items(items)), Some(Field::Index(index))) => {
+///                 Cursor { items, index }
+///             }
+///             _ => unreachable!(),
+///         })
+/// }
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn index_is_in_bounds(cursor in cursors()) {
+///     prop_assert!(cursor.index < cursor.items.len());
+///   }
+/// }
+/// #
+/// # fn main() { index_is_in_bounds(); }
+/// ```
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct StructStrategy<V> {
+    names: Vec<&'static str>,
+    fields: BoxedStrategy<Fields<V>>,
+}
+
+impl<V: Clone + fmt::Debug + 'static> Default for StructStrategy<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: Clone + fmt::Debug + 'static> StructStrategy<V> {
+    /// Start building a strategy without any fields.
+    pub fn new() -> Self {
+        StructStrategy {
+            names: Vec::new(),
+            fields: Just(Fields::new()).boxed(),
+        }
+    }
+
+    fn add_name(&mut self, name: &'static str) {
+        assert!(
+            !self.names.contains(&name),
+            "StructStrategy already has a field named {}",
+            name
+        );
+        self.names.push(name);
+    }
+
+    /// Add a field called `name` generated by `strategy`, independently of
+    /// the other fields.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if there already is a field called `name`.
+    pub fn field(
+        mut self,
+        name: &'static str,
+        strategy: impl Strategy<Value = V> + 'static,
+    ) -> Self {
+        self.add_name(name);
+        let fields = (self.fields, strategy).prop_map(move |(mut f, v)| {
+            f.insert(name, v);
+            f
+        });
+        StructStrategy {
+            names: self.names,
+            fields: fields.boxed(),
+        }
+    }
+
+    /// Add a field called `name` generated by the strategy `fun` returns for
+    /// the fields added before it.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if there already is a field called `name`.
+    pub fn dependent_field<S: Strategy<Value = V> + 'static>(
+        mut self,
+        name: &'static str,
+        fun: impl Fn(&Fields<V>) -> S + 'static,
+    ) -> Self {
+        self.add_name(name);
+        let fields = DependentField {
+            base: self.fields,
+            name,
+            fun: Arc::new(fun),
+        };
+        StructStrategy {
+            names: self.names,
+            fields: fields.boxed(),
+        }
+    }
+
+    /// Finish building a strategy which generates the fields by name.
+    pub fn build(self) -> BoxedStrategy<Fields<V>> {
+        self.fields
+    }
+
+    /// Finish building a strategy which generates the fields by name and
+    /// passes them to `finalizer`, e.g., to construct the struct itself.
+    pub fn build_with<T: fmt::Debug>(
+        self,
+        finalizer: impl Fn(Fields<V>) -> T + 'static,
+    ) -> BoxedStrategy<T> {
+        self.fields.prop_map(finalizer).boxed()
+    }
+}
+
+/// Strategy adding a dependent field to the fields of `base`.
+struct DependentField<V, F> {
+    base: BoxedStrategy<Fields<V>>,
+    name: &'static str,
+    fun: Arc<F>,
+}
+
+impl<V: fmt::Debug, F> fmt::Debug for DependentField<V, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DependentField")
+            .field("base", &self.base)
+            .field("name", &self.name)
+            .field("fun", &"<function>")
+            .finish()
+    }
+}
+
+impl<V, S, F> Strategy for DependentField<V, F>
+where
+    V: Clone + fmt::Debug + 'static,
+    S: Strategy<Value = V> + 'static,
+    F: Fn(&Fields<V>) -> S,
+{
+    type Tree = DependentFieldValueTree<V, F>;
+    type Value = Fields<V>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let base = self.base.new_tree(runner)?;
+        let fields = base.current();
+        let dependent = Box::new((self.fun)(&fields).new_tree(runner)?);
+        Ok(DependentFieldValueTree {
+            base,
+            name: self.name,
+            fun: Arc::clone(&self.fun),
+            fields,
+            dependent,
+            runner: runner.partial_clone(),
+            shrinking_base: true,
+            prev_shrink: None,
+        })
+    }
+}
+
+enum PrevShrink<V> {
+    /// The base was simplified or complicated, replacing these fields and
+    /// dependent field, the last ones which are known to have failed.
+    Base(Fields<V>, Box<dyn ValueTree<Value = V>>),
+    Dependent,
+}
+
+/// `ValueTree` corresponding to `DependentField`.
+struct DependentFieldValueTree<V, F> {
+    base: Box<dyn ValueTree<Value = Fields<V>>>,
+    name: &'static str,
+    fun: Arc<F>,
+    /// The value of `base` which `dependent` was generated for, which `base`
+    /// itself may have moved on from.
+    fields: Fields<V>,
+    dependent: Box<dyn ValueTree<Value = V>>,
+    /// Regenerates `dependent` whenever `base` changes.
+    runner: TestRunner,
+    shrinking_base: bool,
+    prev_shrink: Option<PrevShrink<V>>,
+}
+
+impl<V, S, F> DependentFieldValueTree<V, F>
+where
+    V: Clone + fmt::Debug + 'static,
+    S: Strategy<Value = V> + 'static,
+    F: Fn(&Fields<V>) -> S,
+{
+    /// Regenerates the dependent field for the current value of the base,
+    /// returning the previous fields and dependent field.
+    fn regenerate(
+        &mut self,
+    ) -> Option<(Fields<V>, Box<dyn ValueTree<Value = V>>)> {
+        let fields = self.base.current();
+        let dependent =
+            Box::new((self.fun)(&fields).new_tree(&mut self.runner).ok()?);
+        Some((
+            mem::replace(&mut self.fields, fields),
+            mem::replace(&mut self.dependent, dependent),
+        ))
+    }
+}
+
+impl<V, S, F> ValueTree for DependentFieldValueTree<V, F>
+where
+    V: Clone + fmt::Debug + 'static,
+    S: Strategy<Value = V> + 'static,
+    F: Fn(&Fields<V>) -> S,
+{
+    type Value = Fields<V>;
+
+    fn current(&self) -> Fields<V> {
+        let mut fields = self.fields.clone();
+        fields.insert(self.name, self.dependent.current());
+        fields
+    }
+
+    fn simplify(&mut self) -> bool {
+        if self.shrinking_base && self.base.simplify() {
+            if let Some((fields, dependent)) = self.regenerate() {
+                self.prev_shrink = Some(PrevShrink::Base(fields, dependent));
+                return true;
+            }
+        }
+
+        // From here on, the base stays at `fields`, even if the dependent
+        // field could not be regenerated for its latest value.
+        self.shrinking_base = false;
+        if self.dependent.simplify() {
+            self.prev_shrink = Some(PrevShrink::Dependent);
+            true
+        } else {
+            self.prev_shrink = None;
+            false
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.prev_shrink.take() {
+            None => false,
+            Some(PrevShrink::Base(fields, dependent)) => {
+                if self.base.complicate() && self.regenerate().is_some() {
+                    self.prev_shrink =
+                        Some(PrevShrink::Base(fields, dependent));
+                } else {
+                    // Go back to the last fields which failed. The base may
+                    // still simplify in other ways, e.g. in another field.
+                    self.fields = fields;
+                    self.dependent = dependent;
+                }
+                true
+            }
+            Some(PrevShrink::Dependent) => {
+                let complicated = self.dependent.complicate();
+                if complicated {
+                    self.prev_shrink = Some(PrevShrink::Dependent);
+                }
+                complicated
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::collection::vec;
+    use crate::test_runner::{TestError, TestRunner};
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Field {
+        List(Vec<u32>),
+        Index(usize),
+        Flag(bool),
+    }
+
+    fn indexed() -> BoxedStrategy<(Vec<u32>, usize, bool)> {
+        StructStrategy::new()
+            .field("list", vec(0..100u32, 1..10).prop_map(Field::List))
+            .dependent_field("index", |fields| match fields["list"] {
+                Field::List(ref list) => (0..list.len()).prop_map(Field::Index),
+                ref other => panic!("Unexpected field {:?}", other),
+            })
+            .field("flag", crate::bool::ANY.prop_map(Field::Flag))
+            .build_with(|fields| {
+                match (&fields["list"], &fields["index"], &fields["flag"]) {
+                    (
+                        Field::List(list),
+                        &Field::Index(index),
+                        &Field::Flag(flag),
+                    ) => (list.clone(), index, flag),
+                    other => panic!("Unexpected fields {:?}", other),
+                }
+            })
+    }
+
+    #[test]
+    fn dependent_field_stays_valid_while_shrinking() {
+        let input = indexed();
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            loop {
+                let (list, index, _) = tree.current();
+                assert!(index < list.len(), "{} >= {}", index, list.len());
+                if !tree.simplify() {
+                    break;
+                }
+            }
+            assert_eq!((vec![0], 0, false), tree.current());
+        }
+    }
+
+    #[test]
+    fn minimal_failure_keeps_dependency() {
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&indexed(), |(list, index, _)| {
+            prop_assert!(list[index] < 50);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, (list, index, flag), _, _)) => {
+                assert_eq!(50, list[index]);
+                assert!(!flag);
+            }
+            e => panic!("Unexpected result: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn fields_shrink_in_dependency_order() {
+        let input = StructStrategy::new()
+            .field("a", 0..100u32)
+            .dependent_field("b", |fields| 0..fields["a"] + 100)
+            .build_with(|fields| (fields["a"], fields["b"]));
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..32 {
+            // The runner turns panics into failures, so violations are
+            // recorded instead.
+            let violations = RefCell::new(Vec::new());
+            let last_failure = Cell::new(None);
+            let shrinking_b = Cell::new(false);
+            let tree = input.new_tree(&mut runner).unwrap();
+            let result = runner.run_one(tree, |(a, b)| {
+                if b >= a + 100 {
+                    violations.borrow_mut().push((a, b));
+                }
+                if let Some((fail_a, fail_b)) = last_failure.get() {
+                    if shrinking_b.get() && fail_a != a {
+                        // Once b shrinks, a no longer changes.
+                        violations.borrow_mut().push((a, b));
+                    } else if fail_a == a && fail_b != b {
+                        shrinking_b.set(true);
+                    }
+                }
+                if a > 20 || b > 20 {
+                    last_failure.set(Some((a, b)));
+                    prop_assert!(false);
+                }
+                Ok(())
+            });
+            assert_eq!(Vec::<(u32, u32)>::new(), violations.into_inner());
+            match result {
+                Err(TestError::Fail(_, (a, b), _, _)) => {
+                    assert!(21 == a.max(b), "({}, {})", a, b);
+                }
+                Ok(_) => (),
+                e => panic!("Unexpected result: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn build_generates_fields_by_name() {
+        let input = StructStrategy::new()
+            .field("a", Just(1))
+            .dependent_field("b", |fields| Just(fields["a"] + 1))
+            .build();
+        let mut runner = TestRunner::deterministic();
+        let fields = input.new_tree(&mut runner).unwrap().current();
+        assert_eq!(2, fields.len());
+        assert_eq!((1, 2), (fields["a"], fields["b"]));
+    }
+
+    #[test]
+    #[should_panic(expected = "already has a field named a")]
+    fn rejects_duplicate_names() {
+        let _ = StructStrategy::new()
+            .field("a", Just(1))
+            .field("a", Just(2));
+    }
+}
@@ -0,0 +1,120 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::fmt;
+use core::hash::Hash;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// `Strategy` adaptor which counts the generated values by bucket.
+///
+/// See `Strategy::prop_tally()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct Tally<S, F, K> {
+    source: S,
+    bucket_fn: Arc<F>,
+    sink: Arc<Mutex<HashMap<K, u64>>>,
+}
+
+impl<S, F, K> Tally<S, F, K> {
+    pub(super) fn new(
+        source: S,
+        bucket_fn: F,
+        sink: Arc<Mutex<HashMap<K, u64>>>,
+    ) -> Self {
+        Tally {
+            source,
+            bucket_fn: Arc::new(bucket_fn),
+            sink,
+        }
+    }
+}
+
+impl<S: fmt::Debug, F, K> fmt::Debug for Tally<S, F, K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Tally")
+            .field("source", &self.source)
+            .field("bucket_fn", &"<function>")
+            .field("sink", &"<sink>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F, K> Clone for Tally<S, F, K> {
+    fn clone(&self) -> Self {
+        Tally {
+            source: self.source.clone(),
+            bucket_fn: Arc::clone(&self.bucket_fn),
+            sink: Arc::clone(&self.sink),
+        }
+    }
+}
+
+impl<S: Strategy, F: Fn(&S::Value) -> K, K: Hash + Eq> Strategy
+    for Tally<S, F, K>
+{
+    type Tree = S::Tree;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let tree = self.source.new_tree(runner)?;
+        let key = (self.bucket_fn)(&tree.current());
+        // A panic in another thread while it held the lock cannot leave the
+        // counts inconsistent, so keep counting.
+        let mut sink = self.sink.lock().unwrap_or_else(|e| e.into_inner());
+        *sink.entry(key).or_insert(0) += 1;
+        Ok(tree)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tally_counts_generated_values() {
+        let sink = Arc::new(Mutex::new(HashMap::new()));
+        let input = prop_oneof![
+            3 => 0..10u32,
+            1 => 10..20u32,
+        ]
+        .prop_tally(|&v| v < 10, Arc::clone(&sink));
+
+        let mut runner = TestRunner::deterministic();
+        let draws = 4000;
+        for _ in 0..draws {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            // Shrinking is not counted.
+            while tree.simplify() {}
+        }
+
+        let sink = sink.lock().unwrap();
+        assert_eq!(draws, sink.values().sum::<u64>());
+        let low = sink[&true];
+        assert!(low > draws * 7 / 10 && low < draws * 8 / 10, "{:?}", sink);
+    }
+
+    #[test]
+    fn tally_passes_values_through() {
+        let sink = Arc::new(Mutex::new(HashMap::new()));
+        let input = (0..100i32).prop_tally(|&v| v % 3, Arc::clone(&sink));
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            let value = tree.current();
+            assert!((0..100).contains(&value));
+            while tree.simplify() {}
+            assert_eq!(0, tree.current());
+        }
+        assert_eq!(64, sink.lock().unwrap().values().sum::<u64>());
+    }
+}
@@ -7,9 +7,19 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::std_facade::{fmt, Arc, Box, Rc};
+use crate::std_facade::{fmt, Arc, Box, Rc, String, Vec};
 use core::cmp;
-
+#[cfg(feature = "std")]
+use core::hash::Hash;
+use core::sync::atomic::AtomicUsize;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+use crate::collection::SizeRange;
 use crate::strategy::*;
 use crate::test_runner::*;
 
@@ -84,6 +94,36 @@ pub trait Strategy: fmt::Debug {
         }
     }
 
+    /// Returns a strategy which produces values transformed by the function
+    /// `fun`, like `prop_map()`, but whose shrinking skips steps which do not
+    /// change the transformed value.
+    ///
+    /// When `fun` maps many inputs to the same output, e.g., `|n| n / 100`,
+    /// most shrinking steps of the source produce the same value again, and
+    /// the test is run on it again to no effect. This strategy instead keeps
+    /// shrinking the source until the output changes, for at most 64 steps
+    /// per simplification or complication, so that each test run during
+    /// shrinking sees a new value.
+    ///
+    /// Two outputs are considered the same if their `Debug` representations
+    /// are equal, i.e., if they are indistinguishable in a failure report.
+    /// Computing the representation for every step adds some overhead, so
+    /// this is only worthwhile if `fun` is lossy or the test is expensive.
+    ///
+    /// `fun` should be a deterministic function, as with `prop_map()`.
+    fn prop_map_unique<O: fmt::Debug, F: Fn(Self::Value) -> O>(
+        self,
+        fun: F,
+    ) -> MapUnique<Self, F>
+    where
+        Self: Sized,
+    {
+        MapUnique {
+            source: self,
+            fun: Arc::new(fun),
+        }
+    }
+
     /// Returns a strategy which produces values of type `O` by transforming
     /// `Self` with `Into<O>`.
     ///
@@ -147,6 +187,346 @@ pub trait Strategy: fmt::Debug {
         }
     }
 
+    /// Returns a strategy which generates pairs of a value from `self` and a
+    /// mutation of that value produced by `fun`.
+    ///
+    /// This is useful for differential testing, e.g., checking that a small
+    /// change to an input propagates correctly to the output.
+    ///
+    /// As with `prop_perturb()`, `fun` is always called with an identical
+    /// random number generator for a given value tree. Shrinking only
+    /// simplifies the base value; the mutation is re-derived from the
+    /// simplified value each time, so it stays the same kind of edit.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn test_something((original, edited) in "[a-z]{1,16}".prop_with_mutation(
+    ///       // Replace a single character of the string with `X`.
+    ///       |s, rng| {
+    ///           let mut chars: Vec<char> = s.chars().collect();
+    ///           let ix = rng.gen_range(0..chars.len());
+    ///           chars[ix] = 'X';
+    ///           chars.into_iter().collect()
+    ///       }))
+    ///   {
+    ///       assert_eq!(original.len(), edited.len());
+    ///       prop_assert_eq!(1, original.chars().zip(edited.chars())
+    ///           .filter(|(a, b)| a != b).count());
+    ///   }
+    /// }
+    /// #
+    /// # fn main() { test_something(); }
+    /// ```
+    fn prop_with_mutation<F: Fn(&Self::Value, &mut TestRng) -> Self::Value>(
+        self,
+        fun: F,
+    ) -> WithMutation<Self, F>
+    where
+        Self: Sized,
+    {
+        WithMutation {
+            source: self,
+            fun: Arc::new(fun),
+        }
+    }
+
+    /// Returns a strategy which generates pairs of a value from `self` and
+    /// the output `oracle` produces for it, e.g., a simple reference
+    /// implementation to check the code under test against.
+    ///
+    /// The output is recomputed from the value every time it changes, so
+    /// while shrinking it always belongs to the (possibly shrunk) value it
+    /// is paired with. As with `prop_map()`, `oracle` should be
+    /// deterministic.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// fn insertion_sort(v: &mut [u32]) {
+    ///     for i in 1..v.len() {
+    ///         let mut j = i;
+    ///         while j > 0 && v[j - 1] > v[j] {
+    ///             v.swap(j - 1, j);
+    ///             j -= 1;
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn test_insertion_sort((mut input, sorted) in
+    ///       prop::collection::vec(any::<u32>(), 0..32).prop_with_oracle(
+    ///           |v| {
+    ///               let mut sorted = v.clone();
+    ///               sorted.sort();
+    ///               sorted
+    ///           }))
+    ///   {
+    ///       insertion_sort(&mut input);
+    ///       prop_assert_eq!(sorted, input);
+    ///   }
+    /// }
+    /// #
+    /// # fn main() { test_insertion_sort(); }
+    /// ```
+    fn prop_with_oracle<O: fmt::Debug, F: Fn(&Self::Value) -> O>(
+        self,
+        oracle: F,
+    ) -> WithOracle<Self, F>
+    where
+        Self: Sized,
+    {
+        WithOracle {
+            source: self,
+            oracle: Arc::new(oracle),
+        }
+    }
+
+    /// Returns a strategy which generates the same values as `self`, grouped
+    /// by the key `key` computes for them.
+    ///
+    /// Call `prop_same_group()` on the result to generate pairs of values
+    /// which are equal under the key but may differ otherwise, e.g., to test
+    /// cache or memoization code. As with `prop_map()`, `key` should be
+    /// deterministic.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn test_case_insensitive_eq((a, b) in "[a-cA-C]{0,3}"
+    ///       .prop_group_by(|s| s.to_lowercase())
+    ///       .prop_same_group())
+    ///   {
+    ///       prop_assert!(a.eq_ignore_ascii_case(&b));
+    ///   }
+    /// }
+    /// #
+    /// # fn main() { test_case_insensitive_eq(); }
+    /// ```
+    fn prop_group_by<K: PartialEq, F: Fn(&Self::Value) -> K>(
+        self,
+        key: F,
+    ) -> GroupBy<Self, F>
+    where
+        Self: Sized,
+    {
+        GroupBy {
+            source: self,
+            key: Arc::new(key),
+        }
+    }
+
+    /// Returns a strategy which generates the values of `self` with an RNG
+    /// of its own, using `algorithm` regardless of `Config::rng_algorithm`.
+    ///
+    /// The RNG is seeded from the test runner's RNG, drawing the same amount
+    /// of data from it whatever `algorithm` is, so values generated from the
+    /// same runner before and after this one are unaffected by the choice of
+    /// algorithm. This allows, e.g., recording the bytes used by one part of
+    /// an input with `RngAlgorithm::Recorder` while the rest uses the
+    /// default algorithm. The RNG is available afterwards from
+    /// `WithRngValueTree::rng()`, and the recorded bytes can be replayed
+    /// with `prop_replay()`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `algorithm` is `RngAlgorithm::PassThrough`, since there
+    /// would be no data to pass through other than the seed. Use
+    /// `prop_replay()` to replay recorded bytes instead.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    /// use proptest::test_runner::{RngAlgorithm, TestRunner};
+    /// use proptest::strategy::ValueTree;
+    ///
+    /// # fn main() {
+    /// let header = any::<[u8; 4]>();
+    /// let mut runner = TestRunner::default();
+    /// let tree = header
+    ///     .prop_with_rng(RngAlgorithm::Recorder)
+    ///     .new_tree(&mut runner)
+    ///     .unwrap();
+    /// let bytes = tree.rng().bytes_used();
+    ///
+    /// let replay = header.prop_replay(&bytes).new_tree(&mut runner).unwrap();
+    /// assert_eq!(tree.current(), replay.current());
+    /// # }
+    /// ```
+    fn prop_with_rng(self, algorithm: RngAlgorithm) -> WithRng<Self>
+    where
+        Self: Sized,
+    {
+        assert!(
+            RngAlgorithm::PassThrough != algorithm,
+            "prop_with_rng() cannot use PassThrough; use prop_replay() instead"
+        );
+        WithRng {
+            source: self,
+            rng: RngSource::Algorithm(algorithm),
+        }
+    }
+
+    /// Returns a strategy which generates the values of `self` with a
+    /// `RngAlgorithm::PassThrough` RNG of its own, which passes through
+    /// `bytes`, e.g., as recorded with `prop_with_rng()` and
+    /// `RngAlgorithm::Recorder`.
+    ///
+    /// The same amount of data is drawn from the test runner's RNG as by
+    /// `prop_with_rng()`, so values generated from the same runner before
+    /// and after this one are the same as when the bytes were recorded.
+    /// Once `bytes` is exhausted, the RNG produces zeros.
+    fn prop_replay(self, bytes: &[u8]) -> WithRng<Self>
+    where
+        Self: Sized,
+    {
+        WithRng {
+            source: self,
+            rng: RngSource::Replay(bytes.into()),
+        }
+    }
+
+    /// Returns a strategy which generates at most `max_calls` values from
+    /// `self`, and fails to generate any more after that, e.g., to bound the
+    /// cost of generation embedded in a loop.
+    ///
+    /// The count is kept in an atomic counter shared by all clones of the
+    /// returned strategy, so the clones draw from the same budget, even
+    /// across threads. Shrinking a value already generated is not counted.
+    ///
+    /// Once the fuel is used up, `new_tree()` returns an error, which makes a
+    /// test runner abort the test.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    /// use proptest::test_runner::TestRunner;
+    ///
+    /// # fn main() {
+    /// let input = any::<u32>().prop_fuel(2);
+    /// let mut runner = TestRunner::default();
+    /// assert!(input.new_tree(&mut runner).is_ok());
+    /// assert!(input.new_tree(&mut runner).is_ok());
+    /// assert!(input.new_tree(&mut runner).is_err());
+    /// # }
+    /// ```
+    fn prop_fuel(self, max_calls: usize) -> Fuel<Self>
+    where
+        Self: Sized,
+    {
+        Fuel {
+            source: self,
+            used: Arc::new(AtomicUsize::new(0)),
+            max_calls,
+        }
+    }
+
+    /// Returns a strategy which passes every value of `self`, including those
+    /// produced while shrinking, through `fix`.
+    ///
+    /// `fix` should map any value to one satisfying some invariant, and leave
+    /// values which already satisfy it unchanged; for example, sorting a
+    /// tuple so that its first element is the smallest. Unlike
+    /// `prop_filter()`, no values are rejected, so no draws are wasted.
+    /// Unlike a general `prop_map()`, the value type is unchanged, and since
+    /// shrinking operates on the unrepaired value, each shrunk candidate is
+    /// repaired again before it is used.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// proptest! {
+    ///   fn test_something((lo, hi) in (0..100i32, 0..100i32).prop_constrain(
+    ///       |&(a, b)| (a.min(b), a.max(b))))
+    ///   {
+    ///       prop_assert!(lo <= hi);
+    ///   }
+    /// }
+    /// # fn main() { }
+    /// ```
+    fn prop_constrain<F: Fn(&Self::Value) -> Self::Value>(
+        self,
+        fix: F,
+    ) -> Constrain<Self, F>
+    where
+        Self: Sized,
+    {
+        Constrain {
+            source: self,
+            fix: Arc::new(fix),
+        }
+    }
+
+    /// Returns a strategy which generates sequences of states, starting from
+    /// a value of `self` and then applying `step` to each state to produce the
+    /// next, for a total number of states in `len`.
+    ///
+    /// This is useful for generating things like event logs or random walks
+    /// from a transition function. Unlike `prop_recursive()`, which builds
+    /// trees, each element depends only on the one before it.
+    ///
+    /// As with `prop_perturb()`, `step` is always called with an identical
+    /// sequence of random numbers for a given value tree. Shrinking first
+    /// removes states from the end of the sequence, down to the minimum of
+    /// `len`, then simplifies the initial state; later states are re-derived
+    /// from it each time.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn test_walk(walk in (0..100i32).prop_iterate(
+    ///       // Step up or down by one.
+    ///       |&pos, rng| if rng.gen() { pos + 1 } else { pos - 1 },
+    ///       1..32))
+    ///   {
+    ///       prop_assert!(walk.windows(2).all(|w| (w[0] - w[1]).abs() == 1));
+    ///   }
+    /// }
+    /// #
+    /// # fn main() { test_walk(); }
+    /// ```
+    fn prop_iterate<F: Fn(&Self::Value, &mut TestRng) -> Self::Value>(
+        self,
+        step: F,
+        len: impl Into<SizeRange>,
+    ) -> Iterate<Self, F>
+    where
+        Self: Sized,
+    {
+        Iterate {
+            source: self,
+            step: Arc::new(step),
+            len: len.into(),
+        }
+    }
+
     /// Maps values produced by this strategy into new strategies and picks
     /// values from those strategies.
     ///
@@ -248,6 +628,43 @@ pub trait Strategy: fmt::Debug {
         })
     }
 
+    /// Flattens a strategy which produces strategies into one which picks one
+    /// of those strategies and then produces values from it.
+    ///
+    /// This is equivalent to `prop_flat_map()` with the identity function,
+    /// and shrinks the same way: both the choice of inner strategy and the
+    /// value it produced shrink, with the search for a new failing value
+    /// after switching strategies limited by `Config::max_flat_map_regens`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// // E.g., chosen from configuration.
+    /// fn ranges() -> impl Strategy<Value = std::ops::Range<u32>> {
+    ///     prop_oneof![Just(0..10), Just(100..110)]
+    /// }
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn test_flattened(n in ranges().prop_flatten()) {
+    ///     prop_assert!(n < 10 || (100..110).contains(&n));
+    ///   }
+    /// }
+    /// #
+    /// # fn main() { test_flattened(); }
+    /// ```
+    fn prop_flatten(self) -> Flatten<Self>
+    where
+        Self: Sized,
+        Self::Value: Strategy,
+    {
+        Flatten::new(self)
+    }
+
     /// Maps values produced by this strategy into new strategies and picks
     /// values from those strategies while considering the new strategies to be
     /// independent.
@@ -292,6 +709,60 @@ pub trait Strategy: fmt::Debug {
         }
     }
 
+    /// Like `prop_flat_map()`, but remembers the derived value trees by the
+    /// input they were derived from.
+    ///
+    /// When `prop_flat_map()` shrinks the input, the derived strategy is
+    /// searched for a new failing value. If the new input turns out not to
+    /// be needed for the failure and shrinking moves back to an input it has
+    /// already seen, `prop_flat_map()` regenerates the derived value from
+    /// scratch, discarding whatever shrinking it had done for that input.
+    /// This combinator instead resumes from the derived value tree it left
+    /// behind, so the input and the derived value are shrunk together
+    /// rather than racing each other.
+    ///
+    /// This is mostly useful when the input has several components which
+    /// are removed and restored during shrinking, such as a collection, and
+    /// when `max_shrink_iters` is tight enough that redoing work matters.
+    /// The cache holds at most `Config::max_flat_map_regens` value trees and
+    /// regeneration shares the flat map regen counter with `prop_flat_map()`.
+    /// Since the input is compared to find cached trees, it must implement
+    /// `Clone` and `PartialEq`.
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn test_lengths(
+    ///     rows in prop::collection::vec(1..10usize, 1..5)
+    ///       .prop_ind_flat_map_cached(|lengths| lengths
+    ///         .into_iter()
+    ///         .map(|len| prop::collection::vec(any::<u8>(), len))
+    ///         .collect::<Vec<_>>())
+    ///   ) {
+    ///     prop_assert!(rows.iter().all(|row| !row.is_empty()));
+    ///   }
+    /// }
+    /// #
+    /// # fn main() { test_lengths(); }
+    /// ```
+    fn prop_ind_flat_map_cached<S: Strategy, F: Fn(Self::Value) -> S>(
+        self,
+        fun: F,
+    ) -> FlattenCached<Self, F>
+    where
+        Self: Sized,
+        Self::Value: Clone + PartialEq,
+    {
+        FlattenCached {
+            source: self,
+            fun: Arc::new(fun),
+        }
+    }
+
     /// Returns a strategy which only produces values accepted by `fun`.
     ///
     /// This results in a very naïve form of rejection sampling and should only
@@ -323,6 +794,57 @@ pub trait Strategy: fmt::Debug {
         Filter::new(self, whence.into(), fun)
     }
 
+    /// Returns a strategy which only produces values satisfying `predicate`,
+    /// as if every test using it started with `prop_assume!(predicate(&x))`.
+    ///
+    /// Like `prop_filter()`, this regenerates rejected values inside the
+    /// strategy, so they count against `max_local_rejects` rather than
+    /// `max_global_rejects`, and shrinking never hands the test a value the
+    /// assumption rejects. Like `prop_assume!`, the rejections are recorded
+    /// against `reason` prefixed with the file, line and column of the call,
+    /// so the runner's statistics show which assumption discarded them.
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// fn divisors() -> impl Strategy<Value = i32> {
+    ///     any::<i32>().prop_assume_inner("nonzero", |&d| d != 0)
+    /// }
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn remainder_is_smaller(n in any::<i32>(), d in divisors()) {
+    ///     // `i32::MIN % -1` overflows.
+    ///     if let Some(r) = n.checked_rem(d) {
+    ///       prop_assert!(r.unsigned_abs() < d.unsigned_abs());
+    ///     }
+    ///   }
+    /// }
+    /// #
+    /// # fn main() { remainder_is_smaller(); }
+    /// ```
+    #[track_caller]
+    fn prop_assume_inner<R: fmt::Display, F: Fn(&Self::Value) -> bool>(
+        self,
+        reason: R,
+        predicate: F,
+    ) -> Filter<Self, F>
+    where
+        Self: Sized,
+    {
+        let caller = core::panic::Location::caller();
+        let whence = format!(
+            "{}:{}:{}: {}",
+            caller.file(),
+            caller.line(),
+            caller.column(),
+            reason
+        );
+        Filter::new(self, whence.into(), predicate)
+    }
+
     /// Returns a strategy which only produces transformed values where `fun`
     /// returns `Some(value)` and rejects those where `fun` returns `None`.
     ///
@@ -357,6 +879,75 @@ pub trait Strategy: fmt::Debug {
         FilterMap::new(self, whence.into(), fun)
     }
 
+    /// Returns a strategy which only produces values whose serialized size,
+    /// as computed by `size_fn`, is at most `max_size`, e.g., to keep network
+    /// messages within an MTU.
+    ///
+    /// Unlike `prop_filter()`, an oversized value is not discarded right
+    /// away: it is simplified until it fits, and only rejected, against
+    /// `max_local_rejects`, if it cannot be simplified any further. As
+    /// simplified values are usually smaller, this biases generation towards
+    /// values which fit with room to spare, but rejects far fewer values
+    /// when most generated values would not fit. Shrinking only produces
+    /// values which fit.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn fits_in_a_packet(
+    ///     payload in prop::collection::vec(any::<u8>(), 0..512)
+    ///       .prop_filter_serialized_size(128, |p| 8 + p.len())
+    ///   ) {
+    ///     prop_assert!(payload.len() <= 120);
+    ///   }
+    /// }
+    /// #
+    /// # fn main() { fits_in_a_packet(); }
+    /// ```
+    fn prop_filter_serialized_size<F: Fn(&Self::Value) -> usize>(
+        self,
+        max_size: usize,
+        size_fn: F,
+    ) -> FilterSerializedSize<Self, F>
+    where
+        Self: Sized,
+    {
+        FilterSerializedSize {
+            source: self,
+            max_size,
+            size_fn: Arc::new(size_fn),
+        }
+    }
+
+    /// Returns a strategy which produces the values where `fun` returns
+    /// `Ok(value)` and rejects those where `fun` returns `Err(e)`.
+    ///
+    /// This behaves like `prop_filter_map()`, except that each rejection is
+    /// recorded against `"{whence}: {e}"` rather than just `whence`, so the
+    /// `Display` output of the error shows up in the runner's rejection
+    /// accounting. As with the other filtering methods, these are local
+    /// rejections, subject to `Config::max_local_rejects`.
+    fn prop_try_map<
+        F: Fn(Self::Value) -> Result<O, E>,
+        O: fmt::Debug,
+        E: fmt::Display,
+    >(
+        self,
+        whence: impl Into<Reason>,
+        fun: F,
+    ) -> TryMap<Self, F>
+    where
+        Self: Sized,
+    {
+        TryMap::new(self, whence.into(), fun)
+    }
+
     /// Returns a strategy which picks uniformly from `self` and `other`.
     ///
     /// When shrinking, if a value from `other` was originally chosen but that
@@ -401,6 +992,9 @@ pub trait Strategy: fmt::Debug {
     /// Shrinking shrinks both the inner values and attempts switching from
     /// recursive to non-recursive cases.
     ///
+    /// `Recursive::with_provenance()` pairs each value with whether it is a
+    /// leaf or a branch and how deep, e.g., to show this in failure reports.
+    ///
     /// ## Example
     ///
     /// ```rust,no_run
@@ -550,6 +1144,330 @@ pub trait Strategy: fmt::Debug {
     {
         NoShrink(self)
     }
+
+    /// Wraps this strategy so that shrinking tries `target` first.
+    ///
+    /// Shrinking normally heads towards the intrinsic minimum of the strategy
+    /// (`0`, the empty collection, etc.), which is not always the simplest
+    /// value in the domain of the code under test; for example, a canonical
+    /// configuration may be much easier to reason about than an empty one.
+    /// The first `simplify()` jumps directly to `target`. If the test still
+    /// fails, `target` is reported and shrinking stops there. Otherwise,
+    /// shrinking proceeds as for `self`, likewise stopping if it reaches
+    /// `target`.
+    ///
+    /// `target` need not be a value `self` could generate.
+    fn prop_shrink_toward(self, target: Self::Value) -> ShrinkToward<Self>
+    where
+        Self: Sized,
+        Self::Value: Clone + Eq,
+    {
+        ShrinkToward::new(self, target)
+    }
+
+    /// Wraps this strategy so that shrinking first tries `simplifier`, and
+    /// only falls back to shrinking `self` once it has nothing simpler to
+    /// offer.
+    ///
+    /// `simplifier` is given the current value and returns a simpler
+    /// candidate, or `None` if it knows of none. This allows injecting
+    /// knowledge about the domain, such as "try removing the last rule",
+    /// which element-wise shrinking cannot express. Each candidate which
+    /// still fails the test is simplified again in turn.
+    ///
+    /// As long as no candidate has been accepted, a candidate which passes
+    /// or a `None` makes shrinking continue as for `self`, and `simplifier`
+    /// is tried again on each value that produces. Since `self` knows
+    /// nothing about the values `simplifier` produces, shrinking stops once
+    /// a candidate has been accepted and `simplifier` has nothing simpler to
+    /// offer.
+    ///
+    /// `simplifier` must eventually return `None` when applied repeatedly to
+    /// its own results, e.g., by only ever returning strictly smaller values.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn rules_are_short(
+    ///       rules in prop::collection::vec("[a-z]{1,8}", 0..10)
+    ///           .prop_shrink_with(|rules| {
+    ///               let mut rules = rules.clone();
+    ///               rules.pop().map(|_| rules)
+    ///           })
+    ///   ) {
+    ///     prop_assert!(rules.concat().len() < 100);
+    ///   }
+    /// }
+    /// #
+    /// # fn main() { rules_are_short(); }
+    /// ```
+    fn prop_shrink_with<F: Fn(&Self::Value) -> Option<Self::Value>>(
+        self,
+        simplifier: F,
+    ) -> ShrinkWith<Self, F>
+    where
+        Self: Sized,
+        Self::Value: Clone,
+    {
+        ShrinkWith {
+            source: self,
+            simplifier: Arc::new(simplifier),
+        }
+    }
+
+    /// Returns a strategy which has a test runner try each of `examples`
+    /// before any random cases, and otherwise generates values from `self`.
+    ///
+    /// `TestRunner::run()` tries the examples on every run, after any
+    /// persisted failures. Like those, they don't count towards the number
+    /// of cases, and no seed can reproduce them, so a failing example is not
+    /// persisted. Generating values in any other way, e.g., by calling
+    /// `new_tree()` directly or when replaying a persisted failure, never
+    /// produces an example. The examples need not be values `self` could
+    /// generate.
+    ///
+    /// An example has no value tree of its own, so if it fails, shrinking
+    /// tries the values of `self` in its place, from one it generated down
+    /// to the one it ultimately shrinks to (`0`, the empty collection, etc.),
+    /// and continues shrinking from the first of them which fails. If all of
+    /// them pass, the example itself is reported.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn abs_is_non_negative(
+    ///       n in any::<i64>().prop_with_examples(vec![0, 1, -1, i64::MIN + 1])
+    ///   ) {
+    ///     prop_assert!(n.abs() >= 0);
+    ///   }
+    /// }
+    /// #
+    /// # fn main() { abs_is_non_negative(); }
+    /// ```
+    fn prop_with_examples(
+        self,
+        examples: Vec<Self::Value>,
+    ) -> WithExamples<Self>
+    where
+        Self: Sized,
+        Self::Value: Clone,
+    {
+        WithExamples {
+            source: self,
+            examples: Arc::new(examples),
+        }
+    }
+
+    /// Returns a strategy which pairs each value of `self` with a copy broken
+    /// by `corrupt`, as `(valid, invalid)`, e.g., to test that a validator
+    /// rejects invalid input.
+    ///
+    /// `corrupt` is given the valid value and an RNG, and should return a
+    /// copy with exactly one invariant violated. The invalid value is always
+    /// derived from the current valid value, so the two shrink together.
+    /// Each value tree keeps its own RNG, which is reset before every call to
+    /// `corrupt`, so the same valid value is always corrupted the same way.
+    ///
+    /// The invalid value is guaranteed to differ from the valid one. Values
+    /// which `corrupt` leaves unchanged are rejected as by `prop_filter()`
+    /// when generating, and skipped when shrinking.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// struct Settings {
+    ///     threads: u32,
+    ///     percent: u8,
+    /// }
+    ///
+    /// fn is_valid(settings: &Settings) -> bool {
+    ///     (1..=64).contains(&settings.threads) && settings.percent <= 100
+    /// }
+    ///
+    /// fn valid_settings() -> impl Strategy<Value = Settings> {
+    ///     (1..=64u32, 0..=100u8)
+    ///         .prop_map(|(threads, percent)| Settings { threads, percent })
+    /// }
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn validator_rejects_out_of_range_fields(
+    ///       (valid, invalid) in valid_settings().prop_corrupt(|s, rng| {
+    ///           let mut s = s.clone();
+    ///           if rng.gen() {
+    ///               s.threads = rng.gen_range(65..1000);
+    ///           } else {
+    ///               s.percent = rng.gen_range(101..=255);
+    ///           }
+    ///           s
+    ///       })
+    ///   ) {
+    ///     prop_assert!(is_valid(&valid));
+    ///     prop_assert!(!is_valid(&invalid));
+    ///   }
+    /// }
+    /// #
+    /// # fn main() { validator_rejects_out_of_range_fields(); }
+    /// ```
+    fn prop_corrupt<F: Fn(&Self::Value, &mut TestRng) -> Self::Value>(
+        self,
+        corrupt: F,
+    ) -> Corrupt<Self, F>
+    where
+        Self: Sized,
+        Self::Value: PartialEq,
+    {
+        Corrupt {
+            source: self,
+            corrupt: Arc::new(corrupt),
+        }
+    }
+
+    /// Returns a strategy which produces each value of this strategy twice,
+    /// as a pair.
+    ///
+    /// Both values are built by separate calls to `ValueTree::current()` on
+    /// the same value tree, so they are equal as far as `self` is concerned
+    /// without being clones of each other. This is useful for checking that
+    /// `Eq` and `Hash`, or similar pairs of traits, are consistent. The pair
+    /// shrinks as a single value.
+    fn prop_duplicate(self) -> Duplicate<Self>
+    where
+        Self: Sized,
+    {
+        Duplicate(self)
+    }
+
+    /// Wraps the values of this strategy in `Reported`, so that failure
+    /// messages describe them with `report` instead of their `Debug` output.
+    ///
+    /// This is useful when the `Debug` output is unwieldy or hides the
+    /// relevant information. To generate values of a type which does not
+    /// implement `Debug` at all, construct `Reported` values directly, e.g.,
+    /// with `prop_map()`.
+    fn prop_report_with<F>(self, report: F) -> ReportWith<Self>
+    where
+        Self: Sized,
+        F: Fn(&Self::Value) -> String + Send + Sync + 'static,
+    {
+        ReportWith::new(self, report)
+    }
+
+    /// Wraps this strategy so that each generated value is counted in `sink`
+    /// under the key returned by `bucket_fn`.
+    ///
+    /// This is a diagnostic aid for checking the distribution of a strategy,
+    /// e.g., that a `prop_oneof!` picks its options in the expected
+    /// proportions. Values are passed through unchanged, and only values
+    /// produced by `new_tree()` are counted, not those seen while shrinking.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// use proptest::prelude::*;
+    /// use proptest::strategy::ValueTree;
+    /// use proptest::test_runner::TestRunner;
+    ///
+    /// let sink = Arc::new(Mutex::new(HashMap::new()));
+    /// let strategy = (0..100u32).prop_tally(|&v| v % 2 == 0, sink.clone());
+    ///
+    /// let mut runner = TestRunner::deterministic();
+    /// for _ in 0..1000 {
+    ///     strategy.new_tree(&mut runner).unwrap();
+    /// }
+    /// let evens = sink.lock().unwrap()[&true];
+    /// assert!(evens > 400 && evens < 600);
+    /// ```
+    #[cfg(feature = "std")]
+    fn prop_tally<K: Hash + Eq, F: Fn(&Self::Value) -> K>(
+        self,
+        bucket_fn: F,
+        sink: Arc<Mutex<HashMap<K, u64>>>,
+    ) -> Tally<Self, F, K>
+    where
+        Self: Sized,
+    {
+        Tally::new(self, bucket_fn, sink)
+    }
+
+    /// Returns a strategy which rejects values of `self` that take longer
+    /// than `timeout` to generate, and generates new ones instead, e.g., to
+    /// guard against a sub-strategy which is occasionally very slow, such as
+    /// one built from a complex regex.
+    ///
+    /// Only the time spent in `new_tree()` of `self` is measured, so the
+    /// timeout applies to this sub-strategy alone, not to the whole test
+    /// case. Rejected values count as local rejections attributed to this
+    /// timeout; as with `prop_filter()`, the test is aborted if there are
+    /// too many of them. Shrinking is unaffected.
+    ///
+    /// This is only available with the `std` feature.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use proptest::prelude::*;
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn identifiers_are_short(
+    ///       s in "[a-z]{1,8}(_[a-z0-9]{1,8}){0,3}"
+    ///           .prop_gen_timeout(Duration::from_millis(100))
+    ///   ) {
+    ///     prop_assert!(s.len() <= 35);
+    ///   }
+    /// }
+    /// #
+    /// # fn main() { identifiers_are_short(); }
+    /// ```
+    #[cfg(feature = "std")]
+    fn prop_gen_timeout(self, timeout: Duration) -> GenTimeout<Self>
+    where
+        Self: Sized,
+    {
+        GenTimeout {
+            source: self,
+            timeout,
+        }
+    }
+
+    /// Wraps this strategy so that every call to `current()`, `simplify()`
+    /// and `complicate()` on its value trees is logged, together with `label`
+    /// and the resulting value.
+    ///
+    /// This is intended for diagnosing custom strategies, for example to find
+    /// out why shrinking stalls. Messages go to `Config::telemetry` of the
+    /// runner that created the value tree, or to standard error if that is
+    /// `None`. Apart from logging, the wrapped strategy behaves exactly like
+    /// `self`.
+    fn prop_debug_tree(self, label: impl Into<Reason>) -> DebugTree<Self>
+    where
+        Self: Sized,
+    {
+        DebugTree::new(self, label.into())
+    }
 }
 
 /// A generated value and its associated shrinker.
@@ -719,6 +1637,13 @@ impl<T> Clone for BoxedStrategy<T> {
     }
 }
 
+impl<T> SBoxedStrategy<T> {
+    /// Returns whether `self` and `other` share the same underlying strategy.
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::as_ptr(&self.0) as *const () == Arc::as_ptr(&other.0) as *const ()
+    }
+}
+
 impl<T> Clone for SBoxedStrategy<T> {
     fn clone(&self) -> Self {
         SBoxedStrategy(Arc::clone(&self.0))
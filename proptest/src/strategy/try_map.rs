@@ -0,0 +1,158 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{fmt, Arc};
+
+use crate::strategy::filter_map::{FilterMapFn, FilterMapValueTree};
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// `Strategy` and `ValueTree` try_map adaptor.
+///
+/// See `Strategy::prop_try_map()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct TryMap<S, F> {
+    pub(super) source: S,
+    pub(super) whence: Reason,
+    pub(super) fun: Arc<TryMapFn<F>>,
+}
+
+impl<S, F> TryMap<S, F> {
+    pub(super) fn new(source: S, whence: Reason, fun: F) -> Self {
+        Self {
+            source,
+            whence,
+            fun: Arc::new(TryMapFn(fun)),
+        }
+    }
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for TryMap<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TryMap")
+            .field("source", &self.source)
+            .field("whence", &self.whence)
+            .field("fun", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for TryMap<S, F> {
+    fn clone(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            whence: self.whence.clone(),
+            fun: Arc::clone(&self.fun),
+        }
+    }
+}
+
+impl<
+        S: Strategy,
+        F: Fn(S::Value) -> Result<O, E>,
+        O: fmt::Debug,
+        E: fmt::Display,
+    > Strategy for TryMap<S, F>
+{
+    type Tree = TryMapValueTree<S::Tree, F, O>;
+    type Value = O;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        loop {
+            let val = self.source.new_tree(runner)?;
+            match (self.fun.0)(val.current()) {
+                Ok(current) => {
+                    return Ok(TryMapValueTree::new(val, &self.fun, current))
+                }
+                Err(e) => {
+                    runner.reject_local(format!("{}: {}", self.whence, e))?
+                }
+            }
+        }
+    }
+}
+
+/// `ValueTree` corresponding to `TryMap`.
+///
+/// This is the `FilterMapValueTree` shared with `prop_filter_map()`; only
+/// the way rejections are reported while generating differs.
+pub type TryMapValueTree<V, F, O> = FilterMapValueTree<V, TryMapFn<F>, O>;
+
+/// Adapts a fallible mapping function to a `FilterMapFn` by discarding its
+/// error.
+///
+/// See `TryMap`.
+pub struct TryMapFn<F>(F);
+
+impl<T, O, E, F: Fn(T) -> Result<O, E>> FilterMapFn<T> for TryMapFn<F> {
+    type Output = O;
+
+    fn filter_map(&self, value: T) -> Option<O> {
+        (self.0)(value).ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::std_facade::String;
+
+    fn parse_even(v: u32) -> Result<u32, String> {
+        if 0 == v % 2 {
+            Ok(v / 2)
+        } else {
+            Err(format!("{} is odd", v))
+        }
+    }
+
+    #[test]
+    fn test_try_map() {
+        let input = (0u32..256).prop_try_map("halve", parse_even);
+
+        for _ in 0..256 {
+            let mut runner = TestRunner::default();
+            let mut case = input.new_tree(&mut runner).unwrap();
+
+            assert!(case.current() < 128);
+            while case.simplify() {
+                assert!(case.current() < 128);
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_map_records_error_as_rejection_reason() {
+        let input = (1u32..2).prop_try_map("halve", parse_even);
+        let mut runner = TestRunner::new(Config {
+            max_local_rejects: 3,
+            ..Config::default()
+        });
+
+        assert!(input.new_tree(&mut runner).is_err());
+        let accounting = format!("{}", runner);
+        assert!(
+            accounting.contains("3 times at halve: 1 is odd"),
+            "Unexpected rejection accounting:\n{}",
+            accounting
+        );
+    }
+
+    #[test]
+    fn test_try_map_sanity() {
+        check_strategy_sanity(
+            (0u32..256).prop_try_map("halve", parse_even),
+            Some(CheckStrategySanityOptions {
+                // Due to internal rejection sampling, `simplify()` can
+                // converge back to what `complicate()` would do.
+                strict_complicate_after_simplify: false,
+                ..CheckStrategySanityOptions::default()
+            }),
+        );
+    }
+}
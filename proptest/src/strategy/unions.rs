@@ -51,7 +51,7 @@ impl<T: Strategy> Union<T> {
     pub fn new(options: impl IntoIterator<Item = T>) -> Self {
         let options: Vec<WA<T>> =
             options.into_iter().map(|v| (1, Arc::new(v))).collect();
-        assert!(!options.is_empty());
+        assert!(!options.is_empty(), "Union requires at least one option");
         Self { options }
     }
 
@@ -62,7 +62,7 @@ impl<T: Strategy> Union<T> {
             .map(|r| r.map(|v| (1, Arc::new(v))))
             .collect::<Result<_, _>>()?;
 
-        assert!(!options.is_empty());
+        assert!(!options.is_empty(), "Union requires at least one option");
         Ok(Self { options })
     }
 
@@ -73,13 +73,29 @@ impl<T: Strategy> Union<T> {
     /// weight of 2 will be chosen twice as frequently as one with a weight of
     /// 1\.
     ///
+    /// Unlike `prop_oneof!`, the number of options need not be known at
+    /// compile time, so this can build a union from runtime data. Options of
+    /// different types can be combined by boxing them first.
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    /// use proptest::strategy::Union;
+    ///
+    /// // E.g., loaded from a configuration file.
+    /// let alternatives = vec![(3, "[a-z]+"), (1, "[0-9]+")];
+    /// let strategy = Union::new_weighted(alternatives.into_iter().map(
+    ///     |(weight, pattern)| (weight, pattern.boxed())));
+    /// # let _ = strategy;
+    /// ```
+    ///
     /// ## Panics
     ///
     /// Panics if `options` is empty or any element has a weight of 0.
     ///
     /// Panics if the sum of the weights overflows a `u32`.
-    pub fn new_weighted(options: Vec<W<T>>) -> Self {
-        assert!(!options.is_empty());
+    pub fn new_weighted(options: impl IntoIterator<Item = W<T>>) -> Self {
+        let options: Vec<W<T>> = options.into_iter().collect();
+        assert!(!options.is_empty(), "Union requires at least one option");
         assert!(
             !options.iter().any(|&(w, _)| 0 == w),
             "Union option has a weight of 0"
@@ -94,6 +110,48 @@ impl<T: Strategy> Union<T> {
         Self { options }
     }
 
+    /// Create a strategy which selects from those of the given delegate
+    /// strategies which are enabled, as in `new_weighted()`.
+    ///
+    /// Each option is given as `(enabled, weight, strategy)`, and options
+    /// which are not enabled are dropped. This allows turning some
+    /// alternatives on and off at runtime, e.g., from a configuration, without
+    /// restructuring how the union is built.
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    /// use proptest::strategy::Union;
+    ///
+    /// // E.g., from a configuration file.
+    /// let unicode_enabled = false;
+    /// let strategy = Union::new_optional(vec![
+    ///     (true, 3, "[a-z]+".boxed()),
+    ///     (unicode_enabled, 1, "\\PC+".boxed()),
+    /// ]);
+    /// # let _ = strategy;
+    /// ```
+    ///
+    /// ## Panics
+    ///
+    /// Panics if no option is enabled or any enabled option has a weight of 0.
+    ///
+    /// Panics if the sum of the weights of the enabled options overflows a
+    /// `u32`.
+    pub fn new_optional(
+        options: impl IntoIterator<Item = (bool, u32, T)>,
+    ) -> Self {
+        let options: Vec<W<T>> = options
+            .into_iter()
+            .filter(|&(enabled, _, _)| enabled)
+            .map(|(_, w, v)| (w, v))
+            .collect();
+        assert!(
+            !options.is_empty(),
+            "Union requires at least one enabled option"
+        );
+        Self::new_weighted(options)
+    }
+
     /// Add `other` as an additional alternate strategy with weight 1.
     pub fn or(mut self, other: T) -> Self {
         self.options.push((1, Arc::new(other)));
@@ -495,8 +553,8 @@ mod test {
 
             match result {
                 Ok(true) => passed += 1,
-                Err(TestError::Fail(_, 15)) => converged_low += 1,
-                Err(TestError::Fail(_, 30)) => converged_high += 1,
+                Err(TestError::Fail(_, 15, _, _)) => converged_low += 1,
+                Err(TestError::Fail(_, 30, _, _)) => converged_high += 1,
                 e => panic!("Unexpected result: {:?}", e),
             }
         }
@@ -535,6 +593,84 @@ mod test {
         assert!(counts[1] > counts[2] * 3 / 2);
     }
 
+    #[test]
+    fn float_to_weight_never_starves_extreme_probabilities() {
+        let denormal = f64::from_bits(1);
+        for &f in &[1e-20, f64::MIN_POSITIVE, denormal, 1.0 - f64::EPSILON] {
+            let (pos, neg) = float_to_weight(f);
+            assert!(pos > 0 && neg > 0, "{} -> {:?}", f, (pos, neg));
+            assert_eq!(WEIGHT_BASE, pos + neg);
+        }
+        assert_eq!((1, WEIGHT_BASE - 1), float_to_weight(1e-20));
+    }
+
+    #[test]
+    fn test_union_weighted_picks_rare_option_at_its_share() {
+        let input =
+            Union::new_weighted(vec![(1, Just(true)), (255, Just(false))]);
+
+        let mut runner = TestRunner::deterministic();
+        let rare = (0..65536)
+            .filter(|_| input.new_tree(&mut runner).unwrap().current())
+            .count();
+        // Expect 256.
+        assert!(rare > 192 && rare < 320, "{}", rare);
+    }
+
+    #[test]
+    fn test_union_weighted_from_runtime_options() {
+        let config = [(1, 0u32), (2, 100), (1, 200)];
+        let options: Vec<(u32, BoxedStrategy<u32>)> = config
+            .iter()
+            .map(|&(weight, base)| (weight, (base..base + 10).boxed()))
+            .collect();
+        let input = Union::new_weighted(options);
+
+        let mut counts = [0, 0, 0];
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..4096 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            counts[tree.current() as usize / 100] += 1;
+            while tree.simplify() {}
+            assert_eq!(0, tree.current());
+        }
+
+        assert!(counts.iter().all(|&count| count > 0), "{:?}", counts);
+        assert!(counts[1] > counts[0] * 3 / 2, "{:?}", counts);
+        assert!(counts[1] > counts[2] * 3 / 2, "{:?}", counts);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one option")]
+    fn test_union_weighted_rejects_empty_options() {
+        let _ = Union::<Just<u32>>::new_weighted(vec![]);
+    }
+
+    #[test]
+    fn test_union_optional_drops_disabled_options() {
+        for &enabled in &[false, true] {
+            let input = Union::new_optional(vec![
+                (true, 1, Just(0u32).boxed()),
+                (enabled, 1, Just(1u32).boxed()),
+                (true, 1, (2u32..4).boxed()),
+            ]);
+
+            let mut seen = [false; 4];
+            let mut runner = TestRunner::deterministic();
+            for _ in 0..1024 {
+                seen[input.new_tree(&mut runner).unwrap().current() as usize] =
+                    true;
+            }
+            assert_eq!([true, enabled, true, true], seen);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one enabled option")]
+    fn test_union_optional_rejects_all_disabled() {
+        let _ = Union::new_optional(vec![(false, 1, Just(0u32))]);
+    }
+
     #[test]
     fn test_union_sanity() {
         check_strategy_sanity(
@@ -572,8 +708,8 @@ mod test {
 
             match result {
                 Ok(true) => passed += 1,
-                Err(TestError::Fail(_, 15)) => converged_low += 1,
-                Err(TestError::Fail(_, 30)) => converged_high += 1,
+                Err(TestError::Fail(_, 15, _, _)) => converged_low += 1,
+                Err(TestError::Fail(_, 30, _, _)) => converged_high += 1,
                 e => panic!("Unexpected result: {:?}", e),
             }
         }
@@ -0,0 +1,171 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::mem;
+
+use crate::std_facade::Arc;
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// `Strategy` adaptor which generates values from its source with an RNG
+/// of its own.
+///
+/// See `Strategy::prop_with_rng()` and `Strategy::prop_replay()`.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct WithRng<S> {
+    pub(super) source: S,
+    pub(super) rng: RngSource,
+}
+
+/// Where `WithRng` gets the RNG of its source from.
+#[derive(Clone, Debug)]
+pub(super) enum RngSource {
+    /// A new RNG of the given algorithm, seeded from the test runner's RNG.
+    Algorithm(RngAlgorithm),
+    /// A `PassThrough` RNG replaying the given bytes.
+    Replay(Arc<[u8]>),
+}
+
+impl<S: Strategy> Strategy for WithRng<S> {
+    type Tree = WithRngValueTree<S::Tree>;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let child = match self.rng {
+            RngSource::Algorithm(algorithm) => {
+                runner.rng().gen_rng_with(algorithm)
+            }
+            RngSource::Replay(ref bytes) => {
+                // Still draw the seed, so the rest of the input is the same
+                // as when the bytes were recorded.
+                runner.rng().gen_rng_with(RngAlgorithm::XorShift);
+                TestRng::from_seed(RngAlgorithm::PassThrough, bytes)
+            }
+        };
+        let parent = mem::replace(runner.rng(), child);
+        let source = self.source.new_tree(runner);
+        let rng = mem::replace(runner.rng(), parent);
+        Ok(WithRngValueTree {
+            source: source?,
+            rng,
+        })
+    }
+}
+
+/// `ValueTree` corresponding to `WithRng`.
+#[derive(Clone, Debug)]
+pub struct WithRngValueTree<T> {
+    source: T,
+    rng: TestRng,
+}
+
+impl<T> WithRngValueTree<T> {
+    /// Returns the RNG the value was generated with, e.g., to get the
+    /// `bytes_used()` by a `RngAlgorithm::Recorder`.
+    pub fn rng(&self) -> &TestRng {
+        &self.rng
+    }
+}
+
+impl<T: ValueTree> ValueTree for WithRngValueTree<T> {
+    type Value = T::Value;
+
+    fn current(&self) -> T::Value {
+        self.source.current()
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.source.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.source.complicate()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::collection::vec;
+    use crate::std_facade::Vec;
+
+    fn generate<S: Strategy>(strategy: &S) -> Vec<S::Value> {
+        let mut runner = TestRunner::deterministic();
+        (0..16)
+            .map(|_| strategy.new_tree(&mut runner).unwrap().current())
+            .collect()
+    }
+
+    #[test]
+    fn sub_strategy_rng_is_independent_and_reproducible() {
+        let element = vec(0..1000u32, 4);
+        let chacha = (
+            element.clone().prop_with_rng(RngAlgorithm::ChaCha),
+            0..1000u32,
+        );
+        let xorshift = (
+            element.clone().prop_with_rng(RngAlgorithm::XorShift),
+            0..1000u32,
+        );
+
+        let (with_chacha, rest_chacha): (Vec<_>, Vec<_>) =
+            generate(&chacha).into_iter().unzip();
+        let (with_xorshift, rest_xorshift): (Vec<_>, Vec<_>) =
+            generate(&xorshift).into_iter().unzip();
+        // The sub-strategy's values depend on its algorithm, but the rest
+        // of the values don't.
+        assert_ne!(with_chacha, with_xorshift);
+        assert_eq!(rest_chacha, rest_xorshift);
+        assert_eq!(generate(&chacha), generate(&chacha));
+    }
+
+    #[test]
+    fn recorded_sub_strategy_replays() {
+        let element = vec(0..1000u32, 4);
+        let input = element.clone().prop_with_rng(RngAlgorithm::Recorder);
+        let mut runner = TestRunner::deterministic();
+        let mut replay_runner = TestRunner::default();
+        for _ in 0..16 {
+            let tree = input.new_tree(&mut runner).unwrap();
+            let bytes = tree.rng().bytes_used();
+            assert!(!bytes.is_empty());
+
+            // The replayed value depends only on the bytes, not on the
+            // state of the runner.
+            let replay = element.clone().prop_replay(&bytes);
+            assert_eq!(
+                tree.current(),
+                replay.new_tree(&mut replay_runner).unwrap().current()
+            );
+        }
+    }
+
+    #[test]
+    fn replay_leaves_rest_of_input_alone() {
+        let element = vec(0..1000u32, 4);
+        let recorded = (
+            element.clone().prop_with_rng(RngAlgorithm::Recorder),
+            0..1000u32,
+        );
+        let replayed = (element.prop_replay(&[1, 2, 3]), 0..1000u32);
+
+        let (_, rest_recorded): (Vec<_>, Vec<_>) =
+            generate(&recorded).into_iter().unzip();
+        let (_, rest_replayed): (Vec<_>, Vec<_>) =
+            generate(&replayed).into_iter().unzip();
+        assert_eq!(rest_recorded, rest_replayed);
+    }
+
+    #[test]
+    #[should_panic(expected = "prop_replay")]
+    fn pass_through_is_rejected() {
+        let _ = (0..1000u32).prop_with_rng(RngAlgorithm::PassThrough);
+    }
+}
@@ -8,20 +8,24 @@
 // except according to those terms.
 
 //! Strategies for generating strings and byte strings from regular
-//! expressions.
+//! expressions, as well as a few kinds of structured strings such as domain
+//! names and URLs.
 
 use crate::std_facade::{Box, Cow, String, ToOwned, Vec};
 use core::fmt;
 use core::mem;
 use core::ops::RangeInclusive;
+use core::slice;
 use core::u32;
 
 use regex_syntax::hir::{self, Hir, HirKind::*, Repetition};
 use regex_syntax::{Error as ParseError, ParserBuilder};
 
+use crate::arbitrary::any;
 use crate::bool;
 use crate::char;
 use crate::collection::{size_range, vec, SizeRange};
+use crate::sample::{select, Index};
 use crate::strategy::*;
 use crate::test_runner::*;
 
@@ -357,6 +361,815 @@ fn unsupported<T>(error: &'static str) -> Result<T, Error> {
     Err(Error::UnsupportedRegex(error))
 }
 
+//==============================================================================
+// Structured strings
+//==============================================================================
+
+opaque_strategy_wrapper! {
+    /// Strategy which generates structurally valid domain names, email
+    /// addresses or URLs without going through a regular expression.
+    ///
+    /// Created by `domain_name()`, `email()` and `http_url()`.
+    #[derive(Debug)]
+    pub struct StructuredStringStrategy[][]
+        (SBoxedStrategy<String>) -> StructuredStringValueTree;
+    /// `ValueTree` corresponding to `StructuredStringStrategy`.
+    pub struct StructuredStringValueTree[][]
+        (Box<dyn ValueTree<Value = String>>) -> String;
+}
+
+/// Top-level domains used by `domain_name()`, shortest first.
+static TLDS: &[&str] = &["io", "com", "org", "net", "dev", "example"];
+
+/// Schemes used by `http_url()`.
+static URL_SCHEMES: &[&str] = &["http", "https"];
+
+static LABEL_CHARS: &[RangeInclusive<char>] = &['a'..='z', '0'..='9'];
+
+static LOCAL_PART_CHARS: &[RangeInclusive<char>] = &[
+    'a'..='z',
+    '0'..='9',
+    'A'..='Z',
+    '!'..='!',
+    '#'..='\'',
+    '*'..='+',
+    '-'..='-',
+    '/'..='/',
+    '='..='=',
+    '?'..='?',
+    '^'..='`',
+    '{'..='~',
+];
+
+static PATH_CHARS: &[RangeInclusive<char>] = &[
+    'a'..='z',
+    '0'..='9',
+    'A'..='Z',
+    '-'..='-',
+    '_'..='_',
+    '~'..='~',
+];
+
+fn chars(
+    ranges: &'static [RangeInclusive<char>],
+    size: impl Into<SizeRange>,
+) -> impl Strategy<Value = String> {
+    vec(char::ranges(Cow::Borrowed(ranges)), size)
+        .prop_map(|chars| chars.into_iter().collect())
+}
+
+/// A DNS label of 1 to 15 characters which neither starts nor ends with a
+/// hyphen.
+fn domain_label() -> impl Strategy<Value = String> {
+    let alnum = || char::ranges(Cow::Borrowed(LABEL_CHARS));
+    (alnum(), vec((bool::weighted(0.1), alnum()), 0..8)).prop_map(
+        |(first, rest)| {
+            let mut label = String::new();
+            label.push(first);
+            for (hyphen, c) in rest {
+                if hyphen {
+                    label.push('-');
+                }
+                label.push(c);
+            }
+            label
+        },
+    )
+}
+
+fn domain_name_strategy() -> impl Strategy<Value = String> {
+    (vec(domain_label(), 1..4), crate::sample::select(TLDS)).prop_map(
+        |(labels, tld)| {
+            let mut name = labels.join(".");
+            name.push('.');
+            name.push_str(tld);
+            name
+        },
+    )
+}
+
+/// Creates a strategy which generates domain names such as `a1.b-c.com`.
+///
+/// Names consist of one to three lowercase alphanumeric labels, which may
+/// contain (but never start or end with) hyphens, followed by a top-level
+/// domain from a small fixed list. Values shrink towards the shortest such
+/// name, e.g. `a.io`.
+pub fn domain_name() -> StructuredStringStrategy {
+    StructuredStringStrategy(domain_name_strategy().sboxed())
+}
+
+/// Creates a strategy which generates email addresses such as
+/// `john.d+oe@a1.example`.
+///
+/// The local part is one to three dot-separated runs of the characters RFC
+/// 5322 allows in an unquoted local part; the domain is as generated by
+/// `domain_name()`. Values shrink towards the shortest such address, e.g.
+/// `a@a.io`.
+pub fn email() -> StructuredStringStrategy {
+    StructuredStringStrategy(
+        (
+            vec(chars(LOCAL_PART_CHARS, 1..9), 1..4),
+            domain_name_strategy(),
+        )
+            .prop_map(|(local, domain)| {
+                format!("{}@{}", local.join("."), domain)
+            })
+            .sboxed(),
+    )
+}
+
+/// Creates a strategy which generates HTTP(S) URLs such as
+/// `https://a.b.io:8080/x/y~z`.
+///
+/// URLs consist of a scheme, a host as generated by `domain_name()`, an
+/// optional port and up to three path segments of unreserved characters
+/// (excluding `.`, so that there are no `.` or `..` segments).
+/// Values shrink towards the shortest such URL, e.g. `http://a.io`.
+pub fn http_url() -> StructuredStringStrategy {
+    StructuredStringStrategy(
+        (
+            crate::sample::select(URL_SCHEMES),
+            domain_name_strategy(),
+            crate::option::of(1u16..),
+            vec(chars(PATH_CHARS, 1..9), 0..4),
+        )
+            .prop_map(|(scheme, host, port, path)| {
+                let mut url = format!("{}://{}", scheme, host);
+                if let Some(port) = port {
+                    url.push_str(&format!(":{}", port));
+                }
+                for segment in path {
+                    url.push('/');
+                    url.push_str(&segment);
+                }
+                url
+            })
+            .sboxed(),
+    )
+}
+
+opaque_strategy_wrapper! {
+    /// Strategy which generates sequences of UTF-16 code units.
+    ///
+    /// Created by `utf16()`.
+    #[derive(Debug)]
+    pub struct Utf16Strategy[][]
+        (SBoxedStrategy<Vec<u16>>) -> Utf16ValueTree;
+    /// `ValueTree` corresponding to `Utf16Strategy`.
+    pub struct Utf16ValueTree[][]
+        (Box<dyn ValueTree<Value = Vec<u16>>>) -> Vec<u16>;
+}
+
+/// Creates a strategy which generates UTF-16 text as `Vec<u16>`, such as is
+/// passed to Windows APIs or exchanged with JavaScript.
+///
+/// The text is made up of a number of elements within `size`, each of which
+/// is a `char` (one code unit, or a surrogate pair for characters outside the
+/// Basic Multilingual Plane). If `allow_lone_surrogates` is `true`, some
+/// elements are instead single unpaired surrogates, so the result is not
+/// necessarily valid UTF-16; otherwise it always is.
+///
+/// Shrinking removes elements and simplifies the remaining ones. Lone
+/// surrogates shrink towards ordinary characters.
+pub fn utf16(
+    size: impl Into<SizeRange>,
+    allow_lone_surrogates: bool,
+) -> Utf16Strategy {
+    let scalar = char::any().prop_map(|c| {
+        let mut buf = [0u16; 2];
+        c.encode_utf16(&mut buf).to_vec()
+    });
+    let element = if allow_lone_surrogates {
+        prop_oneof![
+            7 => scalar,
+            1 => (0xD800u16..=0xDFFF).prop_map(|unit| vec![unit]),
+        ]
+        .sboxed()
+    } else {
+        scalar.sboxed()
+    };
+
+    Utf16Strategy(vec(element, size).prop_map(|v| v.concat()).sboxed())
+}
+
+opaque_strategy_wrapper! {
+    /// Strategy which generates balanced or unbalanced bracket sequences.
+    ///
+    /// Created by `brackets()`.
+    #[derive(Debug)]
+    pub struct BracketsStrategy[][]
+        (BoxedStrategy<String>) -> BracketsValueTree;
+    /// `ValueTree` corresponding to `BracketsStrategy`.
+    pub struct BracketsValueTree[][]
+        (Box<dyn ValueTree<Value = String>>) -> String;
+}
+
+/// A bracket pair, given by its index in the alphabet, enclosing a sequence
+/// of further groups.
+#[derive(Clone, Debug)]
+struct BracketGroup(usize, Vec<BracketGroup>);
+
+/// A modification which makes a balanced sequence unbalanced.
+#[derive(Clone, Copy, Debug)]
+enum BracketDefect {
+    /// Insert a lone opening (`false`) or closing (`true`) bracket of the
+    /// given pair, which leaves an odd number of brackets.
+    Insert(usize, bool, Index),
+    /// Replace a closing bracket with that of a different pair.
+    Mismatch(Index, Index),
+}
+
+fn write_brackets(groups: &[BracketGroup], out: &mut Vec<(usize, bool)>) {
+    for &BracketGroup(pair, ref inner) in groups {
+        out.push((pair, false));
+        write_brackets(inner, out);
+        out.push((pair, true));
+    }
+}
+
+/// Creates a strategy which generates sequences of the brackets in
+/// `alphabet`, given as pairs of opening and closing characters, nested at
+/// most `max_depth` levels deep.
+///
+/// If `balanced` is `true`, every opening bracket is closed by the closing
+/// bracket of the same pair, and in the right order. Otherwise, every
+/// sequence is unbalanced: either a lone opening or closing bracket is
+/// inserted into a balanced sequence (making it at most `max_depth + 1`
+/// levels deep), or, if `alphabet` has more than one pair, one closing
+/// bracket is replaced by that of a different pair.
+///
+/// Shrinking removes groups of brackets and reduces the nesting depth. The
+/// sequence stays balanced, or unbalanced, throughout; unbalanced sequences
+/// shrink towards a single bracket.
+///
+/// ## Panics
+///
+/// Panics if `alphabet` is empty or contains a character more than once.
+pub fn brackets(
+    alphabet: &[(char, char)],
+    balanced: bool,
+    max_depth: u32,
+) -> BracketsStrategy {
+    assert!(
+        !alphabet.is_empty(),
+        "brackets() requires at least one pair"
+    );
+    let chars: Vec<char> = alphabet
+        .iter()
+        .flat_map(|&(open, close)| vec![open, close])
+        .collect();
+    for (ix, c) in chars.iter().enumerate() {
+        assert!(
+            !chars[..ix].contains(c),
+            "Bracket {:?} appears more than once in the alphabet",
+            c
+        );
+    }
+
+    let pairs = alphabet.len();
+    let groups =
+        Just(Vec::new()).prop_recursive(max_depth, 32, 4, move |inner| {
+            vec(
+                (0..pairs, inner)
+                    .prop_map(|(pair, inner)| BracketGroup(pair, inner)),
+                1..4,
+            )
+        });
+    let alphabet = alphabet.to_vec();
+    let to_string = move |(groups, defect): (Vec<BracketGroup>, _)| {
+        let mut brackets = Vec::new();
+        write_brackets(&groups, &mut brackets);
+        match defect {
+            None => (),
+            Some(BracketDefect::Mismatch(close, offset))
+                if pairs > 1 && !brackets.is_empty() =>
+            {
+                let closes = brackets.len() / 2;
+                let nth = close.index(closes);
+                let (pair, _) = brackets
+                    .iter_mut()
+                    .filter(|&&mut (_, close)| close)
+                    .nth(nth)
+                    .unwrap();
+                *pair = (*pair + 1 + offset.index(pairs - 1)) % pairs;
+            }
+            Some(BracketDefect::Mismatch(ix, _)) => {
+                brackets.insert(ix.index(brackets.len() + 1), (0, false));
+            }
+            Some(BracketDefect::Insert(pair, close, ix)) => {
+                brackets.insert(ix.index(brackets.len() + 1), (pair, close));
+            }
+        }
+        brackets
+            .into_iter()
+            .map(|(pair, close)| {
+                let (open_char, close_char) = alphabet[pair];
+                if close {
+                    close_char
+                } else {
+                    open_char
+                }
+            })
+            .collect::<String>()
+    };
+
+    let defect = if balanced {
+        Just(None).boxed()
+    } else {
+        prop_oneof![
+            (0..pairs, bool::ANY, any::<Index>()).prop_map(
+                |(pair, close, ix)| {
+                    Some(BracketDefect::Insert(pair, close, ix))
+                }
+            ),
+            any::<(Index, Index)>().prop_map(|(close, offset)| {
+                Some(BracketDefect::Mismatch(close, offset))
+            }),
+        ]
+        .boxed()
+    };
+
+    BracketsStrategy((groups, defect).prop_map(to_string).boxed())
+}
+
+/// Syllables `lorem()` builds words from, simplest first.
+const LOREM_SYLLABLES: &[&str] = &[
+    "a", "lo", "re", "mi", "pa", "su", "ta", "ve", "on", "el", "is", "um",
+    "dor", "sit", "con", "qua",
+];
+
+/// Punctuation `lorem()` may put after a word.
+const LOREM_PUNCTUATION: &[char] = &[',', '.', ';', '!', '?'];
+
+opaque_strategy_wrapper! {
+    /// Strategy which generates text of pseudo-words.
+    ///
+    /// Created by `lorem()`.
+    #[derive(Debug)]
+    pub struct LoremStrategy[][]
+        (SBoxedStrategy<String>) -> LoremValueTree;
+    /// `ValueTree` corresponding to `LoremStrategy`.
+    pub struct LoremValueTree[][]
+        (Box<dyn ValueTree<Value = String>>) -> String;
+}
+
+/// Creates a strategy which generates readable placeholder text such as
+/// `Lore sitmi, paon. Quadorel`, e.g., for testing tokenizers or search.
+///
+/// The text consists of a number of words within `word_count`, separated by
+/// single spaces. Each word is made of one to three syllables from a small
+/// fixed set of lowercase ASCII syllables, and is occasionally followed by
+/// one of `,.;!?`. The first word, and every word after a `.`, `!` or `?`, is
+/// capitalised.
+///
+/// Shrinking removes words, syllables and punctuation, so if `word_count`
+/// includes 0, values shrink towards the empty string.
+pub fn lorem(word_count: impl Into<SizeRange>) -> LoremStrategy {
+    let word = (
+        vec(select(LOREM_SYLLABLES), 1..4),
+        crate::option::weighted(0.15, select(LOREM_PUNCTUATION)),
+    );
+    LoremStrategy(
+        vec(word, word_count)
+            .prop_map(|words| {
+                let mut text = String::new();
+                let mut capitalise = true;
+                for (syllables, punctuation) in words {
+                    if !text.is_empty() {
+                        text.push(' ');
+                    }
+                    let word = syllables.concat();
+                    if capitalise {
+                        text.push_str(&word[..1].to_ascii_uppercase());
+                        text.push_str(&word[1..]);
+                    } else {
+                        text.push_str(&word);
+                    }
+                    capitalise = false;
+                    if let Some(punctuation) = punctuation {
+                        text.push(punctuation);
+                        capitalise = ".!?".contains(punctuation);
+                    }
+                }
+                text
+            })
+            .sboxed(),
+    )
+}
+
+/// Characters `regex_pattern()` matches literally, escaping them if they are
+/// meta characters.
+const REGEX_LITERALS: &[char] = &[
+    'a', 'b', 'c', 'x', '0', '1', ' ', '-', 'é', '.', '*', '+', '?', '(', ')',
+    '[', ']', '{', '}', '|', '^', '$', '\\',
+];
+
+/// Character classes `regex_pattern()` may use.
+const REGEX_CLASSES: &[&str] = &[
+    ".",
+    r"\d",
+    r"\w",
+    r"\s",
+    r"\D",
+    "[a-z]",
+    "[abc]",
+    "[^0-9]",
+    r"[\w&&[^_]]",
+    "[[:alpha:]]",
+    r"\p{Greek}",
+];
+
+/// Zero-width assertions `regex_pattern()` may use.
+const REGEX_ASSERTIONS: &[&str] = &["^", "$", r"\b", r"\B", r"\A", r"\z"];
+
+/// Repetition operators `regex_pattern()` may apply to any subpattern.
+const REGEX_REPETITIONS: &[&str] = &["?", "*", "+", "??", "*?", "+?"];
+
+/// Counted repetitions `regex_pattern()` may apply to a single character or
+/// class.
+const REGEX_COUNTED_REPETITIONS: &[&str] = &["{2}", "{0,3}", "{1,}", "{2,3}?"];
+
+opaque_strategy_wrapper! {
+    /// Strategy which generates valid regular expressions.
+    ///
+    /// Created by `regex_pattern()`.
+    #[derive(Debug)]
+    pub struct RegexPatternStrategy[][]
+        (BoxedStrategy<String>) -> RegexPatternValueTree;
+    /// `ValueTree` corresponding to `RegexPatternStrategy`.
+    pub struct RegexPatternValueTree[][]
+        (Box<dyn ValueTree<Value = String>>) -> String;
+}
+
+/// Creates a strategy which generates strings in the syntax of the `regex`
+/// crate, such as `(?:[a-z]|\d{0,3})+\.`, e.g., for fuzzing code which
+/// compiles user-supplied patterns.
+///
+/// Patterns are built from literal characters (escaped where necessary),
+/// character classes, zero-width assertions, capturing and non-capturing
+/// groups, alternations, and greedy and lazy repetitions. `max_complexity`
+/// bounds how deeply these are nested and, roughly, how many there are.
+///
+/// Every pattern is accepted by `regex::Regex::new()`. To keep the compiled
+/// size of a pattern proportional to its length, counted repetitions such as
+/// `{0,3}` are only ever applied to a single character or class, so they
+/// never nest.
+///
+/// Values shrink towards the empty pattern.
+pub fn regex_pattern(max_complexity: u32) -> RegexPatternStrategy {
+    let atom = prop_oneof![
+        select(REGEX_LITERALS)
+            .prop_map(|c| { regex_syntax::escape(c.encode_utf8(&mut [0; 4])) }),
+        select(REGEX_CLASSES).prop_map(String::from),
+    ];
+    let leaf = prop_oneof![
+        Just(String::new()),
+        atom.clone(),
+        select(REGEX_ASSERTIONS).prop_map(String::from),
+        (atom, select(REGEX_COUNTED_REPETITIONS))
+            .prop_map(|(atom, repetition)| atom + repetition),
+    ];
+    let pattern = leaf.prop_recursive(
+        max_complexity,
+        max_complexity.saturating_mul(4),
+        3,
+        |inner| {
+            prop_oneof![
+                vec(inner.clone(), 2..4).prop_map(|parts| parts.concat()),
+                vec(inner.clone(), 2..4).prop_map(|alternatives| {
+                    format!("(?:{})", alternatives.join("|"))
+                }),
+                inner.clone().prop_map(|group| format!("({})", group)),
+                (inner, select(REGEX_REPETITIONS)).prop_map(
+                    |(pattern, repetition)| {
+                        format!("(?:{}){}", pattern, repetition)
+                    }
+                ),
+            ]
+        },
+    );
+    RegexPatternStrategy(pattern.boxed())
+}
+
+/// The strings `cow_str()` borrows from, simplest first.
+const INTERNED_STRS: &[&str] = &[
+    "",
+    " ",
+    "a",
+    "0",
+    "true",
+    "hello world",
+    "\0",
+    "\n",
+    "é",
+    "日本語",
+    "🦀",
+];
+
+opaque_strategy_wrapper! {
+    /// Strategy which generates both borrowed and owned `Cow<str>`s.
+    ///
+    /// Created by `cow_str()`.
+    #[derive(Debug)]
+    pub struct CowStrStrategy[][]
+        (SBoxedStrategy<Cow<'static, str>>) -> CowStrValueTree;
+    /// `ValueTree` corresponding to `CowStrStrategy`.
+    pub struct CowStrValueTree[][]
+        (Box<dyn ValueTree<Value = Cow<'static, str>>>) -> Cow<'static, str>;
+}
+
+/// Creates a strategy which generates `Cow<'static, str>` values of both
+/// variants, for testing APIs which may behave differently for each.
+///
+/// `Cow::Borrowed` values are picked from a small set of interned strings,
+/// while `Cow::Owned` values are arbitrary strings as generated by
+/// `any::<String>()`. Note that `any::<Cow<'static, str>>()` only produces
+/// `Cow::Owned` values.
+///
+/// Owned values shrink towards borrowed ones, and borrowed values towards
+/// the empty string.
+pub fn cow_str() -> CowStrStrategy {
+    CowStrStrategy(
+        prop_oneof![
+            select(INTERNED_STRS).prop_map(Cow::Borrowed),
+            any::<String>().prop_map(Cow::Owned),
+        ]
+        .sboxed(),
+    )
+}
+
+/// The standard base64 alphabet from RFC 4648.
+const BASE64_STANDARD: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+/// The URL- and filename-safe base64 alphabet from RFC 4648.
+const BASE64_URL_SAFE: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+/// The lowercase hexadecimal digits.
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+#[derive(Clone, Copy, Debug)]
+enum Encoding {
+    Base64(&'static [u8; 64]),
+    Hex,
+}
+
+impl Encoding {
+    fn encode(self, bytes: &[u8]) -> String {
+        let mut encoded = String::new();
+        match self {
+            Encoding::Base64(alphabet) => {
+                for chunk in bytes.chunks(3) {
+                    let mut group = [0u8; 3];
+                    group[..chunk.len()].copy_from_slice(chunk);
+                    let bits = u32::from(group[0]) << 16
+                        | u32::from(group[1]) << 8
+                        | u32::from(group[2]);
+                    for i in 0..4 {
+                        if i <= chunk.len() {
+                            let sextet = (bits >> (18 - 6 * i)) & 0x3F;
+                            encoded.push(char::from(alphabet[sextet as usize]));
+                        } else {
+                            encoded.push('=');
+                        }
+                    }
+                }
+            }
+            Encoding::Hex => {
+                for &byte in bytes {
+                    for &nibble in &[byte >> 4, byte & 0xF] {
+                        encoded.push(char::from(HEX_DIGITS[nibble as usize]));
+                    }
+                }
+            }
+        }
+        encoded
+    }
+}
+
+/// Strategy which generates byte strings together with their encoding as a
+/// `String`.
+///
+/// Created by `base64()`, `base64_url_safe()` and `hex()`.
+#[must_use = "strategies do nothing unless used"]
+#[derive(Clone, Debug)]
+pub struct EncodedBytesStrategy<S> {
+    bytes: S,
+    encoding: Encoding,
+}
+
+/// Creates a strategy which generates byte strings from `bytes` together
+/// with their base64 encoding, using the standard alphabet with `+` and `/`
+/// and padding with `=`, as `(encoded, bytes)` pairs.
+///
+/// The string is recomputed from the bytes whenever they change, so the two
+/// shrink together and always match, e.g., for testing that decoding the
+/// string yields the bytes.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::string::base64;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn base64_is_padded((encoded, bytes) in base64(
+///       prop::collection::vec(any::<u8>(), 0..32),
+///   )) {
+///     prop_assert_eq!((bytes.len() + 2) / 3 * 4, encoded.len());
+///   }
+/// }
+/// #
+/// # fn main() { base64_is_padded(); }
+/// ```
+pub fn base64<S: Strategy<Value = Vec<u8>>>(
+    bytes: S,
+) -> EncodedBytesStrategy<S> {
+    EncodedBytesStrategy {
+        bytes,
+        encoding: Encoding::Base64(BASE64_STANDARD),
+    }
+}
+
+/// Like `base64()`, but using the URL- and filename-safe alphabet with `-`
+/// and `_` in place of `+` and `/`. The encoding is still padded with `=`.
+pub fn base64_url_safe<S: Strategy<Value = Vec<u8>>>(
+    bytes: S,
+) -> EncodedBytesStrategy<S> {
+    EncodedBytesStrategy {
+        bytes,
+        encoding: Encoding::Base64(BASE64_URL_SAFE),
+    }
+}
+
+/// Creates a strategy which generates byte strings from `bytes` together
+/// with their encoding as lowercase hexadecimal digits, as
+/// `(encoded, bytes)` pairs.
+///
+/// As with `base64()`, the two shrink together and always match.
+pub fn hex<S: Strategy<Value = Vec<u8>>>(bytes: S) -> EncodedBytesStrategy<S> {
+    EncodedBytesStrategy {
+        bytes,
+        encoding: Encoding::Hex,
+    }
+}
+
+impl<S: Strategy<Value = Vec<u8>>> Strategy for EncodedBytesStrategy<S> {
+    type Tree = EncodedBytesValueTree<S::Tree>;
+    type Value = (String, Vec<u8>);
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        Ok(EncodedBytesValueTree {
+            bytes: self.bytes.new_tree(runner)?,
+            encoding: self.encoding,
+        })
+    }
+}
+
+/// `ValueTree` corresponding to `EncodedBytesStrategy`.
+#[derive(Clone, Debug)]
+pub struct EncodedBytesValueTree<T> {
+    bytes: T,
+    encoding: Encoding,
+}
+
+impl<T: ValueTree<Value = Vec<u8>>> ValueTree for EncodedBytesValueTree<T> {
+    type Value = (String, Vec<u8>);
+
+    fn current(&self) -> (String, Vec<u8>) {
+        let bytes = self.bytes.current();
+        (self.encoding.encode(&bytes), bytes)
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.bytes.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.bytes.complicate()
+    }
+}
+
+/// The proportions of scripts and categories of characters to generate with
+/// `unicode_mix()`.
+///
+/// Each field is the relative weight of a group of characters; a weight of 0
+/// excludes the group. The script weights select letters of that script,
+/// while `marks` and `punctuation` select combining diacritical marks and
+/// punctuation (ASCII, general and CJK) regardless of script.
+///
+/// The default excludes every group, so at least one weight must be set,
+/// e.g., `ScriptWeights { latin: 3, cyrillic: 1, ..ScriptWeights::default() }`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScriptWeights {
+    /// Weight of Latin letters, including the Latin-1 and Latin Extended-A
+    /// letters.
+    pub latin: u32,
+    /// Weight of Cyrillic letters.
+    pub cyrillic: u32,
+    /// Weight of CJK ideographs and Japanese kana.
+    pub cjk: u32,
+    /// Weight of Arabic letters.
+    pub arabic: u32,
+    /// Weight of combining diacritical marks.
+    pub marks: u32,
+    /// Weight of punctuation.
+    pub punctuation: u32,
+}
+
+static LATIN_LETTERS: &[RangeInclusive<char>] = &[
+    'a'..='z',
+    'A'..='Z',
+    '\u{00C0}'..='\u{00D6}',
+    '\u{00D8}'..='\u{00F6}',
+    '\u{00F8}'..='\u{017F}',
+];
+
+static CYRILLIC_LETTERS: &[RangeInclusive<char>] = &[
+    '\u{0430}'..='\u{044F}',
+    '\u{0400}'..='\u{042F}',
+    '\u{0450}'..='\u{045F}',
+];
+
+static CJK_LETTERS: &[RangeInclusive<char>] = &[
+    '\u{4E00}'..='\u{9FFF}',
+    '\u{3041}'..='\u{3096}',
+    '\u{30A1}'..='\u{30FA}',
+];
+
+static ARABIC_LETTERS: &[RangeInclusive<char>] = &[
+    '\u{0627}'..='\u{063A}',
+    '\u{0621}'..='\u{0626}',
+    '\u{0641}'..='\u{064A}',
+];
+
+static COMBINING_MARKS: &[RangeInclusive<char>] = &['\u{0300}'..='\u{036F}'];
+
+static PUNCTUATION: &[RangeInclusive<char>] = &[
+    '!'..='/',
+    ':'..='@',
+    '['..='`',
+    '{'..='~',
+    '\u{2010}'..='\u{2027}',
+    '\u{3001}'..='\u{3003}',
+    '\u{060C}'..='\u{060C}',
+    '\u{061F}'..='\u{061F}',
+];
+
+opaque_strategy_wrapper! {
+    /// Strategy which generates strings mixing several scripts.
+    ///
+    /// Created by `unicode_mix()`.
+    #[derive(Debug)]
+    pub struct UnicodeMixStrategy[][]
+        (SBoxedStrategy<String>) -> UnicodeMixValueTree;
+    /// `ValueTree` corresponding to `UnicodeMixStrategy`.
+    pub struct UnicodeMixValueTree[][]
+        (Box<dyn ValueTree<Value = String>>) -> String;
+}
+
+/// Creates a strategy which generates strings of a number of characters
+/// within `len`, drawn from the scripts and categories in `weights` in
+/// proportion to their weights, e.g., for internationalization testing.
+///
+/// Within each group, a range of characters is picked uniformly and then a
+/// character within it, as by `char::ranges()`, but without its bias towards
+/// special and ASCII characters, so the mix of scripts is as requested.
+///
+/// Shrinking removes characters and moves the remaining ones towards ASCII
+/// letters, so if `len` includes 0, values shrink towards the empty string.
+///
+/// ## Panics
+///
+/// Panics if every weight in `weights` is 0.
+pub fn unicode_mix(
+    weights: ScriptWeights,
+    len: impl Into<SizeRange>,
+) -> UnicodeMixStrategy {
+    // Each range is a separate option, so that characters can shrink
+    // towards the first range of their group.
+    let group = |weight: u32, ranges: &'static [RangeInclusive<char>]| {
+        let ranges = ranges.iter().map(|range| {
+            char::CharStrategy::new_borrowed(&[], &[], slice::from_ref(range))
+        });
+        (weight > 0, weight, Union::new(ranges))
+    };
+    // Latin comes first, so that characters shrink towards it.
+    let chars = Union::new_optional(vec![
+        group(weights.latin, LATIN_LETTERS),
+        group(weights.punctuation, PUNCTUATION),
+        group(weights.marks, COMBINING_MARKS),
+        group(weights.cyrillic, CYRILLIC_LETTERS),
+        group(weights.arabic, ARABIC_LETTERS),
+        group(weights.cjk, CJK_LETTERS),
+    ]);
+    UnicodeMixStrategy(
+        vec(chars, len)
+            .prop_map(|chars| chars.into_iter().collect())
+            .sboxed(),
+    )
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashSet;
@@ -597,6 +1410,430 @@ mod test {
         assert_send_and_sync(string_regex(".").unwrap());
     }
 
+    fn is_valid_domain_name(s: &str) -> bool {
+        let labels: Vec<_> = s.split('.').collect();
+        s.len() <= 253
+            && labels.len() >= 2
+            && labels.iter().all(|label| {
+                !label.is_empty()
+                    && label.len() <= 63
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-')
+            })
+            && labels[labels.len() - 1]
+                .chars()
+                .all(|c| c.is_ascii_alphabetic())
+    }
+
+    fn is_valid_email(s: &str) -> bool {
+        const SPECIALS: &str = "!#$%&'*+-/=?^_`{|}~";
+        match s.rsplit_once('@') {
+            Some((local, domain)) => {
+                local.len() <= 64
+                    && local.split('.').all(|atom| {
+                        !atom.is_empty()
+                            && atom.chars().all(|c| {
+                                c.is_ascii_alphanumeric()
+                                    || SPECIALS.contains(c)
+                            })
+                    })
+                    && is_valid_domain_name(domain)
+            }
+            None => false,
+        }
+    }
+
+    fn is_valid_http_url(s: &str) -> bool {
+        let rest = match s
+            .strip_prefix("http://")
+            .or_else(|| s.strip_prefix("https://"))
+        {
+            Some(rest) => rest,
+            None => return false,
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(ix) => rest.split_at(ix),
+            None => (rest, ""),
+        };
+        let host = match authority.split_once(':') {
+            Some((host, port)) => match port.parse::<u16>() {
+                Ok(port) if port > 0 => host,
+                _ => return false,
+            },
+            None => authority,
+        };
+        is_valid_domain_name(host)
+            && path.split('/').skip(1).all(|segment| {
+                !segment.is_empty()
+                    && segment != "."
+                    && segment != ".."
+                    && segment.chars().all(|c| {
+                        c.is_ascii_alphanumeric() || "-._~".contains(c)
+                    })
+            })
+    }
+
+    fn assert_structured_strings(
+        strategy: StructuredStringStrategy,
+        is_valid: fn(&str) -> bool,
+        shortest: usize,
+    ) {
+        let mut runner = TestRunner::deterministic();
+        let mut distinct = HashSet::new();
+        for _ in 0..256 {
+            let mut val = strategy.new_tree(&mut runner).unwrap();
+            distinct.insert(val.current());
+            loop {
+                let s = val.current();
+                assert!(is_valid(&s), "Produced invalid string {:?}", s);
+                if !val.simplify() {
+                    break;
+                }
+            }
+            assert_eq!(shortest, val.current().len(), "{:?}", val.current());
+        }
+        assert!(distinct.len() > 200, "Only {} distinct", distinct.len());
+    }
+
+    #[test]
+    fn domain_names_are_valid_and_shrink_to_shortest() {
+        assert_structured_strings(domain_name(), is_valid_domain_name, 4);
+    }
+
+    #[test]
+    fn emails_are_valid_and_shrink_to_shortest() {
+        assert_structured_strings(email(), is_valid_email, 6);
+    }
+
+    #[test]
+    fn http_urls_are_valid_and_shrink_to_shortest() {
+        assert_structured_strings(http_url(), is_valid_http_url, 11);
+    }
+
+    fn is_valid_utf16(units: &[u16]) -> bool {
+        ::std::char::decode_utf16(units.iter().cloned()).all(|c| c.is_ok())
+    }
+
+    #[test]
+    fn utf16_without_lone_surrogates_is_valid() {
+        let mut runner = TestRunner::deterministic();
+        let mut surrogate_pairs = 0;
+        for _ in 0..256 {
+            let mut val = utf16(0..32, false).new_tree(&mut runner).unwrap();
+            if val.current().iter().any(|&u| (0xD800..0xE000).contains(&u)) {
+                surrogate_pairs += 1;
+            }
+            loop {
+                let units = val.current();
+                assert!(is_valid_utf16(&units), "Invalid UTF-16 {:?}", units);
+                if !val.simplify() {
+                    break;
+                }
+            }
+            assert!(val.current().is_empty());
+        }
+        assert!(surrogate_pairs > 0, "Never produced a surrogate pair");
+    }
+
+    #[test]
+    fn utf16_with_lone_surrogates_is_sometimes_invalid() {
+        let mut runner = TestRunner::deterministic();
+        let mut invalid = 0;
+        for _ in 0..256 {
+            let units = utf16(1..32, true).new_tree(&mut runner).unwrap();
+            if !is_valid_utf16(&units.current()) {
+                invalid += 1;
+            }
+        }
+        assert!(invalid > 64, "Only {} invalid", invalid);
+    }
+
+    /// Returns the maximum nesting depth of `s` if it is balanced.
+    fn balanced_depth(s: &str, alphabet: &[(char, char)]) -> Option<u32> {
+        let mut stack = Vec::new();
+        let mut depth = 0;
+        for c in s.chars() {
+            if let Some(&(_, close)) = alphabet.iter().find(|p| p.0 == c) {
+                stack.push(close);
+                depth = depth.max(stack.len() as u32);
+            } else if stack.pop() != Some(c) {
+                return None;
+            }
+        }
+        if stack.is_empty() {
+            Some(depth)
+        } else {
+            None
+        }
+    }
+
+    const BRACKETS: &[(char, char)] = &[('(', ')'), ('[', ']'), ('{', '}')];
+
+    #[test]
+    fn balanced_brackets_stay_balanced() {
+        let mut runner = TestRunner::deterministic();
+        let mut max_depth_seen = 0;
+        for &alphabet in &[BRACKETS, &BRACKETS[..1]] {
+            let strategy = brackets(alphabet, true, 4);
+            for _ in 0..256 {
+                let mut val = strategy.new_tree(&mut runner).unwrap();
+                loop {
+                    let s = val.current();
+                    let depth = balanced_depth(&s, alphabet);
+                    assert!(depth.map_or(false, |d| d <= 4), "Bad {:?}", s);
+                    max_depth_seen = max_depth_seen.max(depth.unwrap());
+                    if !val.simplify() {
+                        break;
+                    }
+                }
+                assert_eq!("", val.current());
+            }
+        }
+        assert!(max_depth_seen > 1);
+    }
+
+    #[test]
+    fn unbalanced_brackets_stay_unbalanced() {
+        let mut runner = TestRunner::deterministic();
+        let mut mismatched = 0;
+        for &alphabet in &[BRACKETS, &BRACKETS[..1]] {
+            let strategy = brackets(alphabet, false, 4);
+            for _ in 0..256 {
+                let mut val = strategy.new_tree(&mut runner).unwrap();
+                let s = val.current();
+                if s.len() % 2 == 0 {
+                    mismatched += 1;
+                }
+                loop {
+                    let s = val.current();
+                    assert_eq!(None, balanced_depth(&s, alphabet), "{:?}", s);
+                    if !val.simplify() {
+                        break;
+                    }
+                }
+                assert_eq!(1, val.current().len(), "{:?}", val.current());
+            }
+        }
+        assert!(mismatched > 0, "Never mismatched a bracket");
+    }
+
+    #[test]
+    fn lorem_generates_words_and_shrinks_to_empty() {
+        let mut runner = TestRunner::deterministic();
+        let mut punctuated = false;
+        for _ in 0..256 {
+            let mut val = lorem(0..20).new_tree(&mut runner).unwrap();
+            loop {
+                let s = val.current();
+                let words = s.split(' ').filter(|w| !w.is_empty());
+                assert!(words.count() < 20, "{:?}", s);
+                assert!(!s.starts_with(' ') && !s.contains("  "), "{:?}", s);
+                for c in s.chars() {
+                    assert!(
+                        c.is_ascii_alphabetic() || " ,.;!?".contains(c),
+                        "Unexpected {:?} in {:?}",
+                        c,
+                        s
+                    );
+                }
+                assert!(s.chars().next().map_or(true, |c| c.is_uppercase()));
+                punctuated |= s.contains(|c| ",.;!?".contains(c));
+                if !val.simplify() {
+                    break;
+                }
+            }
+            assert_eq!("", val.current());
+        }
+        assert!(punctuated, "Never generated punctuation");
+    }
+
+    #[test]
+    fn regex_pattern_compiles_and_shrinks_to_empty() {
+        let input = regex_pattern(6);
+        let mut runner = TestRunner::deterministic();
+        let mut longest = 0;
+        for _ in 0..64 {
+            let mut val = input.new_tree(&mut runner).unwrap();
+            longest = longest.max(val.current().len());
+            loop {
+                let s = val.current();
+                if let Err(e) = regex::Regex::new(&s) {
+                    panic!("{:?} does not compile: {}", s, e);
+                }
+                if !val.simplify() {
+                    break;
+                }
+            }
+            assert_eq!("", val.current());
+        }
+        assert!(longest > 20, "Only got patterns up to {} long", longest);
+    }
+
+    #[test]
+    fn cow_str_generates_both_variants() {
+        let mut runner = TestRunner::deterministic();
+        let (mut borrowed, mut owned) = (0, 0);
+        for _ in 0..1000 {
+            let mut val = cow_str().new_tree(&mut runner).unwrap();
+            match val.current() {
+                Cow::Borrowed(s) => {
+                    assert!(INTERNED_STRS.contains(&s));
+                    borrowed += 1;
+                }
+                Cow::Owned(_) => owned += 1,
+            }
+            while val.simplify() {}
+            assert!(matches!(val.current(), Cow::Borrowed("")));
+        }
+        assert!(borrowed > 400 && owned > 400, "{} {}", borrowed, owned);
+    }
+
+    fn decode_base64(encoded: &str, alphabet: &[u8; 64]) -> Vec<u8> {
+        assert_eq!(0, encoded.len() % 4, "{:?} is not padded", encoded);
+        let mut bytes = Vec::new();
+        for group in encoded.as_bytes().chunks(4) {
+            let padding = group.iter().filter(|&&c| b'=' == c).count();
+            let mut bits = 0u32;
+            for &c in group {
+                let sextet = alphabet.iter().position(|&a| a == c);
+                bits = bits << 6 | sextet.unwrap_or(0) as u32;
+            }
+            bytes.extend_from_slice(&bits.to_be_bytes()[1..4 - padding]);
+        }
+        bytes
+    }
+
+    fn decode_hex(encoded: &str) -> Vec<u8> {
+        (0..encoded.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&encoded[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn encodes_rfc4648_test_vectors() {
+        let encode = Encoding::Base64(BASE64_STANDARD);
+        for &(raw, encoded) in &[
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(encoded, encode.encode(raw.as_bytes()));
+        }
+        assert_eq!("66f3ff", Encoding::Hex.encode(&[0x66, 0xf3, 0xff]));
+        assert_eq!(
+            "-_8=",
+            Encoding::Base64(BASE64_URL_SAFE).encode(&[0xfb, 0xff])
+        );
+    }
+
+    #[test]
+    fn encoded_bytes_decode_to_bytes() {
+        type Decode = dyn Fn(&str) -> Vec<u8>;
+
+        let bytes = vec(any::<u8>(), 0..32);
+        let decoders: [(_, &Decode); 3] = [
+            (base64(bytes.clone()), &|s| {
+                decode_base64(s, BASE64_STANDARD)
+            }),
+            (base64_url_safe(bytes.clone()), &|s| {
+                decode_base64(s, BASE64_URL_SAFE)
+            }),
+            (hex(bytes), &decode_hex),
+        ];
+        let mut runner = TestRunner::deterministic();
+        for (strategy, decode) in &decoders {
+            for _ in 0..256 {
+                let mut val = strategy.new_tree(&mut runner).unwrap();
+                loop {
+                    let (encoded, bytes) = val.current();
+                    assert_eq!(bytes, decode(&encoded));
+                    if !val.simplify() {
+                        break;
+                    }
+                }
+                assert_eq!((String::new(), Vec::new()), val.current());
+            }
+        }
+    }
+
+    #[test]
+    fn unicode_mix_honours_script_weights() {
+        let weights = ScriptWeights {
+            latin: 2,
+            cyrillic: 1,
+            cjk: 1,
+            ..ScriptWeights::default()
+        };
+        let input = unicode_mix(weights, 0..32);
+        let mut runner = TestRunner::deterministic();
+        let (mut latin, mut cyrillic, mut cjk) = (0, 0, 0);
+        for _ in 0..256 {
+            let mut val = input.new_tree(&mut runner).unwrap();
+            for c in val.current().chars() {
+                let in_group = |ranges: &[RangeInclusive<char>]| {
+                    ranges.iter().any(|r| r.contains(&c))
+                };
+                if in_group(LATIN_LETTERS) {
+                    latin += 1;
+                } else if in_group(CYRILLIC_LETTERS) {
+                    cyrillic += 1;
+                } else if in_group(CJK_LETTERS) {
+                    cjk += 1;
+                } else {
+                    panic!("Unexpected {:?} in {:?}", c, val.current());
+                }
+            }
+            while val.simplify() {}
+            assert_eq!("", val.current());
+        }
+
+        let total = f64::from(latin + cyrillic + cjk);
+        for &(count, expected) in &[(latin, 0.5), (cyrillic, 0.25), (cjk, 0.25)]
+        {
+            let share = f64::from(count) / total;
+            assert!(
+                (share - expected).abs() < 0.05,
+                "Expected a share of {}, got {} ({} {} {})",
+                expected,
+                share,
+                latin,
+                cyrillic,
+                cjk
+            );
+        }
+    }
+
+    #[test]
+    fn unicode_mix_shrinks_towards_ascii() {
+        let weights = ScriptWeights {
+            latin: 1,
+            arabic: 1,
+            marks: 1,
+            ..ScriptWeights::default()
+        };
+        let input = unicode_mix(weights, 4);
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut val = input.new_tree(&mut runner).unwrap();
+            while val.simplify() {}
+            assert_eq!("aaaa", val.current());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one enabled option")]
+    fn unicode_mix_requires_a_weight() {
+        let _ = unicode_mix(ScriptWeights::default(), 0..8);
+    }
+
     macro_rules! consistent {
         ($name:ident, $value:expr) => {
             #[test]
@@ -8,13 +8,18 @@
 // except according to those terms.
 
 use crate::std_facade::fmt;
+use core::hash::{Hash, Hasher};
+
+use crate::strategy::{Just, NewTree, Strategy};
+use crate::test_runner::{
+    TestCaseError, TestCaseResult, TestError, TestRunner,
+};
 
 /// Easily define `proptest` tests.
 ///
-/// Within `proptest!`, define one or more functions without return type
-/// normally, except instead of putting `: type` after each parameter, write
-/// `in strategy`, where `strategy` is an expression evaluating to some
-/// `Strategy`.
+/// Within `proptest!`, define one or more functions normally, except instead
+/// of putting `: type` after each parameter, write `in strategy`, where
+/// `strategy` is an expression evaluating to some `Strategy`.
 ///
 /// Each function will be wrapped in a function which sets up a `TestRunner`,
 /// and then invokes the function body with inputs generated according to the
@@ -74,6 +79,33 @@ use crate::std_facade::fmt;
 /// Due to limitations in `macro_rules!`, `pattern: type` does not work in
 /// all circumstances. In such a case, use `(pattern): type` instead.
 ///
+/// Functions normally have no return type. They may instead declare a return
+/// type of `Result<(), E>` for any `E: Display`, so that fallible code can be
+/// called with `?`. Returning `Err(e)` fails the test case with the `Display`
+/// output of `e` as the reason. (If `E` is `TestCaseError`, the error is used
+/// as-is, so `prop_assume!()` and the `prop_assert!()` family still work.)
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// fn parse_digit(c: char) -> Result<u32, String> {
+///   c.to_digit(10).ok_or_else(|| format!("{:?} is not a digit", c))
+/// }
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn digits_parse(c in "[0-9]") -> Result<(), String> {
+///     let digit = parse_digit(c.chars().next().unwrap())?;
+///     assert!(digit < 10);
+///     Ok(())
+///   }
+/// }
+/// #
+/// # fn main() { digits_parse(); }
+/// ```
+///
 /// To override the default configuration, you can start the `proptest!` block
 /// with `#![proptest_config(expr)]`, where `expr` is an expression that
 /// evaluates to a `proptest::test_runner::Config` (or a reference to one).
@@ -96,6 +128,34 @@ use crate::std_facade::fmt;
 /// # fn main() { test_addition(); }
 /// ```
 ///
+/// Alternatively, the block can start with `runner name = expr;`, where `expr`
+/// evaluates to the `TestRunner` that runs each test. The runner is used as-is,
+/// so its configuration and RNG (e.g. `TestRunner::deterministic()`) apply
+/// unchanged. Within the body, `name` is bound to a fresh `TestRunner` forked
+/// from that runner's RNG for each case, which can be used to run nested
+/// sub-properties that are just as reproducible as the outer test.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::test_runner::TestRunner;
+///
+/// proptest! {
+///   runner nested = TestRunner::deterministic();
+///
+///   # /*
+///   #[test]
+///   # */
+///   fn test_prefix(v in prop::collection::vec(0..10, 1..10)) {
+///     nested.run(&(0..v.len()), |len| {
+///       prop_assert!(v[..len].len() < v.len());
+///       Ok(())
+///     }).unwrap();
+///   }
+/// }
+/// #
+/// # fn main() { test_prefix(); }
+/// ```
+///
 /// ## Closure-Style Invocation
 ///
 /// As of proptest 0.8.1, an alternative, "closure-style" invocation is
@@ -149,10 +209,37 @@ use crate::std_facade::fmt;
 /// ```
 #[macro_export]
 macro_rules! proptest {
+    (runner $name:ident = $runner:expr;
+     $(
+        $(#[$meta:meta])*
+       fn $test_name:ident($($parm:pat in $strategy:expr),+ $(,)?) $(-> $ret:ty)? $body:block
+    )*) => {
+        $(
+            $(#[$meta])*
+            fn $test_name() {
+                let runner = $runner;
+                $crate::proptest_helper!(@_BODY_RUNNER runner $name ($($parm in $strategy),+) [] [$($ret)?] $body);
+            }
+        )*
+    };
+    (runner $name:ident = $runner:expr;
+     $(
+        $(#[$meta:meta])*
+        fn $test_name:ident($($arg:tt)+) $(-> $ret:ty)? $body:block
+    )*) => {
+        $(
+            $(#[$meta])*
+            fn $test_name() {
+                let runner = $runner;
+                $crate::proptest_helper!(@_BODY2_RUNNER runner $name ($($arg)+) [] [$($ret)?] $body);
+            }
+        )*
+    };
+
     (#![proptest_config($config:expr)]
      $(
         $(#[$meta:meta])*
-       fn $test_name:ident($($parm:pat in $strategy:expr),+ $(,)?) $body:block
+       fn $test_name:ident($($parm:pat in $strategy:expr),+ $(,)?) $(-> $ret:ty)? $body:block
     )*) => {
         $(
             $(#[$meta])*
@@ -160,14 +247,14 @@ macro_rules! proptest {
                 let mut config = $crate::test_runner::contextualize_config($config.clone());
                 config.test_name = Some(
                     concat!(module_path!(), "::", stringify!($test_name)));
-                $crate::proptest_helper!(@_BODY config ($($parm in $strategy),+) [] $body);
+                $crate::proptest_helper!(@_BODY config ($($parm in $strategy),+) [] [$($ret)?] $body);
             }
         )*
     };
     (#![proptest_config($config:expr)]
      $(
         $(#[$meta:meta])*
-        fn $test_name:ident($($arg:tt)+) $body:block
+        fn $test_name:ident($($arg:tt)+) $(-> $ret:ty)? $body:block
     )*) => {
         $(
             $(#[$meta])*
@@ -175,27 +262,27 @@ macro_rules! proptest {
                 let mut config = $crate::test_runner::contextualize_config($config.clone());
                 config.test_name = Some(
                     concat!(module_path!(), "::", stringify!($test_name)));
-                $crate::proptest_helper!(@_BODY2 config ($($arg)+) [] $body);
+                $crate::proptest_helper!(@_BODY2 config ($($arg)+) [] [$($ret)?] $body);
             }
         )*
     };
 
     ($(
         $(#[$meta:meta])*
-        fn $test_name:ident($($parm:pat in $strategy:expr),+ $(,)?) $body:block
+        fn $test_name:ident($($parm:pat in $strategy:expr),+ $(,)?) $(-> $ret:ty)? $body:block
     )*) => { $crate::proptest! {
         #![proptest_config($crate::test_runner::Config::default())]
         $($(#[$meta])*
-          fn $test_name($($parm in $strategy),+) $body)*
+          fn $test_name($($parm in $strategy),+) $(-> $ret)? $body)*
     } };
 
     ($(
         $(#[$meta:meta])*
-        fn $test_name:ident($($arg:tt)+) $body:block
+        fn $test_name:ident($($arg:tt)+) $(-> $ret:ty)? $body:block
     )*) => { $crate::proptest! {
         #![proptest_config($crate::test_runner::Config::default())]
         $($(#[$meta])*
-          fn $test_name($($arg)+) $body)*
+          fn $test_name($($arg)+) $(-> $ret)? $body)*
     } };
 
     (|($($parm:pat in $strategy:expr),+ $(,)?)| $body:expr) => {
@@ -225,25 +312,107 @@ macro_rules! proptest {
     ($config:expr, |($($parm:pat in $strategy:expr),+ $(,)?)| $body:expr) => { {
         let mut config = $crate::test_runner::contextualize_config($config.__sugar_to_owned());
         $crate::sugar::force_no_fork(&mut config);
-        $crate::proptest_helper!(@_BODY config ($($parm in $strategy),+) [] $body)
+        $crate::proptest_helper!(@_BODY config ($($parm in $strategy),+) [] [] $body)
     } };
 
     ($config:expr, move |($($parm:pat in $strategy:expr),+ $(,)?)| $body:expr) => { {
         let mut config = $crate::test_runner::contextualize_config($config.__sugar_to_owned());
         $crate::sugar::force_no_fork(&mut config);
-        $crate::proptest_helper!(@_BODY config ($($parm in $strategy),+) [move] $body)
+        $crate::proptest_helper!(@_BODY config ($($parm in $strategy),+) [move] [] $body)
     } };
 
     ($config:expr, |($($arg:tt)+)| $body:expr) => { {
         let mut config = $crate::test_runner::contextualize_config($config.__sugar_to_owned());
         $crate::sugar::force_no_fork(&mut config);
-        $crate::proptest_helper!(@_BODY2 config ($($arg)+) [] $body);
+        $crate::proptest_helper!(@_BODY2 config ($($arg)+) [] [] $body);
     } };
 
     ($config:expr, move |($($arg:tt)+)| $body:expr) => { {
         let mut config = $crate::test_runner::contextualize_config($config.__sugar_to_owned());
         $crate::sugar::force_no_fork(&mut config);
-        $crate::proptest_helper!(@_BODY2 config ($($arg)+) [move] $body);
+        $crate::proptest_helper!(@_BODY2 config ($($arg)+) [move] [] $body);
+    } };
+}
+
+/// Define the same property for several types.
+///
+/// Each function takes a type parameter, which may only be used in its
+/// arguments and body, and is followed by `for [Type, ...]`. It expands to a
+/// module named after the function containing one function per type, named
+/// after the type, which runs the property with the type parameter standing
+/// for that type. Arguments are written as in the closure-style invocation of
+/// `proptest!`, so `x: T` draws `x` from `any::<T>()`.
+///
+/// As in `proptest!`, the block can start with
+/// `#![proptest_config(expr)]` to configure every test in it. Since the
+/// properties run in closure style, the "fork" and "timeout" features are not
+/// supported. The types must be single identifiers, e.g., `u8` or a type
+/// imported with `use`, rather than paths or generic types.
+///
+/// ### Example
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest_for_types! {
+///   # /*
+///   #[test]
+///   # */
+///   fn display_round_trips<T>(value: T) for [u8, i64, bool] {
+///     prop_assert_eq!(value, value.to_string().parse::<T>().unwrap());
+///   }
+/// }
+/// #
+/// # fn main() {
+/// #   display_round_trips::u8();
+/// #   display_round_trips::i64();
+/// #   display_round_trips::bool();
+/// # }
+/// ```
+#[macro_export]
+macro_rules! proptest_for_types {
+    (@_TYPE $config:expr; [$(#[$meta:meta])*] $param:ident ($($arg:tt)+)
+     $body:block; $ty:ident) => {
+        $(#[$meta])*
+        #[allow(non_snake_case)]
+        pub fn $ty() {
+            #[allow(dead_code)]
+            type $param = $ty;
+            $crate::proptest!($config, |($($arg)+)| $body);
+        }
+    };
+    (@_TYPES $config:expr; $metas:tt $param:ident $args:tt $body:block;
+     $($ty:ident),+) => {
+        $($crate::proptest_for_types!(
+            @_TYPE $config; $metas $param $args $body; $ty);)+
+    };
+
+    (#![proptest_config($config:expr)]
+     $(
+        $(#[$meta:meta])*
+        fn $test_name:ident<$param:ident>($($arg:tt)+)
+            for [$($ty:ident),+ $(,)?] $body:block
+    )*) => {
+        $(
+            mod $test_name {
+                #[allow(unused_imports)]
+                use super::*;
+
+                $crate::proptest_for_types!(
+                    @_TYPES $config; [$(#[$meta])*] $param ($($arg)+) $body;
+                    $($ty),+);
+            }
+        )*
+    };
+
+    ($(
+        $(#[$meta:meta])*
+        fn $test_name:ident<$param:ident>($($arg:tt)+)
+            for [$($ty:ident),+ $(,)?] $body:block
+    )*) => { $crate::proptest_for_types! {
+        #![proptest_config($crate::test_runner::Config::default())]
+        $($(#[$meta])*
+          fn $test_name<$param>($($arg)+) for [$($ty),+] $body)*
     } };
 }
 
@@ -491,6 +660,79 @@ macro_rules! prop_oneof {
     };
 }
 
+/// Produce a strategy which generates trait objects from one of several
+/// implementor strategies.
+///
+/// The first argument is the trait object type, e.g., `dyn Shape`, followed
+/// by a `;` and the strategies for the implementors, each optionally preceded
+/// by a `u32` weight and `=>` as in `prop_oneof!`. Every value is boxed into
+/// a `Box<dyn Shape>`. Since values must be `Debug`, the trait needs `Debug`
+/// as a supertrait.
+///
+/// As with `prop_oneof!`, values shrink within the chosen implementor and
+/// across implementors towards earlier ones, so they should be listed in
+/// order of ascending complexity when possible. Unlike `prop_oneof!`, the
+/// type produced is always `Union<BoxedStrategy<Box<dyn Shape>>>`.
+///
+/// ## Example
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::strategy::dyn_union;
+/// use std::fmt::Debug;
+///
+/// trait Shape: Debug {
+///   fn area(&self) -> f64;
+/// }
+///
+/// #[derive(Debug)]
+/// struct Square(f64);
+/// impl Shape for Square {
+///   fn area(&self) -> f64 { self.0 * self.0 }
+/// }
+///
+/// #[derive(Debug)]
+/// struct Circle(f64);
+/// impl Shape for Circle {
+///   fn area(&self) -> f64 { std::f64::consts::PI * self.0 * self.0 }
+/// }
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn area_is_not_negative(shape in dyn_union![dyn Shape;
+///       2 => (0.0..10.0).prop_map(Square),
+///       1 => (0.0..10.0).prop_map(Circle),
+///   ]) {
+///     prop_assert!(shape.area() >= 0.0);
+///   }
+/// }
+/// #
+/// # fn main() { area_is_not_negative(); }
+/// ```
+#[macro_export]
+macro_rules! dyn_union {
+    ($trait_object:ty; $($weight:expr => $item:expr),+ $(,)?) => {{
+        let mut options = $crate::std_facade::Vec::new();
+        $(
+            options.push((
+                $weight,
+                $crate::strategy::Strategy::boxed(
+                    $crate::strategy::Strategy::prop_map($item, |value| {
+                        $crate::std_facade::Box::new(value)
+                            as $crate::std_facade::Box<$trait_object>
+                    })),
+            ));
+        )+
+        $crate::strategy::Union::new_weighted(options)
+    }};
+
+    ($trait_object:ty; $($item:expr),+ $(,)?) => {
+        $crate::dyn_union![$trait_object; $(1 => $item),+]
+    };
+}
+
 /// Convenience to define functions which produce new strategies.
 ///
 /// The macro has two general forms. In the first, you define a function with
@@ -607,6 +849,35 @@ macro_rules! prop_oneof {
 /// # fn main() { }
 /// ```
 ///
+/// ## Shrinking
+///
+/// In the first form, each value in the second argument list is shrunk
+/// independently, exactly as for a tuple of the strategies. The body only
+/// sees the values, so to keep several intermediate values and shrink all of
+/// them, simply return them together:
+///
+/// ```rust,no_run
+/// # #![allow(dead_code)]
+/// use proptest::prelude::*;
+///
+/// prop_compose! {
+///   fn width_and_height()(width in 1..100u32, height in 1..100u32)
+///                        -> (u32, u32) {
+///     (width, height)
+///   }
+/// }
+/// # fn main() { }
+/// ```
+///
+/// In the second form, shrinking works as for `prop_flat_map()`: the values
+/// of the third argument list are shrunk first. Once they cannot be shrunk
+/// any further, a value from the second argument list is shrunk, and the
+/// strategies of the third list are then rebuilt from it and *regenerated*,
+/// since their old values may no longer be valid. The values of the third
+/// list therefore do not keep their shrunk state across a change to the
+/// second list. Values passed through with `Just`, like `vec` above, are
+/// always consistent with the values they were derived from.
+///
 /// ## Comparison with Hypothesis' `@composite`
 ///
 /// `prop_compose!` makes it easy to do a lot of things you can do with
@@ -680,8 +951,8 @@ macro_rules! prop_compose {
     ($(#[$meta:meta])*
      $vis:vis
      $([$($modi:tt)*])? fn $name:ident $params:tt
-     ($($arg:tt)+ $(,)?)
-     ($($arg2:tt)+ $(,)?)
+     ($($arg:tt)+)
+     ($($arg2:tt)+)
        -> $return_type:ty $body:block) =>
     {
         #[must_use = "strategies do nothing unless used"]
@@ -689,7 +960,7 @@ macro_rules! prop_compose {
         $vis
         $($($modi)*)? fn $name $params
                  -> impl $crate::strategy::Strategy<Value = $return_type> {
-            let strat = $crate::proptest_helper!(@_WRAP ($($strategy)*));
+            let strat = $crate::proptest_helper!(@_EXT _STRAT ($($arg)+));
             let strat = $crate::strategy::Strategy::prop_flat_map(
                 strat,
                 move |$crate::proptest_helper!(@_EXT _PAT ($($arg)+))|
@@ -793,9 +1064,8 @@ macro_rules! prop_assert_eq {
         let right = $right;
         $crate::prop_assert!(
             left == right,
-            "assertion failed: `(left == right)` \
-             \n  left: `{:?}`,\n right: `{:?}`",
-            left, right);
+            "assertion failed: `(left == right)` {}",
+            $crate::sugar::AssertEqOperands(&left, &right, ""));
     }};
 
     ($left:expr, $right:expr, $fmt:tt $($args:tt)*) => {{
@@ -803,10 +1073,9 @@ macro_rules! prop_assert_eq {
         let right = $right;
         $crate::prop_assert!(
             left == right,
-            concat!(
-                "assertion failed: `(left == right)` \
-                 \n  left: `{:?}`, \n right: `{:?}`: ", $fmt),
-            left, right $($args)*);
+            concat!("assertion failed: `(left == right)` {}: ", $fmt),
+            $crate::sugar::AssertEqOperands(&left, &right, " ")
+            $($args)*);
     }};
 }
 
@@ -959,20 +1228,33 @@ macro_rules! proptest_helper {
     (@_WRAPSTR ($a:pat, $($rest:pat),*)) => {
         (stringify!($a), $crate::proptest_helper!(@_WRAPSTR ($($rest),*)))
     };
+    // the body of a single test case, depending on whether the test function
+    // declares a return type.
+    (@_CASE [] $body:expr) => {{
+        let (): () = $body;
+        Ok(())
+    }};
+    (@_CASE [$ret:ty] $body:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::sugar::{DisplayBodyResult as _, TestCaseBodyResult as _};
+        let result = $crate::sugar::run_body::<$ret, _>(|| $body);
+        (&$crate::sugar::BodyResult(result)).test_case_result()
+    }};
     // build a property testing block that when executed, executes the full property test.
-    (@_BODY $config:ident ($($parm:pat in $strategy:expr),+) [$($mod:tt)*] $body:expr) => {{
+    (@_BODY $config:ident ($($parm:pat in $strategy:expr),+) [$($mod:tt)*] [$($ret:ty)?] $body:expr) => {{
         $config.source_file = Some(file!());
         let mut runner = $crate::test_runner::TestRunner::new($config);
         let names = $crate::proptest_helper!(@_WRAPSTR ($($parm),*));
-        match runner.run(
+        #[allow(unused_imports)]
+        use $crate::sugar::{DebugRun as _, HashedRun as _};
+        match (&$crate::sugar::RunDispatch(
             &$crate::strategy::Strategy::prop_map(
                 $crate::proptest_helper!(@_WRAP ($($strategy)*)),
-                |values| $crate::sugar::NamedArguments(names, values)),
-            $($mod)* |$crate::sugar::NamedArguments(
+                |values| $crate::sugar::NamedArguments(names, values))))
+            .run_test(&mut runner, $($mod)* |$crate::sugar::NamedArguments(
                 _, $crate::proptest_helper!(@_WRAPPAT ($($parm),*)))|
             {
-                let (): () = $body;
-                Ok(())
+                $crate::proptest_helper!(@_CASE [$($ret)?] $body)
             })
         {
             Ok(()) => (),
@@ -980,19 +1262,69 @@ macro_rules! proptest_helper {
         }
     }};
     // build a property testing block that when executed, executes the full property test.
-    (@_BODY2 $config:ident ($($arg:tt)+) [$($mod:tt)*] $body:expr) => {{
+    (@_BODY2 $config:ident ($($arg:tt)+) [$($mod:tt)*] [$($ret:ty)?] $body:expr) => {{
         $config.source_file = Some(file!());
         let mut runner = $crate::test_runner::TestRunner::new($config);
         let names = $crate::proptest_helper!(@_EXT _STR ($($arg)*));
-        match runner.run(
+        #[allow(unused_imports)]
+        use $crate::sugar::{DebugRun as _, HashedRun as _};
+        match (&$crate::sugar::RunDispatch(
             &$crate::strategy::Strategy::prop_map(
                 $crate::proptest_helper!(@_EXT _STRAT ($($arg)*)),
-                |values| $crate::sugar::NamedArguments(names, values)),
-            $($mod)* |$crate::sugar::NamedArguments(
+                |values| $crate::sugar::NamedArguments(names, values))))
+            .run_test(&mut runner, $($mod)* |$crate::sugar::NamedArguments(
                 _, $crate::proptest_helper!(@_EXT _PAT ($($arg)*)))|
             {
-                let (): () = $body;
-                Ok(())
+                $crate::proptest_helper!(@_CASE [$($ret)?] $body)
+            })
+        {
+            Ok(()) => (),
+            Err(e) => panic!("{}\n{}", e, runner),
+        }
+    }};
+
+    // build a property testing block that runs on the given runner and binds
+    // a runner forked from it for each case.
+    (@_BODY_RUNNER $runner:ident $name:ident ($($parm:pat in $strategy:expr),+) [$($mod:tt)*] [$($ret:ty)?] $body:expr) => {{
+        let mut runner: $crate::test_runner::TestRunner = $runner;
+        let names = $crate::proptest_helper!(@_WRAPSTR ($($parm),*));
+        #[allow(unused_imports)]
+        use $crate::sugar::{DebugRun as _, HashedRun as _};
+        match (&$crate::sugar::RunDispatch(
+            &$crate::strategy::Strategy::prop_map(
+                ($crate::sugar::NestedRunner,
+                 $crate::proptest_helper!(@_WRAP ($($strategy)*))),
+                |(nested, values)| $crate::sugar::WithRunner(
+                    nested, $crate::sugar::NamedArguments(names, values)))))
+            .run_test(&mut runner, $($mod)* |$crate::sugar::WithRunner(
+                mut $name, $crate::sugar::NamedArguments(
+                    _, $crate::proptest_helper!(@_WRAPPAT ($($parm),*))))|
+            {
+                $crate::proptest_helper!(@_CASE [$($ret)?] $body)
+            })
+        {
+            Ok(()) => (),
+            Err(e) => panic!("{}\n{}", e, runner),
+        }
+    }};
+    // build a property testing block that runs on the given runner and binds
+    // a runner forked from it for each case.
+    (@_BODY2_RUNNER $runner:ident $name:ident ($($arg:tt)+) [$($mod:tt)*] [$($ret:ty)?] $body:expr) => {{
+        let mut runner: $crate::test_runner::TestRunner = $runner;
+        let names = $crate::proptest_helper!(@_EXT _STR ($($arg)*));
+        #[allow(unused_imports)]
+        use $crate::sugar::{DebugRun as _, HashedRun as _};
+        match (&$crate::sugar::RunDispatch(
+            &$crate::strategy::Strategy::prop_map(
+                ($crate::sugar::NestedRunner,
+                 $crate::proptest_helper!(@_EXT _STRAT ($($arg)*))),
+                |(nested, values)| $crate::sugar::WithRunner(
+                    nested, $crate::sugar::NamedArguments(names, values)))))
+            .run_test(&mut runner, $($mod)* |$crate::sugar::WithRunner(
+                mut $name, $crate::sugar::NamedArguments(
+                    _, $crate::proptest_helper!(@_EXT _PAT ($($arg)*))))|
+            {
+                $crate::proptest_helper!(@_CASE [$($ret)?] $body)
             })
         {
             Ok(()) => (),
@@ -1071,6 +1403,14 @@ macro_rules! proptest_helper {
 #[derive(Clone, Copy)]
 pub struct NamedArguments<N, V>(#[doc(hidden)] pub N, #[doc(hidden)] pub V);
 
+/// Only the values are hashed, like only they are compared by the result
+/// cache when it uses `Debug`.
+impl<N, V: Hash> Hash for NamedArguments<N, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.1.hash(state);
+    }
+}
+
 impl<V: fmt::Debug> fmt::Debug for NamedArguments<&'static str, V> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{} = ", self.0)?;
@@ -1078,6 +1418,134 @@ impl<V: fmt::Debug> fmt::Debug for NamedArguments<&'static str, V> {
     }
 }
 
+/// Strategy producing a `TestRunner` forked from the one running the test,
+/// used for `proptest! { runner name = expr; .. }`.
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+pub struct NestedRunner;
+
+impl Strategy for NestedRunner {
+    type Tree = Just<TestRunner>;
+    type Value = TestRunner;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        Ok(Just(runner.partial_clone()))
+    }
+}
+
+/// Pairs the nested runner with the test arguments, while only showing the
+/// latter in failure output.
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct WithRunner<V>(#[doc(hidden)] pub TestRunner, #[doc(hidden)] pub V);
+
+impl<V: Hash> Hash for WithRunner<V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.1.hash(state);
+    }
+}
+
+impl<V: fmt::Debug> fmt::Debug for WithRunner<V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.1.fmt(f)
+    }
+}
+
+/// Calls the body of a test function declared with a return type, fixing the
+/// closure's return type so that `?` within it converts to that type.
+#[doc(hidden)]
+pub fn run_body<R, F: FnOnce() -> R>(body: F) -> R {
+    body()
+}
+
+/// The result returned by the body of a test function declared with a return
+/// type.
+///
+/// A `TestCaseError` is passed through as-is, so that rejections stay
+/// rejections; any other error becomes a failure with its `Display` output.
+/// The two cases are told apart by `TestCaseBodyResult` being implemented
+/// for `BodyResult` itself and `DisplayBodyResult` only for a reference to
+/// it, so method resolution picks the former whenever it applies.
+#[doc(hidden)]
+pub struct BodyResult<E>(#[doc(hidden)] pub Result<(), E>);
+
+#[doc(hidden)]
+pub trait TestCaseBodyResult {
+    fn test_case_result(&self) -> TestCaseResult;
+}
+
+impl TestCaseBodyResult for BodyResult<TestCaseError> {
+    fn test_case_result(&self) -> TestCaseResult {
+        self.0.clone()
+    }
+}
+
+#[doc(hidden)]
+pub trait DisplayBodyResult {
+    fn test_case_result(&self) -> TestCaseResult;
+}
+
+impl<E: fmt::Display> DisplayBodyResult for &BodyResult<E> {
+    fn test_case_result(&self) -> TestCaseResult {
+        match self.0 {
+            Ok(()) => Ok(()),
+            Err(ref e) => Err(TestCaseError::fail(format!("{}", e))),
+        }
+    }
+}
+
+/// The strategy of a `proptest!` test, for running it with
+/// `TestRunner::run_hashed()` if its values implement `Hash`, so that
+/// `Config::result_cache_strategy` applies, and with `TestRunner::run()`
+/// otherwise.
+///
+/// Like `BodyResult`, this relies on `HashedRun` being implemented for
+/// `RunDispatch` itself and `DebugRun` only for a reference to it.
+#[doc(hidden)]
+pub struct RunDispatch<'a, S>(#[doc(hidden)] pub &'a S);
+
+#[doc(hidden)]
+pub trait HashedRun<S: Strategy> {
+    fn run_test(
+        &self,
+        runner: &mut TestRunner,
+        test: impl Fn(S::Value) -> TestCaseResult,
+    ) -> Result<(), TestError<S::Value>>;
+}
+
+#[cfg(feature = "std")]
+impl<'a, S: Strategy> HashedRun<S> for RunDispatch<'a, S>
+where
+    S::Value: Hash,
+{
+    fn run_test(
+        &self,
+        runner: &mut TestRunner,
+        test: impl Fn(S::Value) -> TestCaseResult,
+    ) -> Result<(), TestError<S::Value>> {
+        runner.run_hashed(self.0, test)
+    }
+}
+
+#[doc(hidden)]
+pub trait DebugRun<S: Strategy> {
+    fn run_test(
+        &self,
+        runner: &mut TestRunner,
+        test: impl Fn(S::Value) -> TestCaseResult,
+    ) -> Result<(), TestError<S::Value>>;
+}
+
+impl<'a, S: Strategy> DebugRun<S> for &RunDispatch<'a, S> {
+    fn run_test(
+        &self,
+        runner: &mut TestRunner,
+        test: impl Fn(S::Value) -> TestCaseResult,
+    ) -> Result<(), TestError<S::Value>> {
+        runner.run(self.0, test)
+    }
+}
+
 macro_rules! named_arguments_tuple {
     ($($ix:tt $argn:ident $argv:ident)*) => {
         impl<'a, $($argn : Copy),*, $($argv),*> fmt::Debug
@@ -1137,6 +1605,112 @@ named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV 3 DN DV 4 EN EV
 named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV 3 DN DV 4 EN EV
                        5 FN FV 6 GN GV 7 HN HV 8 IN IV 9 JN JV);
 
+/// Formats the operands of a failed `prop_assert_eq!`; the last field goes
+/// between the two.
+///
+/// With the `diff` feature, operands whose `Debug` output is longer than
+/// `DIFF_THRESHOLD` are instead shown as a line diff of their pretty-printed
+/// (`{:#?}`) form.
+#[doc(hidden)]
+pub struct AssertEqOperands<'a, L: ?Sized, R: ?Sized>(
+    #[doc(hidden)] pub &'a L,
+    #[doc(hidden)] pub &'a R,
+    #[doc(hidden)] pub &'static str,
+);
+
+#[cfg(feature = "diff")]
+const DIFF_THRESHOLD: usize = 100;
+
+impl<L: fmt::Debug + ?Sized, R: fmt::Debug + ?Sized> fmt::Display
+    for AssertEqOperands<'_, L, R>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[cfg(feature = "diff")]
+        {
+            if format!("{:?}", self.0).len() > DIFF_THRESHOLD
+                || format!("{:?}", self.1).len() > DIFF_THRESHOLD
+            {
+                return write_diff(
+                    f,
+                    &format!("{:#?}", self.0),
+                    &format!("{:#?}", self.1),
+                );
+            }
+        }
+
+        write!(
+            f,
+            "\n  left: `{:?}`,{}\n right: `{:?}`",
+            self.0, self.2, self.1
+        )
+    }
+}
+
+/// Writes a diff of the lines of `left` and `right`, keeping three lines of
+/// unchanged context around each change.
+#[cfg(feature = "diff")]
+fn write_diff(f: &mut fmt::Formatter, left: &str, right: &str) -> fmt::Result {
+    use crate::std_facade::Vec;
+
+    const CONTEXT: usize = 3;
+
+    let left = left.lines().collect::<Vec<_>>();
+    let right = right.lines().collect::<Vec<_>>();
+
+    // `lcs[i][j]` is the length of the longest common subsequence of
+    // `left[i..]` and `right[j..]`. Past a certain size, just show the
+    // operands in full rather than spending quadratic time and memory.
+    let mut ops = Vec::new();
+    if left.len().saturating_mul(right.len()) <= 1 << 20 {
+        let mut lcs = vec![vec![0u32; right.len() + 1]; left.len() + 1];
+        for i in (0..left.len()).rev() {
+            for j in (0..right.len()).rev() {
+                lcs[i][j] = if left[i] == right[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < left.len() && j < right.len() {
+            if left[i] == right[j] {
+                ops.push((' ', left[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+                ops.push(('-', left[i]));
+                i += 1;
+            } else {
+                ops.push(('+', right[j]));
+                j += 1;
+            }
+        }
+        ops.extend(left[i..].iter().map(|&line| ('-', line)));
+        ops.extend(right[j..].iter().map(|&line| ('+', line)));
+    } else {
+        ops.extend(left.iter().map(|&line| ('-', line)));
+        ops.extend(right.iter().map(|&line| ('+', line)));
+    }
+
+    write!(f, "\ndiff (- left, + right):")?;
+    let changed = |ix: usize| ops.get(ix).map_or(false, |&(op, _)| ' ' != op);
+    let mut elided = false;
+    for (ix, &(op, line)) in ops.iter().enumerate() {
+        let near_change =
+            (ix.saturating_sub(CONTEXT)..=ix + CONTEXT).any(&changed);
+        if near_change {
+            write!(f, "\n{} {}", op, line)?;
+            elided = false;
+        } else if !elided {
+            write!(f, "\n  ...")?;
+            elided = true;
+        }
+    }
+    Ok(())
+}
+
 #[cfg(feature = "std")]
 #[doc(hidden)]
 pub fn force_no_fork(config: &mut crate::test_runner::Config) {
@@ -1163,6 +1737,8 @@ pub fn force_no_fork(_: &mut crate::test_runner::Config) {}
 
 #[cfg(test)]
 mod test {
+    use crate::std_facade::string::ToString;
+    use crate::std_facade::String;
     use crate::strategy::Just;
 
     prop_compose! {
@@ -1214,6 +1790,99 @@ mod test {
         }
     }
 
+    prop_compose! {
+        fn pair_of_ints()(a in 0..1000i32, b in 0..1000i32) -> (i32, i32) {
+            (a, b)
+        }
+    }
+
+    prop_compose! {
+        fn typed_first_stage()(a: u8)(b in 0..=a, a in Just(a))
+                             -> (u8, u8) {
+            (a, b)
+        }
+    }
+
+    fn assert_eq_message<T: PartialEq + ::core::fmt::Debug>(
+        left: T,
+        right: T,
+    ) -> String {
+        let check = || -> crate::test_runner::TestCaseResult {
+            prop_assert_eq!(left, right, "context {}", 42);
+            Ok(())
+        };
+        check().unwrap_err().to_string()
+    }
+
+    #[test]
+    fn prop_assert_eq_shows_small_operands_in_full() {
+        let message = assert_eq_message(vec![1, 2], vec![1, 3]);
+        assert!(
+            message.starts_with(
+                "Case failed: assertion failed: `(left == right)` \
+                 \n  left: `[1, 2]`, \n right: `[1, 3]`: context 42 at "
+            ),
+            "{}",
+            message
+        );
+    }
+
+    #[cfg(feature = "diff")]
+    #[test]
+    fn prop_assert_eq_diffs_large_operands() {
+        use crate::std_facade::Vec;
+
+        let left = (0..100).collect::<Vec<u32>>();
+        let mut right = left.clone();
+        right[50] = 0;
+        right.push(100);
+
+        let message = assert_eq_message(left, right);
+        assert!(
+            message.contains("\ndiff (- left, + right):\n  ...\n"),
+            "{}",
+            message
+        );
+        assert!(message.contains("\n-     50,\n+     0,\n"), "{}", message);
+        assert!(message.contains("\n+     100,\n  ]"), "{}", message);
+        assert!(!message.contains("    10,"), "{}", message);
+        assert!(message.contains(": context 42 at "), "{}", message);
+    }
+
+    #[test]
+    fn composed_tuple_shrinks_every_component() {
+        use crate::test_runner::{TestError, TestRunner};
+
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&pair_of_ints(), |(a, b)| {
+            prop_assert!(a < 10 || b < 20);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, value, _, _)) => assert_eq!((10, 20), value),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn composed_second_stage_shrinks_with_first() {
+        use crate::test_runner::{TestError, TestRunner};
+
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&typed_first_stage(), |(a, b)| {
+            prop_assert!(b <= a);
+            prop_assert!(b < 5);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, (a, b), _, _)) => {
+                assert_eq!(5, b);
+                assert!(a >= 5, "a = {}", a);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
     proptest! {
         #[test]
         fn test_something(a in 0u32..42u32, b in 1u32..10u32) {
@@ -1531,6 +2200,182 @@ mod ownership_tests {
     }
 }
 
+#[cfg(test)]
+mod runner_binding_tests {
+    use std::cell::RefCell;
+    use std::vec::Vec;
+
+    use crate::test_runner::{Config, RngAlgorithm, TestRng, TestRunner};
+
+    thread_local! {
+        static SEEN: RefCell<Vec<(u32, u32)>> =
+            const { RefCell::new(Vec::new()) };
+    }
+
+    fn deterministic_runner() -> TestRunner {
+        TestRunner::new_with_rng(
+            Config {
+                cases: 8,
+                failure_persistence: None,
+                ..Config::default()
+            },
+            TestRng::deterministic_rng(RngAlgorithm::ChaCha),
+        )
+    }
+
+    proptest! {
+        runner nested = deterministic_runner();
+
+        fn record_nested(outer in 0u32..1000) {
+            nested.run(&(0u32..1000), |inner| {
+                SEEN.with(|seen| seen.borrow_mut().push((outer, inner)));
+                Ok(())
+            }).unwrap();
+        }
+
+        fn record_nested_typed(outer: u32) {
+            nested.run(&(0u32..1000), |inner| {
+                SEEN.with(|seen| seen.borrow_mut().push((outer, inner)));
+                Ok(())
+            }).unwrap();
+        }
+    }
+
+    fn take_seen() -> Vec<(u32, u32)> {
+        SEEN.with(|seen| seen.replace(Vec::new()))
+    }
+
+    #[test]
+    fn nested_runner_uses_same_deterministic_stream() {
+        record_nested();
+        let first = take_seen();
+        record_nested();
+        let second = take_seen();
+
+        assert_eq!(64, first.len());
+        assert_eq!(first, second);
+
+        // Each case gets its own nested stream rather than replaying one.
+        let inner_first: Vec<_> = first[..8].iter().map(|v| v.1).collect();
+        let inner_second: Vec<_> = first[8..16].iter().map(|v| v.1).collect();
+        assert_ne!(inner_first, inner_second);
+
+        record_nested_typed();
+        let first = take_seen();
+        record_nested_typed();
+        assert_eq!(first, take_seen());
+    }
+}
+
+#[cfg(test)]
+mod result_body_tests {
+    use std::fmt;
+    use std::panic;
+
+    use crate::std_facade::String;
+    use crate::test_runner::TestCaseError;
+
+    #[derive(Debug)]
+    struct TooBig(u32);
+
+    impl fmt::Display for TooBig {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{} is too big", self.0)
+        }
+    }
+
+    fn check_small(v: u32) -> Result<u32, TooBig> {
+        if v < 10 {
+            Ok(v)
+        } else {
+            Err(TooBig(v))
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn question_mark_passes_through_ok(a in 0u32..10, b: bool)
+            -> Result<(), TooBig>
+        {
+            let a = check_small(a)?;
+            assert!(a < 10 || b);
+            Ok(())
+        }
+
+        #[test]
+        fn test_case_error_keeps_rejections(a in 0u32..100)
+            -> Result<(), TestCaseError>
+        {
+            prop_assume!(a < 50);
+            prop_assert!(a < 50);
+            Ok(())
+        }
+
+        fn question_mark_fails_on_err(a in 0u32..100) -> Result<(), TooBig> {
+            check_small(a)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn question_mark_error_becomes_clean_failure() {
+        let message = *panic::catch_unwind(question_mark_fails_on_err)
+            .unwrap_err()
+            .downcast::<String>()
+            .unwrap();
+        assert!(
+            message.starts_with("Test failed: 10 is too big."),
+            "Unexpected message: {}",
+            message
+        );
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod result_cache_tests {
+    #[test]
+    fn macro_keys_result_cache_by_hash() {
+        use crate::strategy::Strategy;
+        use crate::test_runner::{
+            basic_result_cache, Config, ResultCacheStrategy,
+        };
+        use std::fmt;
+        use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+        use std::vec::Vec;
+
+        static DEBUG_CALLS: AtomicUsize = AtomicUsize::new(0);
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Clone, Hash)]
+        struct Big(Vec<u32>);
+
+        impl fmt::Debug for Big {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                DEBUG_CALLS.fetch_add(1, SeqCst);
+                f.debug_tuple("Big").field(&self.0).finish()
+            }
+        }
+
+        proptest! {
+            #![proptest_config(Config {
+                cases: 8,
+                failure_persistence: None,
+                result_cache: basic_result_cache,
+                result_cache_strategy: ResultCacheStrategy::Hash,
+                ..Config::default()
+            })]
+            fn hashed(big in (0..8u32).prop_map(|v| Big(vec![v; 100]))) {
+                RUNS.fetch_add(1, SeqCst);
+                assert_eq!(100, big.0.len());
+            }
+        }
+
+        hashed();
+        assert_eq!(8, RUNS.load(SeqCst));
+        assert_eq!(0, DEBUG_CALLS.load(SeqCst));
+    }
+}
+
 #[cfg(test)]
 mod closure_tests {
     #[test]
@@ -1651,3 +2496,117 @@ mod any_tests {
         let proptest_helper!(@_EXT _PAT( _x in 1..2 )) = 1;
     }
 }
+
+#[cfg(test)]
+mod for_types_tests {
+    use crate::std_facade::Vec;
+
+    proptest_for_types! {
+        #[test]
+        fn widening_preserves_order<T>(a: T, b: T) for [u8, u16, u32] {
+            prop_assert_eq!(a < b, u64::from(a) < u64::from(b));
+        }
+
+        #[test]
+        fn reversing_twice_is_identity<T>(v in crate::collection::vec(
+            crate::arbitrary::any::<T>(), 0..10)) for [u8, u16, u32]
+        {
+            let mut reversed = v.iter().rev().cloned().collect::<Vec<T>>();
+            reversed.reverse();
+            prop_assert_eq!(v, reversed);
+        }
+    }
+
+    proptest_for_types! {
+        #![proptest_config(crate::test_runner::Config::with_cases(8))]
+
+        #[test]
+        fn configured<T>(_x: T) for [u8, bool] {}
+    }
+}
+
+#[cfg(test)]
+mod dyn_union_tests {
+    use crate::std_facade::{fmt, Box};
+    use crate::strategy::{Just, Strategy, ValueTree};
+    use crate::test_runner::TestRunner;
+
+    trait Shape: fmt::Debug {
+        fn area(&self) -> u32;
+        fn name(&self) -> &'static str;
+    }
+
+    #[derive(Clone, Debug)]
+    struct Square(u32);
+
+    impl Shape for Square {
+        fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+
+        fn name(&self) -> &'static str {
+            "square"
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct Rect(u32, u32);
+
+    impl Shape for Rect {
+        fn area(&self) -> u32 {
+            self.0 * self.1
+        }
+
+        fn name(&self) -> &'static str {
+            "rect"
+        }
+    }
+
+    #[test]
+    fn generates_and_shrinks_each_implementor() {
+        let input = dyn_union![dyn Shape;
+            1 => (1..100u32).prop_map(Square),
+            3 => (1..100u32, 1..100u32)
+                .prop_map(|(w, h)| Rect(w, h)),
+        ];
+        let mut runner = TestRunner::deterministic();
+        let (mut squares, mut rects) = (0, 0);
+        for _ in 0..256 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            let shape: Box<dyn Shape> = case.current();
+            match shape.name() {
+                "square" => squares += 1,
+                _ => rects += 1,
+            }
+
+            // Shrink as long as the shape stays a rectangle, so that it
+            // shrinks within that implementor.
+            let rect = "rect" == shape.name();
+            while case.simplify() {
+                if rect && "rect" != case.current().name() {
+                    case.complicate();
+                }
+            }
+            assert_eq!(1, case.current().area());
+            assert_eq!(shape.name(), case.current().name());
+        }
+        assert!(
+            squares > 20 && rects > 3 * squares / 2,
+            "{} {}",
+            squares,
+            rects
+        );
+    }
+
+    #[test]
+    fn accepts_unweighted_syntax() {
+        let input = dyn_union![dyn Shape; Just(Square(1)), Just(Rect(2, 3))];
+        let mut runner = TestRunner::deterministic();
+        let mut areas = [0; 7];
+        for _ in 0..64 {
+            areas[input.new_tree(&mut runner).unwrap().current().area()
+                as usize] += 1;
+        }
+        assert!(areas[1] > 0 && areas[6] > 0 && areas[1] + areas[6] == 64);
+    }
+}
@@ -7,7 +7,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::std_facade::Box;
+use crate::std_facade::{Arc, Box};
+use core::sync::atomic::{AtomicBool, Ordering};
 use core::u32;
 
 #[cfg(feature = "std")]
@@ -19,11 +20,15 @@ use std::fmt;
 #[cfg(feature = "std")]
 use std::str::FromStr;
 
-use crate::test_runner::result_cache::{noop_result_cache, ResultCache};
-use crate::test_runner::rng::RngAlgorithm;
+use crate::test_runner::result_cache::{
+    noop_result_cache, ResultCache, ResultCacheStrategy,
+};
+use crate::test_runner::rng::{RngAlgorithm, RngSeed};
 use crate::test_runner::FailurePersistence;
+use crate::test_runner::Feedback;
 #[cfg(feature = "std")]
 use crate::test_runner::FileFailurePersistence;
+use crate::test_runner::Telemetry;
 
 #[cfg(feature = "std")]
 const CASES: &str = "PROPTEST_CASES";
@@ -36,8 +41,12 @@ const MAX_FLAT_MAP_REGENS: &str = "PROPTEST_MAX_FLAT_MAP_REGENS";
 #[cfg(feature = "std")]
 const MAX_SHRINK_TIME: &str = "PROPTEST_MAX_SHRINK_TIME";
 #[cfg(feature = "std")]
+const MAX_GENERATION_TIME: &str = "PROPTEST_MAX_GENERATION_TIME";
+#[cfg(feature = "std")]
 const MAX_SHRINK_ITERS: &str = "PROPTEST_MAX_SHRINK_ITERS";
 #[cfg(feature = "std")]
+const SHRINK_DEDUP: &str = "PROPTEST_SHRINK_DEDUP";
+#[cfg(feature = "std")]
 const MAX_DEFAULT_SIZE_RANGE: &str = "PROPTEST_MAX_DEFAULT_SIZE_RANGE";
 #[cfg(feature = "fork")]
 const FORK: &str = "PROPTEST_FORK";
@@ -48,6 +57,10 @@ const VERBOSE: &str = "PROPTEST_VERBOSE";
 #[cfg(feature = "std")]
 const RNG_ALGORITHM: &str = "PROPTEST_RNG_ALGORITHM";
 #[cfg(feature = "std")]
+const SEED: &str = "PROPTEST_SEED";
+#[cfg(feature = "std")]
+const EXPLORE_NEIGHBORS: &str = "PROPTEST_EXPLORE_NEIGHBORS";
+#[cfg(feature = "std")]
 const DISABLE_FAILURE_PERSISTENCE: &str =
     "PROPTEST_DISABLE_FAILURE_PERSISTENCE";
 
@@ -115,12 +128,24 @@ pub fn contextualize_config(mut result: Config) -> Config {
                 "u32",
                 MAX_SHRINK_TIME,
             ),
+            MAX_GENERATION_TIME => parse_or_warn(
+                &value,
+                &mut result.max_generation_time,
+                "u32",
+                MAX_GENERATION_TIME,
+            ),
             MAX_SHRINK_ITERS => parse_or_warn(
                 &value,
                 &mut result.max_shrink_iters,
                 "u32",
                 MAX_SHRINK_ITERS,
             ),
+            SHRINK_DEDUP => parse_or_warn(
+                &value,
+                &mut result.shrink_dedup,
+                "bool",
+                SHRINK_DEDUP,
+            ),
             MAX_DEFAULT_SIZE_RANGE => parse_or_warn(
                 &value,
                 &mut result.max_default_size_range,
@@ -136,6 +161,15 @@ pub fn contextualize_config(mut result: Config) -> Config {
                 "RngAlgorithm",
                 RNG_ALGORITHM,
             ),
+            SEED => {
+                parse_or_warn(&value, &mut result.rng_seed, "RngSeed", SEED)
+            }
+            EXPLORE_NEIGHBORS => parse_or_warn(
+                &value,
+                &mut result.explore_neighbors,
+                "bool",
+                EXPLORE_NEIGHBORS,
+            ),
             DISABLE_FAILURE_PERSISTENCE => result.failure_persistence = None,
 
             _ => {
@@ -162,20 +196,31 @@ fn default_default_config() -> Config {
         max_global_rejects: 1024,
         max_flat_map_regens: 1_000_000,
         failure_persistence: None,
+        explore_neighbors: false,
+        feedback: None,
+        telemetry: None,
         source_file: None,
         test_name: None,
         #[cfg(feature = "fork")]
         fork: false,
         #[cfg(feature = "timeout")]
         timeout: 0,
+        #[cfg(feature = "timeout")]
+        timeout_mode: TimeoutMode::Fork,
         #[cfg(feature = "std")]
         max_shrink_time: 0,
+        #[cfg(feature = "std")]
+        max_generation_time: 0,
         max_shrink_iters: u32::MAX,
+        shrink_dedup: false,
+        cancel: None,
         max_default_size_range: 100,
         result_cache: noop_result_cache,
+        result_cache_strategy: ResultCacheStrategy::Debug,
         #[cfg(feature = "std")]
         verbose: 0,
         rng_algorithm: RngAlgorithm::default(),
+        rng_seed: RngSeed::default(),
         _non_exhaustive: (),
     }
 }
@@ -191,6 +236,20 @@ lazy_static! {
     };
 }
 
+/// Builds a preset by applying `configure` to the defaults before
+/// environment variables are considered, so that the latter still override the
+/// preset.
+fn preset_config(configure: impl FnOnce(&mut Config)) -> Config {
+    let mut config = default_default_config();
+    #[cfg(feature = "std")]
+    {
+        config.failure_persistence =
+            Some(Box::new(FileFailurePersistence::default()));
+    }
+    configure(&mut config);
+    contextualize_config(config)
+}
+
 /// Configuration for how a proptest test should be run.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Config {
@@ -248,6 +307,39 @@ pub struct Config {
     /// default.)
     pub failure_persistence: Option<Box<dyn FailurePersistence>>,
 
+    /// Whether to also test values near each persisted failure which now
+    /// passes.
+    ///
+    /// When enabled, a persisted case which no longer fails (e.g., because
+    /// the bug was fixed) is followed by up to 32 nearby cases: the values
+    /// the shrinker would visit from it if each of them passed, which close
+    /// in on the persisted value from the simpler side. Values more complex
+    /// than the persisted one are not tested, since a `ValueTree` can only
+    /// move between its original value and simpler ones. A failure among
+    /// them is shrunk and reported like the persisted case itself, and none
+    /// of them count towards `cases`.
+    ///
+    /// The default is `false`, which can be overridden by setting the
+    /// `PROPTEST_EXPLORE_NEIGHBORS` environment variable. (The variable is
+    /// only considered when the `std` feature is enabled, which it is by
+    /// default.)
+    pub explore_neighbors: bool,
+
+    /// An optional hook reporting how novel each test case was, used to steer
+    /// generation towards inputs similar to interesting ones.
+    ///
+    /// The default is `None`, in which case every case is drawn fresh from the
+    /// runner's RNG. See [`Feedback`](trait.Feedback.html) for details.
+    pub feedback: Option<Box<dyn Feedback>>,
+
+    /// An optional sink for diagnostic messages, such as the shrinking trace
+    /// produced by `Strategy::prop_debug_tree()`.
+    ///
+    /// The default is `None`, in which case such messages are written to
+    /// standard error (and discarded if the `std` feature is disabled). See
+    /// [`Telemetry`](trait.Telemetry.html) for details.
+    pub telemetry: Option<Box<dyn Telemetry>>,
+
     /// File location of the current test, relevant for persistence
     /// and debugging.
     ///
@@ -290,7 +382,8 @@ pub struct Config {
     /// If non-zero, tests are run in a subprocess and each generated case
     /// fails if it takes longer than this number of milliseconds.
     ///
-    /// This implicitly enables forking, even if the `fork` field is `false`.
+    /// With the default `timeout_mode` of `TimeoutMode::Fork`, this implicitly
+    /// enables forking, even if the `fork` field is `false`.
     ///
     /// The type here is plain `u32` (rather than
     /// `Option<std::time::Duration>`) for the sake of ergonomics.
@@ -309,6 +402,18 @@ pub struct Config {
     #[cfg_attr(docsrs, doc(cfg(feature = "timeout")))]
     pub timeout: u32,
 
+    /// How `timeout` is enforced.
+    ///
+    /// `TimeoutMode::Fork` runs the test in a child process which is killed
+    /// if a case runs for too long. `TimeoutMode::Elapsed` avoids forking
+    /// where that is undesirable or unsupported, at the cost of not being
+    /// able to interrupt a stuck case; see `TimeoutMode` for details.
+    ///
+    /// The default is `TimeoutMode::Fork`.
+    #[cfg(feature = "timeout")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "timeout")))]
+    pub timeout_mode: TimeoutMode,
+
     /// If non-zero, give up the shrinking process after this many milliseconds
     /// have elapsed since the start of the shrinking process.
     ///
@@ -325,6 +430,26 @@ pub struct Config {
     #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     pub max_shrink_time: u32,
 
+    /// If non-zero, abort the test if generating a single input, i.e., a call
+    /// to `Strategy::new_tree()`, takes longer than this number of
+    /// milliseconds.
+    ///
+    /// Unlike `timeout`, this is about the strategy rather than the test
+    /// itself, and guards against pathological strategies such as a regex
+    /// which takes a very long time to generate. Generation cannot be
+    /// interrupted, so an overlong call is only detected once it returns.
+    ///
+    /// This configuration is only available when the `std` feature is enabled
+    /// (which it is by default).
+    ///
+    /// The default is `0` (i.e., no limit), which can be overridden by setting
+    /// the `PROPTEST_MAX_GENERATION_TIME` environment variable. (The variable
+    /// is only considered when the `std` feature is enabled, which it is by
+    /// default.)
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub max_generation_time: u32,
+
     /// Give up on shrinking if more than this number of iterations of the test
     /// code are run.
     ///
@@ -341,6 +466,32 @@ pub struct Config {
     /// considered when the `std` feature is enabled, which it is by default.)
     pub max_shrink_iters: u32,
 
+    /// Whether to skip shrinking candidates which are equal to one already
+    /// tested during the same shrink.
+    ///
+    /// Strategies which lose information, such as lossy `prop_map()`s, can
+    /// make the shrinker visit the same value several times. When enabled,
+    /// the outcomes of the 256 most recently tested candidates are
+    /// remembered, keyed by their `Debug` representation, and reused instead
+    /// of running the test again. Unlike `result_cache`, this only applies
+    /// while shrinking, and forgets everything once shrinking finishes.
+    ///
+    /// The default is `false`, which can be overridden by setting the
+    /// `PROPTEST_SHRINK_DEDUP` environment variable. (The variable is only
+    /// considered when the `std` feature is enabled, which it is by default.)
+    pub shrink_dedup: bool,
+
+    /// An optional token to stop shrinking early, e.g., from a signal
+    /// handler.
+    ///
+    /// The token is checked between shrink iterations. Once it is cancelled,
+    /// the runner stops shrinking and reports the simplest failing input
+    /// found so far, with `ShrinkStats::cancelled` set. It does not
+    /// interrupt the test itself or the generation of further cases.
+    ///
+    /// The default is `None`.
+    pub cancel: Option<CancellationToken>,
+
     /// The default maximum size to `proptest::collection::SizeRange`. The default
     /// strategy for collections (like `Vec`) use collections in the range of
     /// `0..max_default_size_range`.
@@ -369,6 +520,21 @@ pub struct Config {
     /// more slowly.
     pub result_cache: fn() -> Box<dyn ResultCache>,
 
+    /// How the result cache identifies test inputs.
+    ///
+    /// With `ResultCacheStrategy::Hash`, inputs are identified by their `Hash`
+    /// implementation rather than their `Debug` representation, which is
+    /// faster for large values and does not conflate values with lossy
+    /// `Debug` output. This applies to tests defined with `proptest!` whose
+    /// input type implements `Hash`, and to tests run with
+    /// `TestRunner::run_hashed()`; other tests, including those run with
+    /// `TestRunner::run()`, fall back to `Debug`. It has no effect unless
+    /// `result_cache` is set to a cache which makes use of the hash, such as
+    /// `basic_result_cache`.
+    ///
+    /// The default is `ResultCacheStrategy::Debug`.
+    pub result_cache_strategy: ResultCacheStrategy,
+
     /// Set to non-zero values to cause proptest to emit human-targeted
     /// messages to stderr as it runs.
     ///
@@ -402,11 +568,90 @@ pub struct Config {
     /// which it is by default.)
     pub rng_algorithm: RngAlgorithm,
 
+    /// How to seed the RNG when not using a user-provided RNG.
+    ///
+    /// With `RngSeed::Fixed`, every `TestRunner` created by
+    /// `TestRunner::new()` from this config starts from the same RNG state,
+    /// so that it generates the same cases on every run. The seed does not
+    /// choose the generator: that is still `rng_algorithm`, and the same seed
+    /// generates different cases under different algorithms. Both fields are
+    /// ignored when the runner is given its own RNG, as by
+    /// `TestRunner::new_with_rng()`.
+    ///
+    /// The default is `RngSeed::Random`, which can be overridden by setting
+    /// the `PROPTEST_SEED` environment variable to either `random` or a `u64`
+    /// to use as a fixed seed. It combines with `PROPTEST_RNG_ALGORITHM` in
+    /// the same way as the fields do, regardless of which variable is set
+    /// first.
+    ///
+    /// (The variable is only considered when the `std` feature is enabled,
+    /// which it is by default.)
+    pub rng_seed: RngSeed,
+
     // Needs to be public so FRU syntax can be used.
     #[doc(hidden)]
     pub _non_exhaustive: (),
 }
 
+/// A flag shared between a `Config` and whoever may want to stop shrinking,
+/// as used by `Config::cancel`.
+///
+/// Clones share the same flag, and compare equal to each other.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a new token which is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the token, and so any clones of it.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns whether `cancel()` has been called on this token or a clone.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Wraps an existing flag, e.g., one set by a signal handler.
+impl From<Arc<AtomicBool>> for CancellationToken {
+    fn from(flag: Arc<AtomicBool>) -> Self {
+        CancellationToken(flag)
+    }
+}
+
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// How `Config::timeout` is enforced.
+#[cfg(feature = "timeout")]
+#[cfg_attr(docsrs, doc(cfg(feature = "timeout")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeoutMode {
+    /// Run the test in a forked child process, which the parent kills once a
+    /// case exceeds the timeout. This can interrupt cases which never return.
+    Fork,
+    /// Run the test in the current process without forking. Each case is
+    /// timed, and one which took longer than the timeout is marked as a
+    /// failure once it returns.
+    ///
+    /// Nothing interrupts a case while it runs, so this only catches cases
+    /// which are slow, not ones which never return (e.g., an infinite loop).
+    /// Use `TimeoutMode::Fork` if cases may hang.
+    ///
+    /// Note that this mode is still only available with the `timeout`
+    /// feature, which depends on `fork`; it avoids forking at run time, not
+    /// the dependency on `rusty-fork`.
+    Elapsed,
+}
+
 impl Config {
     /// Constructs a `Config` only differing from the `default()` in the
     /// number of test cases required to pass the test successfully.
@@ -448,6 +693,66 @@ impl Config {
         }
     }
 
+    /// Constructs a `Config` suited to running tests locally while iterating
+    /// on code, trading thoroughness for fast feedback.
+    ///
+    /// This runs 32 cases and gives up shrinking after 256 iterations; other
+    /// fields are as for `default()`. As with `default()`, environment
+    /// variables such as `PROPTEST_CASES` take precedence.
+    pub fn quick() -> Self {
+        preset_config(|config| {
+            config.cases = 32;
+            config.max_shrink_iters = 256;
+        })
+    }
+
+    /// Constructs a `Config` suited to continuous integration.
+    ///
+    /// This runs 1024 cases from a fixed seed, so that every run exercises the
+    /// same cases and failures are reproducible from the log alone, with
+    /// failure persistence through the default `FileFailurePersistence` (when
+    /// the `std` feature is enabled) so that failures found in CI can be
+    /// checked in and replayed; other fields are as for `default()`. As with
+    /// `default()`, environment variables such as `PROPTEST_CASES` take
+    /// precedence, so a different seed can be picked with `PROPTEST_SEED`.
+    pub fn ci() -> Self {
+        preset_config(|config| {
+            config.cases = 1024;
+            config.rng_seed = RngSeed::Fixed(0);
+        })
+    }
+
+    /// Constructs a `Config` for occasional, long-running searches for rare
+    /// failures.
+    ///
+    /// This runs 16384 cases, tolerates proportionally more global rejects,
+    /// and allows up to 65536 shrinking iterations; other fields are as for
+    /// `default()`. As with `default()`, environment variables such as
+    /// `PROPTEST_CASES` take precedence.
+    pub fn thorough() -> Self {
+        preset_config(|config| {
+            config.cases = 16_384;
+            config.max_global_rejects = 65_536;
+            config.max_shrink_iters = 65_536;
+        })
+    }
+
+    /// Constructs a `Config` for a quick smoke test of every property, e.g.,
+    /// as a CI job which only needs to know that the tests build and run.
+    ///
+    /// This runs a single case, without failure persistence, so persisted
+    /// failures are neither replayed nor saved, and without shrinking, so a
+    /// failure is reported with the input which first triggered it; other
+    /// fields are as for `default()`. As with `default()`, environment
+    /// variables such as `PROPTEST_CASES` take precedence.
+    pub fn smoke() -> Self {
+        preset_config(|config| {
+            config.cases = 1;
+            config.failure_persistence = None;
+            config.max_shrink_iters = 0;
+        })
+    }
+
     /// Constructs a `Config` only differing from the provided Config instance, `self`,
     /// in the source_file of the present test.
     ///
@@ -478,7 +783,17 @@ impl Config {
     /// This method exists even if the "fork" feature is disabled, in which
     /// case it simply returns false.
     pub fn fork(&self) -> bool {
-        self._fork() || self.timeout() > 0
+        self._fork() || (self.timeout() > 0 && self.timeout_forks())
+    }
+
+    #[cfg(feature = "timeout")]
+    fn timeout_forks(&self) -> bool {
+        TimeoutMode::Fork == self.timeout_mode
+    }
+
+    #[cfg(not(feature = "timeout"))]
+    fn timeout_forks(&self) -> bool {
+        false
     }
 
     #[cfg(feature = "fork")]
@@ -542,3 +857,93 @@ impl Default for Config {
         default_default_config()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::std_facade::Vec;
+    use crate::strategy::{Strategy, ValueTree};
+    use crate::test_runner::TestRunner;
+
+    // Forked so that environment variables which override the presets can be
+    // cleared without affecting other tests.
+    #[cfg(feature = "fork")]
+    rusty_fork_test! {
+        #[test]
+        fn presets_set_expected_fields() {
+            for var in &[CASES, MAX_GLOBAL_REJECTS, MAX_SHRINK_ITERS, SEED] {
+                std::env::remove_var(var);
+            }
+            let default = Config::default();
+
+            let quick = Config::quick();
+            assert_eq!(32, quick.cases);
+            assert_eq!(256, quick.max_shrink_iters);
+            assert_eq!(default.max_global_rejects, quick.max_global_rejects);
+            assert_eq!(RngSeed::Random, quick.rng_seed);
+
+            let ci = Config::ci();
+            assert_eq!(1024, ci.cases);
+            assert_eq!(RngSeed::Fixed(0), ci.rng_seed);
+            assert_eq!(default.max_shrink_iters, ci.max_shrink_iters);
+            assert_eq!(
+                Some(Box::new(FileFailurePersistence::default())
+                    as Box<dyn FailurePersistence>),
+                ci.failure_persistence
+            );
+
+            let thorough = Config::thorough();
+            assert_eq!(16_384, thorough.cases);
+            assert_eq!(65_536, thorough.max_global_rejects);
+            assert_eq!(65_536, thorough.max_shrink_iters);
+            assert_eq!(RngSeed::Random, thorough.rng_seed);
+
+            let smoke = Config::smoke();
+            assert_eq!(1, smoke.cases);
+            assert_eq!(None, smoke.failure_persistence);
+            assert_eq!(0, smoke.max_shrink_iters());
+        }
+    }
+
+    fn first_cases(config: Config) -> Vec<u64> {
+        let mut runner = TestRunner::new(config);
+        (0..16)
+            .map(|_| (0u64..).new_tree(&mut runner).unwrap().current())
+            .collect()
+    }
+
+    #[test]
+    fn fixed_rng_seed_generates_same_cases() {
+        let seeded = |rng_seed| Config {
+            rng_seed,
+            ..Config::default()
+        };
+
+        assert_eq!(
+            first_cases(seeded(RngSeed::Fixed(42))),
+            first_cases(seeded(RngSeed::Fixed(42)))
+        );
+        assert_ne!(
+            first_cases(seeded(RngSeed::Fixed(42))),
+            first_cases(seeded(RngSeed::Fixed(43)))
+        );
+        assert_eq!(Ok(RngSeed::Fixed(42)), "42".parse());
+        assert_eq!(Ok(RngSeed::Random), "random".parse());
+    }
+
+    // Forked so that the environment variable is seen by the default config
+    // and by nothing else.
+    #[cfg(feature = "fork")]
+    rusty_fork_test! {
+        #[test]
+        fn seed_env_var_reproduces_cases() {
+            std::env::set_var(SEED, "42");
+
+            assert_eq!(RngSeed::Fixed(42), Config::default().rng_seed);
+            assert_eq!(
+                first_cases(Config::default()),
+                first_cases(Config::default())
+            );
+        }
+    }
+}
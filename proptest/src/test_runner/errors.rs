@@ -12,7 +12,7 @@ use crate::std_facade::fmt;
 #[cfg(feature = "std")]
 use std::string::ToString;
 
-use crate::test_runner::Reason;
+use crate::test_runner::{PersistedSeed, Reason};
 
 /// Errors which can be returned from test cases to indicate non-successful
 /// completion.
@@ -107,16 +107,50 @@ pub enum TestError<T> {
     /// A failing test case was found. The string indicates where and/or why
     /// the test failed. The `T` is the minimal input found to reproduce the
     /// failure.
-    Fail(Reason, T),
+    ///
+    /// The `PersistedSeed` is the seed from which the failing input was
+    /// generated, if known. Passing it to a fresh `TestRunner` through its
+    /// `FailurePersistence` regenerates and shrinks the same input. It is
+    /// `None` when the runner was handed an already-generated value tree,
+    /// e.g. via `TestRunner::run_one()`.
+    ///
+    /// The `ShrinkStats` record how much work went into shrinking the input.
+    Fail(Reason, T, Option<PersistedSeed>, ShrinkStats),
+}
+
+/// Counters describing the shrinking of a failing input, as reported in
+/// `TestError::Fail`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct ShrinkStats {
+    /// The number of times the test was run against a shrunk input. This is
+    /// bounded by `Config::max_shrink_iters`.
+    pub shrink_iterations: u32,
+    /// The number of times a `prop_flat_map()` strategy regenerated its inner
+    /// value while shrinking.
+    pub regenerations: u32,
+    /// Whether shrinking was stopped early through `Config::cancel`, in
+    /// which case the input may not be minimal.
+    pub cancelled: bool,
 }
 
 impl<T: fmt::Debug> fmt::Display for TestError<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             TestError::Abort(ref why) => write!(f, "Test aborted: {}", why),
-            TestError::Fail(ref why, ref what) => {
+            TestError::Fail(ref why, ref what, _, ref stats) => {
                 writeln!(f, "Test failed: {}.", why)?;
-                write!(f, "minimal failing input: {:#?}", what)
+                writeln!(f, "minimal failing input: {:#?}", what)?;
+                writeln!(
+                    f,
+                    "\tshrink iterations: {}",
+                    stats.shrink_iterations
+                )?;
+                write!(f, "\tregenerations: {}", stats.regenerations)?;
+                if stats.cancelled {
+                    write!(f, "\n\tshrinking was cancelled")?;
+                }
+                Ok(())
             }
         }
     }
@@ -163,6 +163,13 @@ impl FailurePersistence for FileFailurePersistence {
         }
     }
 
+    fn all_persisted(
+        &self,
+        source_file: Option<&'static str>,
+    ) -> Vec<PersistedSeed> {
+        self.load_persisted_failures2(source_file)
+    }
+
     fn box_clone(&self) -> Box<dyn FailurePersistence> {
         Box::new(*self)
     }
@@ -499,6 +506,27 @@ mod tests {
         assert_eq!(None, SourceParallel("ext").resolve(None));
     }
 
+    #[test]
+    fn all_persisted_lists_saved_seeds() {
+        use crate::test_runner::rng::Seed;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("regressions.txt");
+        let file: &'static str =
+            Box::leak(file.to_str().unwrap().to_owned().into_boxed_str());
+        let mut persistence = Direct(file);
+        assert!(persistence.all_persisted(None).is_empty());
+
+        let seeds = [1, 2, 3]
+            .iter()
+            .map(|&n| PersistedSeed(Seed::XorShift([n; 16])))
+            .collect::<Vec<_>>();
+        for seed in &seeds {
+            persistence.save_persisted_failure2(None, seed.clone(), &"");
+        }
+        assert_eq!(seeds, persistence.all_persisted(None));
+    }
+
     #[test]
     fn relative_source_files_absolutified() {
         const TEST_RUNNER_PATH: &[&str] = &["src", "test_runner", "mod.rs"];
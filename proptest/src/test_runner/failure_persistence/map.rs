@@ -48,6 +48,13 @@ impl FailurePersistence for MapFailurePersistence {
         set.insert(seed);
     }
 
+    fn all_persisted(
+        &self,
+        source_file: Option<&'static str>,
+    ) -> Vec<PersistedSeed> {
+        self.load_persisted_failures2(source_file)
+    }
+
     fn box_clone(&self) -> Box<dyn FailurePersistence> {
         Box::new(self.clone())
     }
@@ -68,6 +75,7 @@ impl FailurePersistence for MapFailurePersistence {
 mod tests {
     use super::*;
     use crate::test_runner::failure_persistence::tests::*;
+    use crate::test_runner::rng::Seed;
 
     #[test]
     fn initial_map_is_empty() {
@@ -88,6 +96,23 @@ mod tests {
         assert!(p.load_persisted_failures2(UNREL_PATH).is_empty());
     }
 
+    #[test]
+    fn all_persisted_lists_saved_seeds() {
+        let mut p = MapFailurePersistence::default();
+        let seeds = [1, 2, 3]
+            .iter()
+            .map(|&n| PersistedSeed(Seed::XorShift([n; 16])))
+            .collect::<Vec<_>>();
+        for seed in &seeds {
+            p.save_persisted_failure2(HI_PATH, seed.clone(), &"");
+        }
+        p.save_persisted_failure2(UNREL_PATH, INC_SEED, &"");
+
+        assert_eq!(seeds, p.all_persisted(HI_PATH));
+        assert_eq!(vec![INC_SEED], p.all_persisted(UNREL_PATH));
+        assert!(p.all_persisted(None).is_empty());
+    }
+
     #[test]
     fn seeds_deduplicated() {
         let mut p = MapFailurePersistence::default();
@@ -118,6 +118,19 @@ pub trait FailurePersistence: Send + Sync + fmt::Debug {
         panic!("save_persisted_failure2 not implemented");
     }
 
+    /// List all seeds persisted for the given `source_file`, without running
+    /// anything, e.g. for tooling which lists known regressions.
+    ///
+    /// The default implementation returns nothing; implementations which can
+    /// enumerate their seeds should override it.
+    #[allow(unused_variables)]
+    fn all_persisted(
+        &self,
+        source_file: Option<&'static str>,
+    ) -> Vec<PersistedSeed> {
+        Vec::new()
+    }
+
     /// Delegate method for producing a trait object usable with `Clone`
     fn box_clone(&self) -> Box<dyn FailurePersistence>;
 
@@ -0,0 +1,109 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{fmt, Arc, Box, Vec};
+use core::any::Any;
+
+use rand::Rng;
+
+use crate::test_runner::rng::{Seed, TestRng};
+
+/// The maximum number of interesting inputs retained by the runner.
+const MAX_CORPUS_SIZE: usize = 1024;
+
+/// Reports how "interesting" the most recently run test case was, allowing
+/// the `TestRunner` to steer generation in the style of a greybox fuzzer.
+///
+/// A typical implementation shares some state (e.g. a coverage map behind an
+/// `Arc`) with the test body, which records what the case exercised. After
+/// each case, the runner calls `novelty()`; the implementation compares what
+/// was recorded against what it has seen before and returns a score.
+///
+/// When a case has non-zero novelty, the runner retains the random bytes that
+/// were used to generate it. Subsequent inputs are then drawn either fresh or
+/// by mutating the bytes of a retained input, so that new inputs keep most of
+/// the structure of the interesting one. Mutation works best with strategies
+/// that draw directly from the runner's RNG; strategies that fork independent
+/// RNGs (such as `prop_perturb()`) will see less of the original input.
+///
+/// Feedback is ignored when the test is run in a forked process, since the
+/// parent process cannot observe it.
+///
+/// See `Config::feedback`.
+pub trait Feedback: Send + Sync + fmt::Debug {
+    /// Returns the novelty score of the test case which just ran.
+    ///
+    /// Zero means the case was not interesting; any other value indicates
+    /// that the runner should keep exploring around it.
+    fn novelty(&mut self) -> u32;
+
+    /// Delegate method for producing a trait object usable with `Clone`
+    fn box_clone(&self) -> Box<dyn Feedback>;
+
+    /// Equality testing delegate required due to constraints of trait objects.
+    fn eq(&self, other: &dyn Feedback) -> bool;
+
+    /// Assistant method for trait object comparison.
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<'a, 'b> PartialEq<dyn Feedback + 'b> for dyn Feedback + 'a {
+    fn eq(&self, other: &(dyn Feedback + 'b)) -> bool {
+        Feedback::eq(self, other)
+    }
+}
+
+impl Clone for Box<dyn Feedback> {
+    fn clone(&self) -> Box<dyn Feedback> {
+        self.box_clone()
+    }
+}
+
+/// The set of interesting inputs found during a run with `Feedback` enabled,
+/// and the RNG used to decide how to generate the next input.
+pub(crate) struct FeedbackCorpus {
+    rng: TestRng,
+    entries: Vec<Arc<[u8]>>,
+}
+
+impl FeedbackCorpus {
+    pub(crate) fn new(rng: TestRng) -> Self {
+        FeedbackCorpus {
+            rng,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Choose the seed for the next test case.
+    ///
+    /// Half of the time (once there is anything to mutate), this replays the
+    /// bytes of a retained input with one byte replaced. Otherwise it draws a
+    /// fresh recording seed so that the bytes it consumes can be retained.
+    pub(crate) fn next_seed(&mut self) -> Seed {
+        if !self.entries.is_empty() && self.rng.gen() {
+            let ix = self.rng.gen_range(0..self.entries.len());
+            let mut bytes = self.entries[ix].to_vec();
+            if !bytes.is_empty() {
+                let pos = self.rng.gen_range(0..bytes.len());
+                bytes[pos] = self.rng.gen();
+            }
+            Seed::PassThrough(None, bytes.into())
+        } else {
+            Seed::Recorder(self.rng.gen())
+        }
+    }
+
+    /// Retain `bytes` as an interesting input.
+    pub(crate) fn add(&mut self, bytes: Vec<u8>) {
+        if self.entries.len() >= MAX_CORPUS_SIZE {
+            self.entries.remove(0);
+        }
+        self.entries.push(bytes.into());
+    }
+}
@@ -15,17 +15,21 @@
 mod config;
 mod errors;
 mod failure_persistence;
+mod feedback;
 mod reason;
 #[cfg(feature = "fork")]
 mod replay;
 mod result_cache;
 mod rng;
 mod runner;
+mod telemetry;
 
 pub use self::config::*;
 pub use self::errors::*;
 pub use self::failure_persistence::*;
+pub use self::feedback::*;
 pub use self::reason::*;
 pub use self::result_cache::*;
 pub use self::rng::*;
 pub use self::runner::*;
+pub use self::telemetry::*;
@@ -23,17 +23,72 @@ use crate::test_runner::errors::TestCaseResult;
 #[derive(Debug)]
 pub struct ResultCacheKey<'a> {
     value: &'a dyn fmt::Debug,
+    hash: Option<u64>,
 }
 
 impl<'a> ResultCacheKey<'a> {
-    pub(crate) fn new(value: &'a dyn fmt::Debug) -> Self {
-        Self { value }
+    pub(crate) fn new(value: &'a dyn fmt::Debug, hash: Option<u64>) -> Self {
+        Self { value, hash }
     }
 
     /// Return the test input value as an `&dyn Debug`.
     pub fn value_debug(&self) -> &dyn fmt::Debug {
         self.value
     }
+
+    /// Return the hash of the test input value, if it was computed.
+    ///
+    /// This is only the case when `Config::result_cache_strategy` is
+    /// `ResultCacheStrategy::Hash` and the input type implements `Hash`,
+    /// with the test either defined with `proptest!` or run with
+    /// `TestRunner::run_hashed()`.
+    pub fn value_hash(&self) -> Option<u64> {
+        self.hash
+    }
+}
+
+/// How result caches identify test input values.
+///
+/// See `Config::result_cache_strategy`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResultCacheStrategy {
+    /// Identify values by their `Debug` representation, which works for any
+    /// input type, but is slow for large values and conflates values whose
+    /// `Debug` output is lossy.
+    #[default]
+    Debug,
+    /// Identify values by their `Hash` implementation where the input type is
+    /// known to implement it, falling back to `Debug` otherwise.
+    Hash,
+}
+
+/// A `ResultCache` together with how to hash the inputs of a particular
+/// test, as used by `TestRunner`.
+pub(crate) struct CaseCache<T> {
+    cache: Box<dyn ResultCache>,
+    hash: fn(&T) -> Option<u64>,
+}
+
+impl<T: fmt::Debug> CaseCache<T> {
+    pub(crate) fn new(
+        cache: Box<dyn ResultCache>,
+        hash: fn(&T) -> Option<u64>,
+    ) -> Self {
+        CaseCache { cache, hash }
+    }
+
+    pub(crate) fn key(&self, case: &T) -> u64 {
+        self.cache
+            .key(&ResultCacheKey::new(case, (self.hash)(case)))
+    }
+
+    pub(crate) fn put(&mut self, key: u64, result: &TestCaseResult) {
+        self.cache.put(key, result);
+    }
+
+    pub(crate) fn get(&self, key: u64) -> Option<&TestCaseResult> {
+        self.cache.get(key)
+    }
 }
 
 /// An object which can cache the outcomes of tests.
@@ -64,6 +119,10 @@ struct BasicResultCache {
 impl ResultCache for BasicResultCache {
     fn key(&self, val: &ResultCacheKey) -> u64 {
         use std::collections::hash_map::DefaultHasher;
+
+        if let Some(hash) = val.value_hash() {
+            return hash;
+        }
         use std::hash::Hasher;
         use std::io::{self, Write};
 
@@ -95,7 +154,9 @@ impl ResultCache for BasicResultCache {
 
 /// A basic result cache.
 ///
-/// Values are identified by their `Debug` string representation.
+/// Values are identified by their hash if one was computed (see
+/// `Config::result_cache_strategy`), and by their `Debug` string
+/// representation otherwise.
 #[cfg(feature = "std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub fn basic_result_cache() -> Box<dyn ResultCache> {
@@ -105,6 +105,40 @@ impl fmt::Display for RngAlgorithm {
     }
 }
 
+/// How a `TestRunner` created from a `Config` seeds its random number
+/// generator.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RngSeed {
+    /// Seed from a source of entropy, so that every run generates different
+    /// cases. This is the default.
+    #[default]
+    Random,
+    /// Derive the seed from the given value, so that every run with the same
+    /// value and `RngAlgorithm` generates the same cases.
+    Fixed(u64),
+}
+
+// These two are only used for parsing the environment variable
+// PROPTEST_SEED.
+impl str::FromStr for RngSeed {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, ()> {
+        if "random" == s {
+            Ok(RngSeed::Random)
+        } else {
+            s.parse().map(RngSeed::Fixed).map_err(|_| ())
+        }
+    }
+}
+impl fmt::Display for RngSeed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RngSeed::Random => write!(f, "random"),
+            RngSeed::Fixed(seed) => write!(f, "{}", seed),
+        }
+    }
+}
+
 /// Proptest's random number generator.
 #[derive(Clone, Debug)]
 pub struct TestRng {
@@ -229,6 +263,22 @@ impl RngCore for TestRng {
     }
 }
 
+/// Seeds a `TestRng` using the default `RngAlgorithm::ChaCha`, so that it
+/// can be constructed by generic `rand` code.
+///
+/// `from_seed(seed)` is equivalent to
+/// `TestRng::from_seed(RngAlgorithm::ChaCha, &seed)`, and the other methods
+/// derive such a seed as specified by `SeedableRng`. (Note that the inherent
+/// `TestRng::from_seed()` takes precedence over the trait method of the same
+/// name in method-call syntax.)
+impl SeedableRng for TestRng {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        TestRng::from_seed_internal(Seed::ChaCha(seed))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub(crate) enum Seed {
     XorShift([u8; 16]),
@@ -427,6 +477,34 @@ impl TestRng {
         }
     }
 
+    /// Returns the bytes obtained from the RNG so far without copying them
+    /// (only works if the RNG is set to `Recorder`).
+    ///
+    /// This is useful for finding out where two runs diverge. Passing these
+    /// bytes to a `PassThrough` RNG replays the same values.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if this RNG does not capture generated data.
+    pub fn recorded_bytes(&self) -> &[u8] {
+        match self.rng {
+            TestRngImpl::Recorder { ref record, .. } => record,
+            _ => panic!("recorded_bytes() called on non-Recorder RNG"),
+        }
+    }
+
+    /// Returns the bytes consumed from this RNG so far, if it is a `Recorder`
+    /// or a `PassThrough` RNG.
+    pub(crate) fn bytes_consumed(&self) -> Option<Vec<u8>> {
+        match self.rng {
+            TestRngImpl::Recorder { ref record, .. } => Some(record.clone()),
+            TestRngImpl::PassThrough { off, ref data, .. } => {
+                Some(data[..off].to_vec())
+            }
+            _ => None,
+        }
+    }
+
     /// Construct a default TestRng from entropy.
     pub(crate) fn default_rng(algorithm: RngAlgorithm) -> Self {
         #[cfg(feature = "std")]
@@ -524,6 +602,32 @@ impl TestRng {
         })
     }
 
+    /// Construct a `TestRng` whose seed is derived from `seed`.
+    pub(crate) fn from_u64_seed(algorithm: RngAlgorithm, seed: u64) -> Self {
+        fn expand<const N: usize>(seed: u64) -> [u8; N] {
+            let mut bytes = [0u8; N];
+            for (ix, chunk) in bytes.chunks_mut(8).enumerate() {
+                // Mix in the chunk index so the RNG is never seeded with a
+                // repeating pattern (or all zeroes).
+                let word = (seed ^ ix as u64)
+                    .wrapping_add(1)
+                    .wrapping_mul(0x9E37_79B9_7F4A_7C15);
+                chunk.copy_from_slice(&word.to_le_bytes()[..chunk.len()]);
+            }
+            bytes
+        }
+
+        Self::from_seed_internal(match algorithm {
+            RngAlgorithm::XorShift => Seed::XorShift(expand(seed)),
+            RngAlgorithm::ChaCha => Seed::ChaCha(expand(seed)),
+            RngAlgorithm::PassThrough => {
+                panic!("cannot create a seeded instance of PassThrough")
+            }
+            RngAlgorithm::Recorder => Seed::Recorder(expand(seed)),
+            RngAlgorithm::_NonExhaustive => unreachable!(),
+        })
+    }
+
     /// Returns a `TestRng` with a particular hard-coded seed.
     ///
     /// The seed value will always be the same for a particular version of
@@ -558,6 +662,18 @@ impl TestRng {
         Self::from_seed_internal(self.new_rng_seed())
     }
 
+    /// Construct a TestRng using `algorithm`, seeded from this TestRng.
+    ///
+    /// The same number of bytes is drawn from this TestRng whatever the
+    /// algorithm. A `PassThrough` RNG is given those 32 bytes as its data.
+    pub(crate) fn gen_rng_with(&mut self, algorithm: RngAlgorithm) -> Self {
+        let seed = self.gen::<[u8; 32]>();
+        match algorithm {
+            RngAlgorithm::XorShift => Self::from_seed(algorithm, &seed[..16]),
+            _ => Self::from_seed(algorithm, &seed),
+        }
+    }
+
     /// Overwrite the given TestRng with the provided seed.
     pub(crate) fn set_seed(&mut self, seed: Seed) {
         *self = Self::from_seed_internal(seed);
@@ -647,7 +763,8 @@ impl TestRng {
 mod test {
     use crate::std_facade::Vec;
 
-    use rand::{Rng, RngCore};
+    use rand::{Rng, RngCore, SeedableRng};
+    use rand_chacha::ChaChaRng;
 
     use super::{RngAlgorithm, Seed, TestRng};
     use crate::arbitrary::any;
@@ -726,4 +843,59 @@ mod test {
         rng.try_fill_bytes(&mut buf[0..4]).unwrap();
         assert_eq!([0, 0, 0, 0], buf);
     }
+
+    #[test]
+    fn recorded_bytes_replay_through_passthrough() {
+        use crate::collection::vec;
+        use crate::test_runner::{Config, TestRunner};
+
+        let input = (vec(any::<u32>(), 0..16), any::<bool>());
+        let mut recorder = TestRunner::new_with_rng(
+            Config::default(),
+            TestRng::deterministic_rng(RngAlgorithm::Recorder),
+        );
+        let values = (0..16)
+            .map(|_| input.new_tree(&mut recorder).unwrap().current())
+            .collect::<Vec<_>>();
+
+        let rng = recorder.rng();
+        let bytes = rng.recorded_bytes();
+        assert_eq!(rng.bytes_used(), bytes);
+        let mut replay = TestRunner::new_with_rng(
+            Config::default(),
+            TestRng::from_seed(RngAlgorithm::PassThrough, bytes),
+        );
+        for value in values {
+            let replayed = input.new_tree(&mut replay).unwrap().current();
+            assert_eq!(value, replayed);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "non-Recorder RNG")]
+    fn recorded_bytes_requires_recorder() {
+        let _ =
+            TestRng::deterministic_rng(RngAlgorithm::ChaCha).recorded_bytes();
+    }
+
+    #[test]
+    fn seedable_rng_uses_default_algorithm() {
+        let mut rng1 = TestRng::seed_from_u64(42);
+        let mut rng2 = TestRng::seed_from_u64(42);
+        let mut chacha = ChaChaRng::seed_from_u64(42);
+        for _ in 0..64 {
+            let value = rng1.next_u64();
+            assert_eq!(value, rng2.next_u64());
+            assert_eq!(value, chacha.next_u64());
+        }
+        assert_ne!(
+            TestRng::seed_from_u64(42).next_u64(),
+            TestRng::seed_from_u64(43).next_u64()
+        );
+
+        let seed = [7u8; 32];
+        let mut rng1 = <TestRng as SeedableRng>::from_seed(seed);
+        let mut rng2 = TestRng::from_seed(RngAlgorithm::ChaCha, &seed);
+        assert_eq!(rng1.gen::<[u8; 32]>(), rng2.gen::<[u8; 32]>());
+    }
 }
@@ -7,11 +7,15 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::std_facade::{Arc, BTreeMap, Box, String, Vec};
+use crate::std_facade::{Arc, BTreeMap, Box, String, Vec, VecDeque};
+#[cfg(feature = "std")]
+use core::hash::{Hash, Hasher};
 use core::sync::atomic::AtomicUsize;
 use core::sync::atomic::Ordering::SeqCst;
 use core::{fmt, iter};
 #[cfg(feature = "std")]
+use std::collections::hash_map::DefaultHasher;
+#[cfg(feature = "std")]
 use std::panic::{self, AssertUnwindSafe};
 
 #[cfg(feature = "fork")]
@@ -29,11 +33,12 @@ use crate::strategy::*;
 use crate::test_runner::config::*;
 use crate::test_runner::errors::*;
 use crate::test_runner::failure_persistence::PersistedSeed;
+use crate::test_runner::feedback::FeedbackCorpus;
 use crate::test_runner::reason::*;
 #[cfg(feature = "fork")]
 use crate::test_runner::replay;
 use crate::test_runner::result_cache::*;
-use crate::test_runner::rng::TestRng;
+use crate::test_runner::rng::{RngSeed, TestRng};
 
 #[cfg(feature = "fork")]
 const ENV_FORK_FILE: &'static str = "_PROPTEST_FORKFILE";
@@ -44,6 +49,10 @@ const ALWAYS: u32 = 0;
 pub const INFO_LOG: u32 = 1;
 const TRACE: u32 = 2;
 
+/// How many neighbours of a passing persisted case to test with
+/// `Config::explore_neighbors`.
+const NEIGHBOR_CASES: u32 = 32;
+
 #[cfg(feature = "std")]
 macro_rules! verbose_message {
     ($runner:expr, $level:expr, $fmt:tt $($arg:tt)*) => { {
@@ -73,8 +82,14 @@ pub struct TestRunner {
     successes: u32,
     local_rejects: u32,
     global_rejects: u32,
+    shrink_steps: u32,
     rng: TestRng,
     flat_map_regens: Arc<AtomicUsize>,
+    /// The index of the examples to generate, while trying the examples of
+    /// `Strategy::prop_with_examples()`.
+    example: Option<usize>,
+    /// Whether any strategy generated its example for `example`.
+    example_taken: bool,
 
     local_reject_detail: RejectionDetail,
     global_reject_detail: RejectionDetail,
@@ -87,8 +102,11 @@ impl fmt::Debug for TestRunner {
             .field("successes", &self.successes)
             .field("local_rejects", &self.local_rejects)
             .field("global_rejects", &self.global_rejects)
+            .field("shrink_steps", &self.shrink_steps)
             .field("rng", &"<TestRng>")
             .field("flat_map_regens", &self.flat_map_regens)
+            .field("example", &self.example)
+            .field("example_taken", &self.example_taken)
             .field("local_reject_detail", &self.local_reject_detail)
             .field("global_reject_detail", &self.global_reject_detail)
             .finish()
@@ -115,6 +133,23 @@ impl fmt::Display for TestRunner {
     }
 }
 
+/// Counters describing what a `TestRunner` has done so far, as returned by
+/// `TestRunner::stats()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct RunStats {
+    /// The number of test cases which passed.
+    pub successes: u32,
+    /// The number of values rejected while generating inputs, e.g., by
+    /// `prop_filter()`. This is bounded by `Config::max_local_rejects`.
+    pub local_rejects: u32,
+    /// The number of whole inputs rejected by the test, e.g., with
+    /// `prop_assume!`. This is bounded by `Config::max_global_rejects`.
+    pub global_rejects: u32,
+    /// The number of times the test was run against a shrunk input.
+    pub shrink_steps: u32,
+}
+
 /// Equivalent to: `TestRunner::new(Config::default())`.
 impl Default for TestRunner {
     fn default() -> Self {
@@ -181,7 +216,7 @@ fn call_test<V, F, R>(
     case: V,
     test: &F,
     replay_from_fork: &mut R,
-    result_cache: &mut dyn ResultCache,
+    result_cache: &mut CaseCache<V>,
     _: &mut ForkOutput,
     is_from_persisted_seed: bool,
 ) -> TestCaseResultV2
@@ -194,7 +229,7 @@ where
         return result.map(|_| TestCaseOk::ReplayFromForkSuccess);
     }
 
-    let cache_key = result_cache.key(&ResultCacheKey::new(&case));
+    let cache_key = result_cache.key(&case);
     if let Some(result) = result_cache.get(cache_key) {
         return result.clone().map(|_| TestCaseOk::CacheHitSuccess);
     }
@@ -216,7 +251,7 @@ fn call_test<V, F, R>(
     case: V,
     test: &F,
     replay_from_fork: &mut R,
-    result_cache: &mut dyn ResultCache,
+    result_cache: &mut CaseCache<V>,
     fork_output: &mut ForkOutput,
     is_from_persisted_seed: bool,
 ) -> TestCaseResultV2
@@ -240,7 +275,7 @@ where
 
     verbose_message!(runner, TRACE, "Next test input: {:?}", case);
 
-    let cache_key = result_cache.key(&ResultCacheKey::new(&case));
+    let cache_key = result_cache.key(&case);
     if let Some(result) = result_cache.get(cache_key) {
         verbose_message!(
             runner,
@@ -300,18 +335,50 @@ where
 
 type TestRunResult<S> = Result<(), TestError<<S as Strategy>::Value>>;
 
+/// The outcomes of the `SHRINK_DEDUP_CAPACITY` most recently tested
+/// shrinking candidates, keyed by their `Debug` representation, for
+/// `Config::shrink_dedup`.
+#[derive(Default)]
+struct ShrinkDedup {
+    entries: VecDeque<(String, TestCaseResultV2)>,
+}
+
+const SHRINK_DEDUP_CAPACITY: usize = 256;
+
+impl ShrinkDedup {
+    fn get(&mut self, key: &str) -> Option<TestCaseResultV2> {
+        let ix = self.entries.iter().position(|(k, _)| k == key)?;
+        // Move the entry to the back so it is evicted last.
+        let entry = self.entries.remove(ix).unwrap();
+        let result = entry.1.clone();
+        self.entries.push_back(entry);
+        Some(result)
+    }
+
+    fn put(&mut self, key: String, result: &TestCaseResultV2) {
+        if self.entries.len() >= SHRINK_DEDUP_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((key, result.clone()));
+    }
+}
+
 impl TestRunner {
     /// Create a fresh `TestRunner` with the given configuration.
     ///
-    /// The runner will use an RNG with a generated seed and the default
-    /// algorithm.
+    /// The runner will use an RNG of the algorithm `Config::rng_algorithm`,
+    /// seeded as given by `Config::rng_seed` (by default, a generated seed).
     ///
-    /// In `no_std` environments, every `TestRunner` will use the same
-    /// hard-coded seed. This seed is not contractually guaranteed and may be
-    /// changed between releases without notice.
+    /// In `no_std` environments, every `TestRunner` without a fixed seed will
+    /// use the same hard-coded seed. This seed is not contractually guaranteed
+    /// and may be changed between releases without notice.
     pub fn new(config: Config) -> Self {
         let algorithm = config.rng_algorithm;
-        TestRunner::new_with_rng(config, TestRng::default_rng(algorithm))
+        let rng = match config.rng_seed {
+            RngSeed::Random => TestRng::default_rng(algorithm),
+            RngSeed::Fixed(seed) => TestRng::from_u64_seed(algorithm, seed),
+        };
+        TestRunner::new_with_rng(config, rng)
     }
 
     /// Create a fresh `TestRunner` with the standard deterministic RNG.
@@ -342,8 +409,11 @@ impl TestRunner {
             successes: 0,
             local_rejects: 0,
             global_rejects: 0,
+            shrink_steps: 0,
             rng: rng,
             flat_map_regens: Arc::new(AtomicUsize::new(0)),
+            example: None,
+            example_taken: false,
             local_reject_detail: BTreeMap::new(),
             global_reject_detail: BTreeMap::new(),
         }
@@ -358,13 +428,28 @@ impl TestRunner {
             successes: 0,
             local_rejects: 0,
             global_rejects: 0,
+            shrink_steps: 0,
             rng: self.new_rng(),
             flat_map_regens: Arc::clone(&self.flat_map_regens),
+            example: None,
+            example_taken: false,
             local_reject_detail: BTreeMap::new(),
             global_reject_detail: BTreeMap::new(),
         }
     }
 
+    /// Returns the element of `examples` at the current index, if the runner
+    /// is trying the examples of `Strategy::prop_with_examples()` and there
+    /// is one.
+    pub(crate) fn take_example<T: Clone>(
+        &mut self,
+        examples: &[T],
+    ) -> Option<T> {
+        let example = examples.get(self.example?)?.clone();
+        self.example_taken = true;
+        Some(example)
+    }
+
     /// Returns the RNG for this test run.
     pub fn rng(&mut self) -> &mut TestRng {
         &mut self.rng
@@ -381,6 +466,17 @@ impl TestRunner {
         &self.config
     }
 
+    /// Returns the counters accumulated by the runs of this runner so far,
+    /// whether they passed or failed.
+    pub fn stats(&self) -> RunStats {
+        RunStats {
+            successes: self.successes,
+            local_rejects: self.local_rejects,
+            global_rejects: self.global_rejects,
+            shrink_steps: self.shrink_steps,
+        }
+    }
+
     /// Dumps the bytes obtained from the RNG so far (only works if the RNG is
     /// set to `Recorder`).
     ///
@@ -406,11 +502,52 @@ impl TestRunner {
         &mut self,
         strategy: &S,
         test: impl Fn(S::Value) -> TestCaseResult,
+    ) -> TestRunResult<S> {
+        self.run_with_hash(strategy, test, |_| None)
+    }
+
+    /// Like `run()`, but for inputs which implement `Hash`, so that the
+    /// result cache can identify them by their hash if
+    /// `Config::result_cache_strategy` is `ResultCacheStrategy::Hash`.
+    ///
+    /// `run()` itself cannot tell whether its input type implements `Hash`,
+    /// so always leaves it to the result cache to identify inputs by their
+    /// `Debug` representation. `proptest!` calls this method instead of
+    /// `run()` whenever its input type implements `Hash`.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn run_hashed<S: Strategy>(
+        &mut self,
+        strategy: &S,
+        test: impl Fn(S::Value) -> TestCaseResult,
+    ) -> TestRunResult<S>
+    where
+        S::Value: Hash,
+    {
+        fn hash<T: Hash>(case: &T) -> Option<u64> {
+            let mut hasher = DefaultHasher::new();
+            case.hash(&mut hasher);
+            Some(hasher.finish())
+        }
+
+        match self.config.result_cache_strategy {
+            ResultCacheStrategy::Debug => self.run(strategy, test),
+            ResultCacheStrategy::Hash => {
+                self.run_with_hash(strategy, test, hash::<S::Value>)
+            }
+        }
+    }
+
+    fn run_with_hash<S: Strategy>(
+        &mut self,
+        strategy: &S,
+        test: impl Fn(S::Value) -> TestCaseResult,
+        hash: fn(&S::Value) -> Option<u64>,
     ) -> TestRunResult<S> {
         if self.config.fork() {
-            self.run_in_fork(strategy, test)
+            self.run_in_fork(strategy, test, hash)
         } else {
-            self.run_in_process(strategy, test)
+            self.run_in_process(strategy, test, hash)
         }
     }
 
@@ -419,6 +556,7 @@ impl TestRunner {
         &mut self,
         _: &S,
         _: impl Fn(S::Value) -> TestCaseResult,
+        _: fn(&S::Value) -> Option<u64>,
     ) -> TestRunResult<S> {
         unreachable!()
     }
@@ -428,6 +566,7 @@ impl TestRunner {
         &mut self,
         strategy: &S,
         test: impl Fn(S::Value) -> TestCaseResult,
+        hash: fn(&S::Value) -> Option<u64>,
     ) -> TestRunResult<S> {
         let mut test = Some(test);
 
@@ -480,7 +619,11 @@ impl TestRunner {
                         timeout,
                     )
                 },
-                || match self.run_in_process(strategy, test.take().unwrap()) {
+                || match self.run_in_process(
+                    strategy,
+                    test.take().unwrap(),
+                    hash,
+                ) {
                     Ok(_) => (),
                     Err(e) => panic!(
                         "Test failed normally in child process.\n{}\n{}",
@@ -558,6 +701,7 @@ impl TestRunner {
             |_| panic!("Ran past the end of the replay"),
             replay.steps.into_iter(),
             ForkOutput::empty(),
+            hash,
         )
     }
 
@@ -565,6 +709,7 @@ impl TestRunner {
         &mut self,
         strategy: &S,
         test: impl Fn(S::Value) -> TestCaseResult,
+        hash: fn(&S::Value) -> Option<u64>,
     ) -> TestRunResult<S> {
         let (replay_steps, fork_output) = init_replay(&mut self.rng);
         self.run_in_process_with_replay(
@@ -572,6 +717,7 @@ impl TestRunner {
             test,
             replay_steps.into_iter(),
             fork_output,
+            hash,
         )
     }
 
@@ -581,6 +727,7 @@ impl TestRunner {
         test: impl Fn(S::Value) -> TestCaseResult,
         mut replay_from_fork: impl Iterator<Item = TestCaseResult>,
         mut fork_output: ForkOutput,
+        hash: fn(&S::Value) -> Option<u64>,
     ) -> TestRunResult<S> {
         let old_rng = self.rng.clone();
 
@@ -591,34 +738,103 @@ impl TestRunner {
             .map(|f| f.load_persisted_failures2(self.config.source_file))
             .unwrap_or_default();
 
-        let mut result_cache = self.new_cache();
+        let mut result_cache = CaseCache::new(self.new_cache(), hash);
 
-        for PersistedSeed(persisted_seed) in persisted_failure_seeds {
-            self.rng.set_seed(persisted_seed);
-            self.gen_and_run_case(
+        for persisted_seed in persisted_failure_seeds {
+            self.rng.set_seed(persisted_seed.0.clone());
+            let mut result = self.gen_and_run_case(
                 strategy,
                 &test,
                 &mut replay_from_fork,
-                &mut *result_cache,
+                &mut result_cache,
                 &mut fork_output,
                 true,
-            )?;
+            );
+            if result.is_ok() && self.config.explore_neighbors {
+                self.rng.set_seed(persisted_seed.0.clone());
+                result = self.run_neighbors(
+                    strategy,
+                    &test,
+                    &mut replay_from_fork,
+                    &mut result_cache,
+                    &mut fork_output,
+                );
+            }
+            if let Err(TestError::Fail(_, _, ref mut failing_seed, _)) = result
+            {
+                *failing_seed = Some(persisted_seed);
+            }
+            result?;
         }
         self.rng = old_rng;
 
+        // The examples of `prop_with_examples()` come next. Like persisted
+        // failures, they don't count towards `cases`, and since no seed
+        // reproduces them, failing ones are not persisted.
+        let rng = self.rng.clone();
+        for ix in 0.. {
+            self.example = Some(ix);
+            self.example_taken = false;
+            let case = self.new_tree_timed(strategy);
+            self.example = None;
+            if !self.example_taken {
+                break;
+            }
+
+            let case = unwrap_or!(case, msg =>
+                    return Err(TestError::Abort(msg)));
+            self.run_one_with_replay(
+                case,
+                &test,
+                &mut replay_from_fork,
+                &mut result_cache,
+                &mut fork_output,
+                true,
+            )?;
+        }
+        // Trying the examples must not affect the random cases.
+        self.rng = rng;
+
+        // Feedback is only observable when the test runs in this process.
+        let mut corpus =
+            if self.config.feedback.is_some() && !self.config.fork() {
+                Some(FeedbackCorpus::new(self.rng.gen_rng()))
+            } else {
+                None
+            };
+
         while self.successes < self.config.cases {
             // Generate a new seed and make an RNG from that so that we know
             // what seed to persist if this case fails.
-            let seed = self.rng.gen_get_seed();
-            let result = self.gen_and_run_case(
+            let seed = match corpus {
+                Some(ref mut corpus) => {
+                    let seed = corpus.next_seed();
+                    self.rng.set_seed(seed.clone());
+                    seed
+                }
+                None => self.rng.gen_get_seed(),
+            };
+            let mut result = self.gen_and_run_case(
                 strategy,
                 &test,
                 &mut replay_from_fork,
-                &mut *result_cache,
+                &mut result_cache,
                 &mut fork_output,
                 false,
             );
-            if let Err(TestError::Fail(_, ref value)) = result {
+            if let (Some(ref mut corpus), Some(ref mut feedback)) =
+                (&mut corpus, &mut self.config.feedback)
+            {
+                if feedback.novelty() > 0 {
+                    if let Some(bytes) = self.rng.bytes_consumed() {
+                        corpus.add(bytes);
+                    }
+                }
+            }
+            if let Err(TestError::Fail(_, ref value, ref mut failing_seed, _)) =
+                result
+            {
+                *failing_seed = Some(PersistedSeed(seed.clone()));
                 if let Some(ref mut failure_persistence) =
                     self.config.failure_persistence
                 {
@@ -652,11 +868,11 @@ impl TestRunner {
         strategy: &S,
         f: &impl Fn(S::Value) -> TestCaseResult,
         replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
-        result_cache: &mut dyn ResultCache,
+        result_cache: &mut CaseCache<S::Value>,
         fork_output: &mut ForkOutput,
         is_from_persisted_seed: bool,
     ) -> TestRunResult<S> {
-        let case = unwrap_or!(strategy.new_tree(self), msg =>
+        let case = unwrap_or!(self.new_tree_timed(strategy), msg =>
                 return Err(TestError::Abort(msg)));
 
         // We only count new cases to our set of successful runs against
@@ -681,6 +897,66 @@ impl TestRunner {
         Ok(())
     }
 
+    /// Test the neighbours of the case generated from the current RNG state,
+    /// for `Config::explore_neighbors`.
+    ///
+    /// The neighbours are the values the shrinker would visit if each of them
+    /// passed: after one simplification, each complication closes in on the
+    /// original value again, e.g. by bisecting back towards a number. They
+    /// are all simpler than the original value: `complicate()` only undoes
+    /// simplifications, so there is no way to step past the original value.
+    fn run_neighbors<S: Strategy>(
+        &mut self,
+        strategy: &S,
+        test: &impl Fn(S::Value) -> TestCaseResult,
+        replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
+        result_cache: &mut CaseCache<S::Value>,
+        fork_output: &mut ForkOutput,
+    ) -> TestRunResult<S> {
+        let mut case = unwrap_or!(self.new_tree_timed(strategy), msg =>
+                return Err(TestError::Abort(msg)));
+
+        let mut remaining = NEIGHBOR_CASES;
+        let mut more = case.simplify();
+        while more && remaining > 0 {
+            remaining -= 1;
+            match call_test(
+                self,
+                case.current(),
+                test,
+                replay_from_fork,
+                result_cache,
+                fork_output,
+                true,
+            ) {
+                Ok(_) => (),
+                Err(TestCaseError::Fail(why)) => {
+                    let (last_failure, stats) = self.shrink(
+                        &mut case,
+                        test,
+                        replay_from_fork,
+                        result_cache,
+                        fork_output,
+                        true,
+                    );
+                    let why = last_failure.unwrap_or(why);
+                    return Err(TestError::Fail(
+                        why,
+                        case.current(),
+                        None,
+                        stats,
+                    ));
+                }
+                Err(TestCaseError::Reject(whence)) => {
+                    self.reject_global(whence)?
+                }
+            }
+            more = case.complicate() || case.simplify();
+        }
+
+        Ok(())
+    }
+
     /// Run one specific test case against this runner.
     ///
     /// If the test fails, finds the minimal failing test case. If the test
@@ -695,12 +971,12 @@ impl TestRunner {
         case: V,
         test: impl Fn(V::Value) -> TestCaseResult,
     ) -> Result<bool, TestError<V::Value>> {
-        let mut result_cache = self.new_cache();
+        let mut result_cache = CaseCache::new(self.new_cache(), |_| None);
         self.run_one_with_replay(
             case,
             test,
             &mut iter::empty::<TestCaseResult>().fuse(),
-            &mut *result_cache,
+            &mut result_cache,
             &mut ForkOutput::empty(),
             false,
         )
@@ -710,12 +986,91 @@ impl TestRunner {
         })
     }
 
+    /// Run `test` against each case in `inputs`, in order, such as a corpus of
+    /// real-world inputs to check before (or instead of) generating random
+    /// ones with `run()`.
+    ///
+    /// No strategy is involved, so the cases are given directly as value
+    /// trees. A case which fails is shrunk as by `run_one()`, and the first
+    /// such failure is returned. To pass plain values which are not to be
+    /// shrunk, wrap them in `Just`; values which should shrink can be wrapped
+    /// in a value tree starting from them, e.g., `num::u32::BinarySearch`.
+    ///
+    /// Rejected cases are skipped, and count towards
+    /// `Config::max_global_rejects` as in `run()`. Like `run_one()`, this does
+    /// not consult failure persistence or honour the `fork` config.
+    pub fn run_corpus<V: ValueTree>(
+        &mut self,
+        inputs: impl IntoIterator<Item = V>,
+        test: impl Fn(V::Value) -> TestCaseResult,
+    ) -> Result<(), TestError<V::Value>> {
+        for case in inputs {
+            self.run_one(case, &test)?;
+        }
+        Ok(())
+    }
+
+    /// Generate a single case from `strategy` and run `test` against it,
+    /// without shrinking.
+    ///
+    /// This is a building block for driving proptest from a custom loop, such
+    /// as a fuzzer, one case at a time. Unlike `run()`, it does not consult
+    /// failure persistence, does not loop until `Config::cases` successes have
+    /// been seen, and does not honour the `fork` config.
+    ///
+    /// Returns `Ok(true)` if the test passed, in which case it counts towards
+    /// the successes reported by this runner.
+    ///
+    /// Returns `Ok(false)` if the test rejected the input. The rejection is
+    /// accounted for as a global reject exactly as in `run()`; it is up to the
+    /// caller to retry with a new case. Once `Config::max_global_rejects` is
+    /// exceeded, `Err(TestError::Abort)` is returned instead.
+    ///
+    /// Returns `Err(TestError::Fail)` with the failure reason and the
+    /// *unshrunk* `ValueTree` if the test failed, so that the caller can
+    /// shrink it manually, e.g. via `run_one()`.
+    ///
+    /// If `strategy` fails to generate a value, `Err(TestError::Abort)` is
+    /// returned.
+    pub fn gen_and_run_one<S: Strategy>(
+        &mut self,
+        strategy: &S,
+        test: impl Fn(S::Value) -> TestCaseResult,
+    ) -> Result<bool, TestError<S::Tree>> {
+        let case = unwrap_or!(self.new_tree_timed(strategy), msg =>
+                return Err(TestError::Abort(msg)));
+        let mut result_cache = CaseCache::new(self.new_cache(), |_| None);
+        let result = call_test(
+            self,
+            case.current(),
+            &test,
+            &mut iter::empty::<TestCaseResult>(),
+            &mut result_cache,
+            &mut ForkOutput::empty(),
+            false,
+        );
+
+        match result {
+            Ok(_) => {
+                self.successes += 1;
+                Ok(true)
+            }
+            Err(TestCaseError::Fail(why)) => {
+                Err(TestError::Fail(why, case, None, ShrinkStats::default()))
+            }
+            Err(TestCaseError::Reject(whence)) => {
+                self.reject_global(whence)?;
+                Ok(false)
+            }
+        }
+    }
+
     fn run_one_with_replay<V: ValueTree>(
         &mut self,
         mut case: V,
         test: impl Fn(V::Value) -> TestCaseResult,
         replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
-        result_cache: &mut dyn ResultCache,
+        result_cache: &mut CaseCache<V::Value>,
         fork_output: &mut ForkOutput,
         is_from_persisted_seed: bool,
     ) -> Result<TestCaseOk, TestError<V::Value>> {
@@ -732,17 +1087,16 @@ impl TestRunner {
         match result {
             Ok(success_type) => Ok(success_type),
             Err(TestCaseError::Fail(why)) => {
-                let why = self
-                    .shrink(
-                        &mut case,
-                        test,
-                        replay_from_fork,
-                        result_cache,
-                        fork_output,
-                        is_from_persisted_seed,
-                    )
-                    .unwrap_or(why);
-                Err(TestError::Fail(why, case.current()))
+                let (last_failure, stats) = self.shrink(
+                    &mut case,
+                    test,
+                    replay_from_fork,
+                    result_cache,
+                    fork_output,
+                    is_from_persisted_seed,
+                );
+                let why = last_failure.unwrap_or(why);
+                Err(TestError::Fail(why, case.current(), None, stats))
             }
             Err(TestCaseError::Reject(whence)) => {
                 self.reject_global(whence)?;
@@ -756,15 +1110,24 @@ impl TestRunner {
         case: &mut V,
         test: impl Fn(V::Value) -> TestCaseResult,
         replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
-        result_cache: &mut dyn ResultCache,
+        result_cache: &mut CaseCache<V::Value>,
         fork_output: &mut ForkOutput,
         is_from_persisted_seed: bool,
-    ) -> Option<Reason> {
+    ) -> (Option<Reason>, ShrinkStats) {
         #[cfg(feature = "std")]
         use std::time;
 
+        // Don't even take the first step, which bailing out would only undo
+        // again, leaving the case wherever `complicate()` stops.
+        if 0 == self.config.max_shrink_iters() {
+            return (None, ShrinkStats::default());
+        }
+
         let mut last_failure = None;
         let mut iterations = 0;
+        let regens_before = self.flat_map_regens_used();
+        let mut tested = ShrinkDedup::default();
+        let mut cancelled = false;
         #[cfg(feature = "std")]
         let start_time = time::Instant::now();
 
@@ -828,6 +1191,20 @@ impl TestRunner {
                         current
                     );
                     true
+                } else if self
+                    .config
+                    .cancel
+                    .as_ref()
+                    .map_or(false, CancellationToken::is_cancelled)
+                {
+                    verbose_message!(
+                        self,
+                        ALWAYS,
+                        "Aborting shrinking after {} iterations: cancelled",
+                        iterations
+                    );
+                    cancelled = true;
+                    true
                 } else {
                     false
                 };
@@ -842,15 +1219,37 @@ impl TestRunner {
 
                 iterations += 1;
 
-                let result = call_test(
-                    self,
-                    case.current(),
-                    &test,
-                    replay_from_fork,
-                    result_cache,
-                    fork_output,
-                    is_from_persisted_seed,
-                );
+                let value = case.current();
+                let dedup_key = if self.config.shrink_dedup {
+                    Some(format!("{:?}", value))
+                } else {
+                    None
+                };
+                let deduped =
+                    dedup_key.as_ref().and_then(|key| tested.get(key));
+                let result = if let Some(result) = deduped {
+                    verbose_message!(
+                        self,
+                        TRACE,
+                        "Shrinking candidate already tested, skipping \
+                         execution"
+                    );
+                    result
+                } else {
+                    let result = call_test(
+                        self,
+                        value,
+                        &test,
+                        replay_from_fork,
+                        result_cache,
+                        fork_output,
+                        is_from_persisted_seed,
+                    );
+                    if let Some(key) = dedup_key {
+                        tested.put(key, &result);
+                    }
+                    result
+                };
 
                 match result {
                     // Rejections are effectively a pass here,
@@ -871,7 +1270,13 @@ impl TestRunner {
             }
         }
 
-        last_failure
+        self.shrink_steps += iterations;
+        let stats = ShrinkStats {
+            shrink_iterations: iterations,
+            regenerations: self.flat_map_regens_used() - regens_before,
+            cancelled,
+        };
+        (last_failure, stats)
     }
 
     /// Update the state to account for a local rejection from `whence`, and
@@ -911,6 +1316,34 @@ impl TestRunner {
             .or_insert(1);
     }
 
+    /// Generate a new value tree from `strategy`, failing if that takes longer
+    /// than `Config::max_generation_time`.
+    fn new_tree_timed<S: Strategy>(&mut self, strategy: &S) -> NewTree<S> {
+        #[cfg(feature = "std")]
+        let start_time = std::time::Instant::now();
+        let tree = strategy.new_tree(self)?;
+
+        #[cfg(feature = "std")]
+        {
+            let limit = self.config.max_generation_time;
+            let elapsed = start_time.elapsed();
+            let elapsed_ms = elapsed
+                .as_secs()
+                .saturating_mul(1000)
+                .saturating_add(elapsed.subsec_millis().into());
+            if limit > 0 && elapsed_ms > limit as u64 {
+                return Err(format!(
+                    "Generation timeout of {} ms exceeded: generating the \
+                     input took {} ms (see Config::max_generation_time)",
+                    limit, elapsed_ms
+                )
+                .into());
+            }
+        }
+
+        Ok(tree)
+    }
+
     /// Increment the counter of flat map regenerations and return whether it
     /// is still under the configured limit.
     pub fn flat_map_regen(&self) -> bool {
@@ -918,6 +1351,13 @@ impl TestRunner {
             < self.config.max_flat_map_regens as usize
     }
 
+    /// Return the number of flat map regenerations which were allowed by
+    /// `flat_map_regen()` so far.
+    fn flat_map_regens_used(&self) -> u32 {
+        let regens = self.flat_map_regens.load(SeqCst);
+        regens.min(self.config.max_flat_map_regens as usize) as u32
+    }
+
     fn new_cache(&self) -> Box<dyn ResultCache> {
         (self.config.result_cache)()
     }
@@ -1047,10 +1487,16 @@ fn await_child(
 mod test {
     use std::cell::Cell;
     use std::fs;
+    use std::string::ToString;
 
     use super::*;
     use crate::strategy::Strategy;
-    use crate::test_runner::{FileFailurePersistence, RngAlgorithm, TestRng};
+    #[cfg(feature = "timeout")]
+    use crate::test_runner::TimeoutMode;
+    use crate::test_runner::{
+        FailurePersistence, Feedback, FileFailurePersistence,
+        MapFailurePersistence, RngAlgorithm, TestRng,
+    };
 
     #[test]
     fn gives_up_after_too_many_rejections() {
@@ -1068,6 +1514,310 @@ mod test {
         assert_eq!(config.max_global_rejects + 1, runs.get());
     }
 
+    #[test]
+    fn gen_and_run_one_drives_cases_individually() {
+        let mut runner = TestRunner::new(Config {
+            max_global_rejects: 4,
+            ..Config::default()
+        });
+        let strategy = 0u32..100;
+
+        let mut passed = 0;
+        let mut rejected = 0;
+        let failure = loop {
+            match runner.gen_and_run_one(&strategy, |v| {
+                prop_assume!(v % 10 != 0 || rejected > 0);
+                prop_assert!(v < 90);
+                Ok(())
+            }) {
+                Ok(true) => passed += 1,
+                Ok(false) => rejected += 1,
+                Err(TestError::Fail(why, tree, _, _)) => break (why, tree),
+                Err(TestError::Abort(why)) => panic!("Aborted: {}", why),
+            }
+        };
+
+        assert_eq!(passed, runner.successes);
+        assert_eq!(rejected, runner.global_rejects);
+        assert!(failure.1.current() >= 90);
+
+        // The tree comes back unshrunk, so the caller can shrink it.
+        let result = runner.run_one(failure.1, |v| {
+            prop_assert!(v < 90);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, value, _, _)) => assert_eq!(90, value),
+            e => panic!("Unexpected result: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn stats_count_rejects_of_passing_run() {
+        let filtered = Cell::new(0);
+        let assumed = Cell::new(0);
+        let input = (0..100u32).prop_filter("odd", |&v| {
+            let odd = 1 == v % 2;
+            filtered.set(filtered.get() + u32::from(!odd));
+            odd
+        });
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        runner
+            .run(&input, |v| {
+                if 0 == v % 3 {
+                    assumed.set(assumed.get() + 1);
+                    return Err(TestCaseError::reject("divisible by 3"));
+                }
+                Ok(())
+            })
+            .unwrap();
+
+        let stats = runner.stats();
+        assert_eq!(256, stats.successes);
+        assert!(filtered.get() > 0 && assumed.get() > 0);
+        assert_eq!(filtered.get(), stats.local_rejects);
+        assert_eq!(assumed.get(), stats.global_rejects);
+        assert_eq!(0, stats.shrink_steps);
+    }
+
+    #[test]
+    fn stats_count_shrink_steps_of_failing_run() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let result = runner.run(&(0..1000u32), |v| {
+            prop_assert!(v < 500);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, 500, _, shrink)) => {
+                assert!(shrink.shrink_iterations > 0);
+                assert_eq!(
+                    shrink.shrink_iterations,
+                    runner.stats().shrink_steps
+                );
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn run_corpus_checks_each_input_and_shrinks_failure() {
+        use crate::num::u32::BinarySearch;
+
+        let mut runner = TestRunner::default();
+        let seen = RefCell::new(Vec::new());
+        let result =
+            runner.run_corpus([3u32, 17, 4].iter().map(|&v| Just(v)), |v| {
+                seen.borrow_mut().push(v);
+                Ok(())
+            });
+        assert_eq!(Ok(()), result);
+        assert_eq!(vec![3, 17, 4], *seen.borrow());
+
+        let result = runner.run_corpus(
+            [1u32, 40, 1000, 7].iter().map(|&v| BinarySearch::new(v)),
+            |v| {
+                prop_assert!(v < 50);
+                Ok(())
+            },
+        );
+        match result {
+            Err(TestError::Fail(_, value, _, _)) => assert_eq!(50, value),
+            e => panic!("Unexpected result: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn failure_reports_shrink_stats() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let error = runner
+            .run(&(1_000_000u32..u32::MAX), |v| {
+                prop_assert!(v < 1_000_000);
+                Ok(())
+            })
+            .unwrap_err();
+
+        let message = error.to_string();
+        match error {
+            TestError::Fail(_, value, _, stats) => {
+                assert_eq!(1_000_000, value);
+                assert!(stats.shrink_iterations > 0);
+                assert!(
+                    stats.shrink_iterations
+                        <= Config::default().max_shrink_iters()
+                );
+                assert_eq!(0, stats.regenerations);
+                assert!(message.contains(&format!(
+                    "\tshrink iterations: {}\n",
+                    stats.shrink_iterations
+                )));
+            }
+            e => panic!("Unexpected result: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn failure_reports_flat_map_regenerations() {
+        // Regenerating the inner value occasionally gets shrinking stuck
+        // above the minimum, so use a fixed seed.
+        let mut runner = TestRunner::new_with_rng(
+            Config {
+                failure_persistence: None,
+                ..Config::default()
+            },
+            TestRng::deterministic_rng(RngAlgorithm::ChaCha),
+        );
+        let input = (1..100u32).prop_flat_map(|n| (Just(n), 0..n));
+        let result = runner.run(&input, |(n, _)| {
+            prop_assert!(n < 50);
+            Ok(())
+        });
+
+        match result {
+            Err(TestError::Fail(_, (n, _), _, stats)) => {
+                assert_eq!(50, n);
+                assert!(stats.regenerations > 0);
+            }
+            e => panic!("Unexpected result: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn gen_and_run_one_aborts_after_too_many_rejections() {
+        let mut runner = TestRunner::new(Config {
+            max_global_rejects: 4,
+            ..Config::default()
+        });
+
+        for _ in 0..4 {
+            assert_eq!(
+                Ok(false),
+                runner
+                    .gen_and_run_one(&(0u32..), |_| {
+                        Err(TestCaseError::reject("reject"))
+                    })
+                    .map_err(|_| ())
+            );
+        }
+        match runner.gen_and_run_one(&(0u32..), |_| {
+            Err(TestCaseError::reject("reject"))
+        }) {
+            Err(TestError::Abort(_)) => (),
+            Ok(v) => panic!("Unexpected result: Ok({})", v),
+            Err(TestError::Fail(why, _, _, _)) => {
+                panic!("Unexpected failure: {}", why)
+            }
+        }
+    }
+
+    #[test]
+    fn slow_generation_aborts() {
+        use std::thread;
+        use std::time::Duration;
+
+        #[derive(Debug)]
+        struct Slow;
+
+        impl Strategy for Slow {
+            type Tree = <Just<u32> as Strategy>::Tree;
+            type Value = u32;
+
+            fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+                thread::sleep(Duration::from_millis(50));
+                Just(0).new_tree(runner)
+            }
+        }
+
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            max_generation_time: 10,
+            ..Config::default()
+        });
+        match runner.run(&Slow, |_| Ok(())) {
+            Err(TestError::Abort(why)) => {
+                assert!(
+                    why.message().starts_with("Generation timeout of 10 ms"),
+                    "{}",
+                    why
+                );
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+
+        match runner.gen_and_run_one(&Slow, |_| Ok(())) {
+            Err(TestError::Abort(_)) => (),
+            result => panic!("Unexpected result: {:?}", result.map_err(|_| ())),
+        }
+    }
+
+    #[derive(Clone, Debug, Default)]
+    struct DepthFeedback(Arc<std::sync::Mutex<(usize, [bool; 5])>>);
+
+    impl Feedback for DepthFeedback {
+        fn novelty(&mut self) -> u32 {
+            let (depth, ref mut seen) = *self.0.lock().unwrap();
+            !core::mem::replace(&mut seen[depth], true) as u32
+        }
+
+        fn box_clone(&self) -> Box<dyn Feedback> {
+            Box::new(self.clone())
+        }
+
+        fn eq(&self, other: &dyn Feedback) -> bool {
+            other
+                .as_any()
+                .downcast_ref::<Self>()
+                .map_or(false, |x| Arc::ptr_eq(&x.0, &self.0))
+        }
+
+        fn as_any(&self) -> &dyn core::any::Any {
+            self
+        }
+    }
+
+    #[test]
+    fn feedback_steers_towards_rare_branch() {
+        let feedback = DepthFeedback::default();
+        let mut runner = TestRunner::new_with_rng(
+            Config {
+                cases: 20_000,
+                failure_persistence: None,
+                feedback: Some(Box::new(feedback.clone())),
+                ..Config::default()
+            },
+            TestRng::deterministic_rng(RngAlgorithm::ChaCha),
+        );
+
+        // Pure random generation needs 65536 cases on average to get here.
+        let result = runner.run(
+            &(0u8..16, 0u8..16, 0u8..16, 0u8..16),
+            move |(a, b, c, d)| {
+                let depth = [a == 3, b == 5, c == 9, d == 14]
+                    .iter()
+                    .take_while(|&&hit| hit)
+                    .count();
+                feedback.0.lock().unwrap().0 = depth;
+                prop_assert!(depth < 4);
+                Ok(())
+            },
+        );
+
+        match result {
+            Err(TestError::Fail(_, value, _, _)) => {
+                assert_eq!((3, 5, 9, 14), value)
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
     #[test]
     fn test_pass() {
         let mut runner = TestRunner::default();
@@ -1092,7 +1842,13 @@ mod test {
             }
         });
 
-        assert_eq!(Err(TestError::Fail("not less than 5".into(), 5)), result);
+        match result {
+            Err(TestError::Fail(why, value, Some(_), _)) => {
+                assert_eq!(Reason::from("not less than 5"), why);
+                assert_eq!(5, value);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
     }
 
     #[test]
@@ -1105,7 +1861,53 @@ mod test {
             assert!(v < 5, "not less than 5");
             Ok(())
         });
-        assert_eq!(Err(TestError::Fail("not less than 5".into(), 5)), result);
+        match result {
+            Err(TestError::Fail(why, value, Some(_), _)) => {
+                assert_eq!(Reason::from("not less than 5"), why);
+                assert_eq!(5, value);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn failing_seed_reproduces_shrunk_value() {
+        let strategy = crate::collection::vec(0u32..1000, 0..100);
+        let test = |v: Vec<u32>| {
+            if v.iter().sum::<u32>() > 5000 {
+                Err(TestCaseError::fail("sum too large"))
+            } else {
+                Ok(())
+            }
+        };
+
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let (value, seed) = match runner.run(&strategy, test) {
+            Err(TestError::Fail(_, value, Some(seed), _)) => (value, seed),
+            result => panic!("Unexpected result: {:?}", result),
+        };
+
+        let mut persistence = MapFailurePersistence::default();
+        persistence.save_persisted_failure2(
+            Some("failing_seed"),
+            seed.clone(),
+            &value,
+        );
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: Some(Box::new(persistence)),
+            source_file: Some("failing_seed"),
+            ..Config::default()
+        });
+        match runner.run(&strategy, test) {
+            Err(TestError::Fail(_, replayed, replayed_seed, _)) => {
+                assert_eq!(value, replayed);
+                assert_eq!(Some(seed), replayed_seed);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
     }
 
     #[test]
@@ -1286,7 +2088,7 @@ mod test {
             .unwrap();
 
         match failure {
-            TestError::Fail(_, value) => assert_eq!(500, value),
+            TestError::Fail(_, value, _, _) => assert_eq!(500, value),
             failure => panic!("Unexpected failure: {:?}", failure),
         }
     }
@@ -1314,7 +2116,7 @@ mod test {
             .unwrap();
 
         match failure {
-            TestError::Fail(_, value) => assert_eq!(500, value),
+            TestError::Fail(_, value, _, _) => assert_eq!(500, value),
             failure => panic!("Unexpected failure: {:?}", failure),
         }
     }
@@ -1342,7 +2144,7 @@ mod test {
             .unwrap();
 
         match failure {
-            TestError::Fail(_, value) => assert_eq!(500, value),
+            TestError::Fail(_, value, _, _) => assert_eq!(500, value),
             failure => panic!("Unexpected failure: {:?}", failure),
         }
     }
@@ -1373,7 +2175,7 @@ mod test {
             .unwrap();
 
         match failure {
-            TestError::Fail(_, value) => assert_eq!(500, value),
+            TestError::Fail(_, value, _, _) => assert_eq!(500, value),
             failure => panic!("Unexpected failure: {:?}", failure),
         }
     }
@@ -1414,11 +2216,242 @@ mod test {
             .unwrap();
 
         match failure {
-            TestError::Fail(_, value) => assert_eq!(500, value),
+            TestError::Fail(_, value, _, _) => assert_eq!(500, value),
             failure => panic!("Unexpected failure: {:?}", failure),
         }
     }
 
+    #[cfg(feature = "timeout")]
+    #[test]
+    fn elapsed_timeout_fails_slow_case_without_forking() {
+        let config = Config {
+            timeout: 100,
+            timeout_mode: TimeoutMode::Elapsed,
+            failure_persistence: None,
+            ..Config::default()
+        };
+        assert!(!config.fork());
+
+        let pid = std::process::id();
+        let mut runner = TestRunner::new(config);
+        let failure = runner
+            .run(&(0u32..1000), |v| {
+                assert_eq!(pid, std::process::id());
+                if v >= 500 {
+                    ::std::thread::sleep(::std::time::Duration::from_millis(
+                        150,
+                    ));
+                }
+                Ok(())
+            })
+            .err()
+            .unwrap();
+
+        match failure {
+            TestError::Fail(why, value, _, _) => {
+                assert_eq!(500, value);
+                assert!(why.message().contains("Timeout"), "{}", why);
+            }
+            failure => panic!("Unexpected failure: {:?}", failure),
+        }
+    }
+
+    #[test]
+    fn explore_neighbors_tests_values_near_persisted_case() {
+        let strategy = 0u32..1000;
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let (value, seed) = match runner.run(&strategy, |v| {
+            prop_assert!(v < 500);
+            Ok(())
+        }) {
+            Err(TestError::Fail(_, value, Some(seed), _)) => (value, seed),
+            result => panic!("Unexpected result: {:?}", result),
+        };
+
+        let seen = |explore_neighbors| {
+            let mut persistence = MapFailurePersistence::default();
+            persistence.save_persisted_failure2(
+                Some("neighbors"),
+                seed.clone(),
+                &value,
+            );
+            let mut runner = TestRunner::new(Config {
+                failure_persistence: Some(Box::new(persistence)),
+                source_file: Some("neighbors"),
+                explore_neighbors,
+                cases: 1,
+                ..Config::default()
+            });
+            let seen = std::cell::RefCell::new(Vec::new());
+            runner
+                .run(&strategy, |v| {
+                    seen.borrow_mut().push(v);
+                    Ok(())
+                })
+                .unwrap();
+            seen.into_inner()
+        };
+
+        assert_eq!(2, seen(false).len());
+        let seen = seen(true);
+        let persisted = seen[0];
+        assert!(persisted >= 500);
+        let neighbors = &seen[1..seen.len() - 1];
+        assert!(neighbors.len() > 2, "{:?}", seen);
+        assert!(neighbors.iter().all(|&v| v <= persisted), "{:?}", seen);
+        assert!(neighbors.iter().any(|&v| v < persisted), "{:?}", seen);
+    }
+
+    #[test]
+    fn shrink_dedup_skips_repeated_candidates() {
+        let calls = |shrink_dedup| {
+            let mut runner = TestRunner::new_with_rng(
+                Config {
+                    failure_persistence: None,
+                    shrink_dedup,
+                    ..Config::default()
+                },
+                TestRng::deterministic_rng(RngAlgorithm::ChaCha),
+            );
+            let calls = Cell::new(0);
+            let result =
+                runner.run(&(0u32..1_000_000).prop_map(|v| v / 1000), |v| {
+                    calls.set(calls.get() + 1);
+                    prop_assert!(v < 5);
+                    Ok(())
+                });
+            match result {
+                Err(TestError::Fail(_, value, _, _)) => assert_eq!(5, value),
+                e => panic!("Unexpected result: {:?}", e),
+            }
+            calls.get()
+        };
+
+        let without = calls(false);
+        let with = calls(true);
+        assert!(
+            with < without,
+            "{} calls with dedup, {} without",
+            with,
+            without
+        );
+    }
+
+    #[test]
+    fn cancel_stops_shrinking_with_a_failing_value() {
+        let cancel = CancellationToken::new();
+        let mut runner = TestRunner::new_with_rng(
+            Config {
+                failure_persistence: None,
+                cancel: Some(cancel.clone()),
+                ..Config::default()
+            },
+            TestRng::deterministic_rng(RngAlgorithm::ChaCha),
+        );
+        let calls = Cell::new(0);
+        let result = runner.run(&(0..u64::MAX), |v| {
+            calls.set(calls.get() + 1);
+            if 5 == calls.get() {
+                cancel.cancel();
+            }
+            prop_assert!(v < 1000);
+            Ok(())
+        });
+
+        assert_eq!(5, calls.get());
+        match result {
+            Err(TestError::Fail(_, value, _, stats)) => {
+                assert!(value >= 1000, "{} passes the test", value);
+                assert!(stats.cancelled);
+                assert_eq!(4, stats.shrink_iterations);
+            }
+            e => panic!("Unexpected result: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn smoke_runs_one_case_without_shrinking() {
+        // Pin the fields which environment variables can override.
+        let config = Config {
+            cases: 1,
+            max_shrink_iters: 0,
+            ..Config::smoke()
+        };
+        let calls = Cell::new(0);
+        let mut runner = TestRunner::new(config.clone());
+        assert_eq!(
+            Ok(()),
+            runner.run(&(0..1000u32), |_| {
+                calls.set(calls.get() + 1);
+                Ok(())
+            })
+        );
+        assert_eq!(1, calls.get());
+
+        let seen = Cell::new(None);
+        let mut runner = TestRunner::new(config);
+        let result = runner.run(&(500..1000u32), |v| {
+            seen.set(Some(v));
+            prop_assert!(v < 500);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, value, _, stats)) => {
+                assert_eq!(seen.get(), Some(value));
+                assert_eq!(0, stats.shrink_iterations);
+            }
+            e => panic!("Unexpected result: {:?}", e),
+        }
+        assert_eq!(0, runner.stats().shrink_steps);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_result_cache_strategy_skips_debug_formatting() {
+        static DEBUG_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Clone, PartialEq, Eq, Hash)]
+        struct Big(Vec<u32>);
+
+        impl fmt::Debug for Big {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                DEBUG_CALLS.fetch_add(1, SeqCst);
+                f.debug_tuple("Big").field(&self.0).finish()
+            }
+        }
+
+        let run = |result_cache_strategy| {
+            let mut runner = TestRunner::new_with_rng(
+                Config {
+                    cases: 8,
+                    failure_persistence: None,
+                    result_cache:
+                        crate::test_runner::result_cache::basic_result_cache,
+                    result_cache_strategy,
+                    ..Config::default()
+                },
+                TestRng::deterministic_rng(RngAlgorithm::ChaCha),
+            );
+            let calls = Cell::new(0);
+            let strategy = (0..8u32).prop_map(|v| Big(vec![v; 10_000]));
+            let result = runner.run_hashed(&strategy, |_| {
+                calls.set(calls.get() + 1);
+                Ok(())
+            });
+            assert_eq!(Ok(()), result);
+            calls.get()
+        };
+
+        // Every distinct value is run exactly once either way.
+        assert_eq!(8, run(ResultCacheStrategy::Hash));
+        assert_eq!(0, DEBUG_CALLS.load(SeqCst));
+        assert_eq!(8, run(ResultCacheStrategy::Debug));
+        assert!(DEBUG_CALLS.load(SeqCst) > 8);
+    }
+
     #[cfg(feature = "std")]
     #[test]
     fn duplicate_tests_not_run_with_basic_result_cache() {
@@ -1447,7 +2480,7 @@ mod test {
                 });
 
             assert!(pass.get());
-            if let Err(TestError::Fail(_, val)) = result {
+            if let Err(TestError::Fail(_, val, _, _)) = result {
                 assert_eq!(6, val);
             } else {
                 panic!("Incorrect result: {:?}", result);
@@ -1524,7 +2557,7 @@ mod timeout_tests {
             Ok(())
         });
 
-        if let Err(TestError::Fail(_, value)) = result {
+        if let Err(TestError::Fail(_, value, _, _)) = result {
             // Ensure the final value was in fact a failing case.
             assert!(value > u32::MAX as u64);
         } else {
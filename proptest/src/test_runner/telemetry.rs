@@ -0,0 +1,45 @@
+//-
+// Copyright 2024 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{fmt, Box};
+use core::any::Any;
+
+/// Receives diagnostic messages produced while generating and shrinking
+/// values, such as those emitted by `Strategy::prop_debug_tree()`.
+///
+/// Implementations must be cheap to call, since messages may be produced for
+/// every step of shrinking. A typical implementation appends them to a shared
+/// buffer or forwards them to a logging framework.
+///
+/// See `Config::telemetry`.
+pub trait Telemetry: Send + Sync + fmt::Debug {
+    /// Records a single diagnostic message.
+    fn record(&self, message: &str);
+
+    /// Delegate method for producing a trait object usable with `Clone`
+    fn box_clone(&self) -> Box<dyn Telemetry>;
+
+    /// Equality testing delegate required due to constraints of trait objects.
+    fn eq(&self, other: &dyn Telemetry) -> bool;
+
+    /// Assistant method for trait object comparison.
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<'a, 'b> PartialEq<dyn Telemetry + 'b> for dyn Telemetry + 'a {
+    fn eq(&self, other: &(dyn Telemetry + 'b)) -> bool {
+        Telemetry::eq(self, other)
+    }
+}
+
+impl Clone for Box<dyn Telemetry> {
+    fn clone(&self) -> Box<dyn Telemetry> {
+        self.box_clone()
+    }
+}
@@ -11,6 +11,10 @@
 //!
 //! There is no explicit "tuple strategy"; simply make a tuple containing the
 //! strategy and that tuple is itself a strategy.
+//!
+//! Tuples of up to 12 strategies are supported, since that is the largest
+//! arity for which the standard library implements `Debug` (required of every
+//! `Strategy::Value`). Larger combinations can be built by nesting tuples.
 
 use crate::strategy::*;
 use crate::test_runner::*;